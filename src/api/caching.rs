@@ -0,0 +1,103 @@
+use std::hash::{DefaultHasher, Hasher};
+
+use axum::{
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// Catalog responses may be reused for a minute, then must be revalidated with `If-None-Match`.
+/// Only by the client: they follow the user's stored language preference, which shared
+/// caches can't see.
+const CATALOG_CACHE_CONTROL: &str = "private, max-age=60";
+
+/// Serializes `value` as JSON with a weak `ETag`, answering `304 Not Modified` with an
+/// empty body when the request's `If-None-Match` already names the current representation.
+///
+/// The tag is weak because the compression layer sends the same tag for gzip, brotli and
+/// identity bodies, and a strong validator must differ per encoding (RFC 9110 §8.8.3).
+pub fn cached_json<T: Serialize>(request_headers: &HeaderMap, value: &T) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let etag = weak_etag(&body);
+
+    let mut response = if if_none_match_matches(request_headers, &etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        (
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            )],
+            body,
+        )
+            .into_response()
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(
+        header::ETAG,
+        HeaderValue::from_str(&etag).expect("ETag is hex"),
+    );
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(CATALOG_CACHE_CONTROL),
+    );
+    response
+}
+
+fn weak_etag(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(body);
+    format!("W/\"{:016x}{:08x}\"", hasher.finish(), body.len())
+}
+
+/// `If-None-Match` uses weak comparison (RFC 9110 §13.1.2), so `W/` prefixes are ignored.
+fn if_none_match_matches(request_headers: &HeaderMap, etag: &str) -> bool {
+    request_headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| {
+            candidate == "*"
+                || candidate.trim_start_matches("W/") == etag.trim_start_matches("W/")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+
+    use super::cached_json;
+
+    #[test]
+    fn test_etag_is_stable_and_304_on_match() {
+        let first = cached_json(&HeaderMap::new(), &vec!["a", "b"]);
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers()[header::ETAG].clone();
+        let tag = etag.to_str().unwrap();
+        assert!(tag.starts_with("W/\""));
+        assert_eq!(
+            first.headers()[header::CACHE_CONTROL],
+            "private, max-age=60"
+        );
+
+        // Clients may echo the tag with or without its `W/` prefix.
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"other\", {}", &tag[2..])).unwrap(),
+        );
+        let second = cached_json(&headers, &vec!["a", "b"]);
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(second.headers()[header::ETAG], etag);
+
+        let changed = cached_json(&headers, &vec!["a", "c"]);
+        assert_eq!(changed.status(), StatusCode::OK);
+        assert_ne!(changed.headers()[header::ETAG], etag);
+    }
+}
@@ -2,32 +2,117 @@ use std::sync::Arc;
 
 // src/models/cart.rs
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CartItem {
     pub product_id: String,
+    /// SKU of the chosen variant; absent for products sold without variants.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_sku: Option<String>,
     pub quantity: u32,
+    /// Unit price when the item was added, to warn the buyer if it changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_unit_price: Option<f64>,
 }
 
+impl CartItem {
+    /// Whether both items refer to the same cart line (same product and variant).
+    pub fn is_same_line(&self, other: &CartItem) -> bool {
+        self.product_id == other.product_id && self.variant_sku == other.variant_sku
+    }
+}
+
+use std::collections::HashMap;
+
 use axum::{
     Router,
-    extract::{Extension, Json},
+    extract::{Extension, FromRequestParts, Json},
+    http::{HeaderMap, HeaderValue, StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
     routing::{delete, get, post, put},
 };
+use chrono::Utc;
+use uuid::Uuid;
 
 use crate::{
-    services::cart_services::{CartError, CartService},
+    api::{
+        model::{ProductError, ProductService},
+        openapi::ErrorMessage,
+    },
+    models::{
+        cart_metrics::CartMetrics,
+        cart_summary::CartSummary,
+        cart_sync::{
+            CartOperation, CartOperationKind, CartSyncRequest, CartSyncResponse, OperationOutcome,
+            OperationStatus,
+        },
+        locale::{Locale, Localize},
+    },
+    services::{
+        cart_pricing::price_cart,
+        cart_services::{CartError, CartService, GUEST_CART_PREFIX, OfflineOperation},
+        promotion_service::{PromotionError, PromotionService},
+    },
     state::AppState,
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CartRequest {
     pub product_id: String,
+    /// Required when the product has variants.
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+    /// 1 to 99 units; defaults to 1 when adding. Updating to 0 removes the item.
     pub quantity: Option<u32>,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CouponRequest {
+    /// Case doesn't matter.
+    pub code: String,
+}
+
+/// Header naming a guest cart, holding a token from POST `/api/cart/guest`.
+pub const CART_TOKEN_HEADER: &str = "x-cart-token";
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GuestCart {
+    /// Send it in the `X-Cart-Token` header of cart requests until the visitor signs in.
+    pub cart_token: String,
+}
+
+/// The signed-in user, until authentication lands.
+const SIGNED_IN_USER: &str = "user123";
+
+/// Key of the cart a request works on: the guest cart named by `X-Cart-Token`, else the
+/// signed-in user's cart.
+pub struct CartOwner(pub String);
+
+impl CartOwner {
+    fn guest(token: &str) -> Result<Self, CartError> {
+        let token = Uuid::parse_str(token).map_err(|_| CartError::InvalidCartToken)?;
+        Ok(CartOwner(format!("{GUEST_CART_PREFIX}{token}")))
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CartOwner {
+    type Rejection = CartError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match parts.headers.get(CART_TOKEN_HEADER) {
+            Some(token) => {
+                CartOwner::guest(token.to_str().map_err(|_| CartError::InvalidCartToken)?)
+            }
+            None => Ok(CartOwner(SIGNED_IN_USER.to_string())),
+        }
+    }
+}
+
 pub fn cart_routes(appstate: Arc<AppState>) -> Router {
     let cart_service = appstate.cart_service.clone();
+    let product_service = appstate.product_service.clone();
+    let promotion_service = appstate.promotion_service.clone();
     Router::new()
         .nest(
             "/api/cart",
@@ -35,73 +120,532 @@ pub fn cart_routes(appstate: Arc<AppState>) -> Router {
                 .route("/", get(get_cart))
                 .route("/add", post(add_to_cart))
                 .route("/update", put(update_cart))
-                .route("/remove", delete(remove_from_cart)),
+                .route("/remove", delete(remove_from_cart))
+                .route("/guest", post(create_guest_cart))
+                .route("/merge", post(merge_guest_cart))
+                .route("/sync", post(sync_cart))
+                .route("/coupon", post(apply_coupon).delete(remove_coupon)),
         )
+        .route("/api/admin/carts/metrics", get(cart_metrics))
         .layer(Extension(cart_service))
+        .layer(Extension(product_service))
+        .layer(Extension(promotion_service))
 }
 
 /// Handler to add an item to the user's shopping cart.
 ///
 /// POST `/api/cart/add`
+#[utoipa::path(
+    post,
+    path = "/api/cart/add",
+    tag = "cart",
+    request_body = CartRequest,
+    responses(
+        (status = 200, description = "Item added; `ETag` holds the cart's new version", body = String),
+        (status = 400, description = "Missing or unknown variant, quantity outside 1..=99 or cart limits exceeded", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Product not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 500, description = "Cart storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
 async fn add_to_cart(
     Extension(cart_service): Extension<CartService>,
+    Extension(product_service): Extension<ProductService>,
+    CartOwner(cart_key): CartOwner,
     Json(payload): Json<CartRequest>,
-) -> Result<Json<&'static str>, CartError> {
-    let user_id = "user123".to_string();
+) -> Result<Response, CartError> {
+    let product = product_service.get_product_by_id(&payload.product_id).await?;
+    let unit_price = product.unit_price(payload.variant_sku.as_deref())?;
+
     let item = CartItem {
         product_id: payload.product_id,
+        variant_sku: payload.variant_sku,
         quantity: payload.quantity.unwrap_or(1),
+        added_unit_price: Some(unit_price),
     };
 
-    cart_service.add_item(user_id, item)?;
-    Ok(Json("Item added to cart"))
+    let version = cart_service.add_item(cart_key, item, None)?;
+    Ok(([(header::ETAG, cart_etag(version))], Json("Item added to cart")).into_response())
 }
 
-/// Handler to update the quantity of an item in the user's shopping cart.
+/// Handler to set the quantity of an item in the user's shopping cart; 0 removes it.
+/// `If-Match` must carry the cart's `ETag` so that a write based on an outdated cart
+/// (from another device, or a replayed request) is refused.
 ///
 /// PUT `/api/cart/update`
+#[utoipa::path(
+    put,
+    path = "/api/cart/update",
+    tag = "cart",
+    request_body = CartRequest,
+    params(("If-Match" = String, Header, description = "`ETag` of the cart the change is based on, or `*`")),
+    responses(
+        (status = 200, description = "Item updated; `ETag` holds the cart's new version", body = String),
+        (status = 400, description = "Missing quantity, quantity above 99 or cart limits exceeded", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Product not found or not in the cart", body = ErrorMessage, content_type = "text/plain"),
+        (status = 412, description = "The cart changed since; the current cart, with its `ETag`", body = CartSummary),
+        (status = 428, description = "Missing If-Match", body = ErrorMessage, content_type = "text/plain"),
+        (status = 500, description = "Cart storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
 async fn update_cart(
     Extension(cart_service): Extension<CartService>,
+    Extension(product_service): Extension<ProductService>,
+    Extension(promotion_service): Extension<PromotionService>,
+    locale: Locale,
+    CartOwner(cart_key): CartOwner,
+    headers: HeaderMap,
     Json(payload): Json<CartRequest>,
-) -> Result<Json<&'static str>, CartError> {
-    let user_id = "user123".to_string();
-    if let Some(quantity) = payload.quantity {
-        let item = CartItem {
-            product_id: payload.product_id,
-            quantity,
-        };
-        cart_service.update_item(user_id, item)?;
+) -> Result<Response, CartError> {
+    let expected_version = expected_version(&headers)?;
+    let quantity = payload.quantity.ok_or(CartError::InvalidQuantity)?;
+    // Withdrawn products can still be removed.
+    if quantity > 0 {
+        let product = product_service.get_product_by_id(&payload.product_id).await?;
+        product.resolve_variant(payload.variant_sku.as_deref())?;
     }
-    Ok(Json("Item updated in cart"))
+    let item = CartItem {
+        product_id: payload.product_id,
+        variant_sku: payload.variant_sku,
+        quantity,
+        added_unit_price: None,
+    };
+    let written = cart_service.update_item(cart_key.clone(), item, expected_version);
+    let message = if quantity == 0 {
+        "Item removed from cart"
+    } else {
+        "Item updated in cart"
+    };
+    write_response(
+        written,
+        message,
+        &cart_service,
+        &product_service,
+        &promotion_service,
+        locale,
+        &cart_key,
+    )
+    .await
 }
 
-/// Handler to remove an item from the user's shopping cart.
+/// Handler to remove an item from the user's shopping cart. Requires `If-Match` like
+/// `update`.
 ///
 /// DELETE `/api/cart/remove`
+#[utoipa::path(
+    delete,
+    path = "/api/cart/remove",
+    tag = "cart",
+    request_body = CartRequest,
+    params(("If-Match" = String, Header, description = "`ETag` of the cart the change is based on, or `*`")),
+    responses(
+        (status = 200, description = "Item removed; `ETag` holds the cart's new version", body = String),
+        (status = 404, description = "Item not in the cart", body = ErrorMessage, content_type = "text/plain"),
+        (status = 412, description = "The cart changed since; the current cart, with its `ETag`", body = CartSummary),
+        (status = 428, description = "Missing If-Match", body = ErrorMessage, content_type = "text/plain"),
+        (status = 500, description = "Cart storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
 async fn remove_from_cart(
     Extension(cart_service): Extension<CartService>,
+    Extension(product_service): Extension<ProductService>,
+    Extension(promotion_service): Extension<PromotionService>,
+    locale: Locale,
+    CartOwner(cart_key): CartOwner,
+    headers: HeaderMap,
     Json(payload): Json<CartRequest>,
-) -> Result<Json<&'static str>, CartError> {
-    let user_id = "user123".to_string();
-    cart_service.remove_item(user_id, payload.product_id)?;
-    Ok(Json("Item removed from cart"))
+) -> Result<Response, CartError> {
+    let expected_version = expected_version(&headers)?;
+    let written = cart_service.remove_item(
+        cart_key.clone(),
+        payload.product_id,
+        payload.variant_sku,
+        expected_version,
+    );
+    write_response(
+        written,
+        "Item removed from cart",
+        &cart_service,
+        &product_service,
+        &promotion_service,
+        locale,
+        &cart_key,
+    )
+    .await
 }
 
-/// Handler to retrieve the current contents of the user's shopping cart.
+pub(crate) fn cart_etag(version: u64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{version}\"")).expect("ETag is a number")
+}
+
+/// The cart version named by `If-Match`, `None` for `*`. Comparison is strong (RFC 9110
+/// §13.1.1), so weak or unrecognized tags never match.
+pub(crate) fn expected_version(headers: &HeaderMap) -> Result<Option<u64>, CartError> {
+    let value = headers
+        .get(header::IF_MATCH)
+        .ok_or(CartError::PreconditionRequired)?
+        .to_str()
+        .map_err(|_| CartError::VersionMismatch)?
+        .trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    value
+        .strip_prefix('"')
+        .and_then(|tag| tag.strip_suffix('"'))
+        .and_then(|tag| tag.parse().ok())
+        .map(Some)
+        .ok_or(CartError::VersionMismatch)
+}
+
+/// Answers a conditional cart write with the cart's new `ETag`, or, when the cart changed
+/// since the client read it, with `412` and the current cart to reconcile against.
+async fn write_response(
+    written: Result<u64, CartError>,
+    message: &'static str,
+    cart_service: &CartService,
+    product_service: &ProductService,
+    promotion_service: &PromotionService,
+    locale: Locale,
+    cart_key: &str,
+) -> Result<Response, CartError> {
+    match written {
+        Ok(version) => Ok(([(header::ETAG, cart_etag(version))], Json(message)).into_response()),
+        Err(CartError::VersionMismatch) => {
+            stale_cart_response(
+                cart_service,
+                product_service,
+                promotion_service,
+                locale,
+                cart_key,
+            )
+            .await
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// `412` with the current cart and its `ETag`, for a write based on an outdated cart.
+pub(crate) async fn stale_cart_response(
+    cart_service: &CartService,
+    product_service: &ProductService,
+    promotion_service: &PromotionService,
+    locale: Locale,
+    cart_key: &str,
+) -> Result<Response, CartError> {
+    let (summary, version) =
+        priced_cart(cart_service, product_service, promotion_service, locale, cart_key).await?;
+    let etag = [(header::ETAG, cart_etag(version))];
+    Ok((StatusCode::PRECONDITION_FAILED, etag, Json(summary)).into_response())
+}
+
+/// Handler to retrieve the user's shopping cart priced at current catalog prices, with
+/// product names in the negotiated language, totals and a delivery estimate. Items that
+/// are out of stock, withdrawn or repriced since they were added come with warnings.
+/// The `ETag` header carries the cart's version, for `If-Match` on later writes.
 ///
 /// GET `/api/cart`
+#[utoipa::path(
+    get,
+    path = "/api/cart",
+    tag = "cart",
+    responses(
+        (status = 200, description = "Current cart with prices and totals; `ETag` holds its version", body = CartSummary),
+        (status = 500, description = "Storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
 async fn get_cart(
     Extension(cart_service): Extension<CartService>,
+    Extension(product_service): Extension<ProductService>,
+    Extension(promotion_service): Extension<PromotionService>,
+    locale: Locale,
+    CartOwner(cart_key): CartOwner,
+) -> Result<Response, CartError> {
+    let (summary, version) =
+        priced_cart(&cart_service, &product_service, &promotion_service, locale, &cart_key)
+            .await?;
+    Ok(([(header::ETAG, cart_etag(version))], Json(summary)).into_response())
+}
+
+/// Prices the cart under `cart_key` with the promotions it gets, returning it with its
+/// version. Checkout charges what this computes, so the summary and the order agree;
+/// per-user limits are therefore checked against the signed-in user's orders, also for
+/// guest carts.
+pub async fn priced_cart(
+    cart_service: &CartService,
+    product_service: &ProductService,
+    promotion_service: &PromotionService,
+    locale: Locale,
+    cart_key: &str,
+) -> Result<(CartSummary, u64), CartError> {
+    let snapshot = cart_service.snapshot(cart_key)?;
+    let promotions =
+        promotion_service.applicable(SIGNED_IN_USER, snapshot.coupon.as_deref(), Utc::now())?;
+    let mut products = HashMap::new();
+    for item in &snapshot.items {
+        match product_service.get_product_by_id(&item.product_id).await {
+            Ok(mut product) => {
+                product_service.localize(&mut product, locale);
+                products.insert(product.id.clone(), product);
+            }
+            Err(ProductError::ProductNotFound) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    let mut summary = price_cart(snapshot.items, &products, &promotions);
+    summary.updated_at = snapshot.updated_at;
+    summary.coupon_code = snapshot.coupon;
+    Ok((summary, snapshot.version))
+}
+
+/// Replays cart changes a client queued while offline, in one go. The changes are applied
+/// together, oldest first by `made_at` (the order sent breaks ties), and nothing else
+/// writes to the cart meanwhile. Conflicts are settled per line by last change wins: a
+/// change made before the line last changed on the server, e.g. from another device, is
+/// superseded. Changes the cart refuses are rejected individually; only the others are
+/// stored, and the response lists the ones left out.
+///
+/// POST `/api/cart/sync`
+#[utoipa::path(
+    post,
+    path = "/api/cart/sync",
+    tag = "cart",
+    request_body = CartSyncRequest,
+    responses(
+        (status = 200, description = "The resulting cart, with its `ETag`, and one outcome per operation", body = CartSyncResponse),
+        (status = 500, description = "Storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn sync_cart(
+    Extension(cart_service): Extension<CartService>,
+    Extension(product_service): Extension<ProductService>,
+    Extension(promotion_service): Extension<PromotionService>,
+    locale: Locale,
+    CartOwner(cart_key): CartOwner,
+    Json(payload): Json<CartSyncRequest>,
+) -> Result<Response, CartError> {
+    let mut operations = Vec::with_capacity(payload.operations.len());
+    for operation in &payload.operations {
+        match offline_operation(&product_service, operation).await {
+            Err(CartError::LockError) => return Err(CartError::LockError),
+            prepared => operations.push(prepared),
+        }
+    }
+    let results = cart_service.apply_offline(&cart_key, operations, Utc::now())?;
+    let outcomes: Vec<_> = payload
+        .operations
+        .into_iter()
+        .zip(results)
+        .map(|(operation, result)| {
+            let (status, reason) = match result {
+                Ok(status) => (status, None),
+                Err(err) => (OperationStatus::Rejected, Some(err.localized(locale))),
+            };
+            OperationOutcome {
+                op_id: operation.op_id,
+                status,
+                reason,
+            }
+        })
+        .collect();
+    let dropped = outcomes
+        .iter()
+        .filter(|outcome| outcome.status != OperationStatus::Applied)
+        .map(|outcome| outcome.op_id.clone())
+        .collect();
+
+    let (cart, version) =
+        priced_cart(&cart_service, &product_service, &promotion_service, locale, &cart_key)
+            .await?;
+    let etag = [(header::ETAG, cart_etag(version))];
+    let synced = CartSyncResponse {
+        cart,
+        outcomes,
+        dropped,
+    };
+    Ok((etag, Json(synced)).into_response())
+}
+
+/// Checks an offline operation against the catalog like the matching cart endpoint.
+async fn offline_operation(
+    product_service: &ProductService,
+    operation: &CartOperation,
+) -> Result<OfflineOperation, CartError> {
+    let variant_sku = operation.variant_sku.clone();
+    let (quantity, added_unit_price) = match operation.kind {
+        CartOperationKind::Add => {
+            let product = product_service.get_product_by_id(&operation.product_id).await?;
+            let unit_price = product.unit_price(variant_sku.as_deref())?;
+            (operation.quantity.unwrap_or(1), Some(unit_price))
+        }
+        CartOperationKind::Update => {
+            let quantity = operation.quantity.ok_or(CartError::InvalidQuantity)?;
+            // Withdrawn products can still be removed.
+            if quantity > 0 {
+                let product = product_service.get_product_by_id(&operation.product_id).await?;
+                product.resolve_variant(variant_sku.as_deref())?;
+            }
+            (quantity, None)
+        }
+        CartOperationKind::Remove => (0, None),
+    };
+    Ok(OfflineOperation {
+        kind: operation.kind,
+        item: CartItem {
+            product_id: operation.product_id.clone(),
+            variant_sku,
+            quantity,
+            added_unit_price,
+        },
+        made_at: operation.made_at,
+    })
+}
+
+/// Applies a coupon code to the cart, replacing the one applied before. The discount
+/// shows in the cart summary while the cart meets the promotion's conditions.
+///
+/// POST `/api/cart/coupon`
+#[utoipa::path(
+    post,
+    path = "/api/cart/coupon",
+    tag = "cart",
+    request_body = CouponRequest,
+    responses(
+        (status = 200, description = "The cart with the coupon, with its `ETag`", body = CartSummary),
+        (status = 400, description = "Coupon not valid at the moment", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Unknown coupon code", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Coupon already used as many times as allowed", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn apply_coupon(
+    Extension(cart_service): Extension<CartService>,
+    Extension(product_service): Extension<ProductService>,
+    Extension(promotion_service): Extension<PromotionService>,
+    locale: Locale,
+    CartOwner(cart_key): CartOwner,
+    Json(payload): Json<CouponRequest>,
+) -> Result<Response, PromotionError> {
+    // Per-user limits count the signed-in user's orders, as at checkout, whichever cart
+    // the coupon goes on.
+    let promotion = promotion_service.coupon(&payload.code, SIGNED_IN_USER, Utc::now())?;
+    cart_service.set_coupon(&cart_key, promotion.code)?;
+    let (summary, version) =
+        priced_cart(&cart_service, &product_service, &promotion_service, locale, &cart_key)
+            .await?;
+    Ok(([(header::ETAG, cart_etag(version))], Json(summary)).into_response())
+}
+
+/// Takes the coupon off the cart.
+///
+/// DELETE `/api/cart/coupon`
+#[utoipa::path(
+    delete,
+    path = "/api/cart/coupon",
+    tag = "cart",
+    responses(
+        (status = 200, description = "The cart without a coupon, with its `ETag`", body = CartSummary),
+        (status = 500, description = "Storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn remove_coupon(
+    Extension(cart_service): Extension<CartService>,
+    Extension(product_service): Extension<ProductService>,
+    Extension(promotion_service): Extension<PromotionService>,
+    locale: Locale,
+    CartOwner(cart_key): CartOwner,
+) -> Result<Response, CartError> {
+    cart_service.set_coupon(&cart_key, None)?;
+    let (summary, version) =
+        priced_cart(&cart_service, &product_service, &promotion_service, locale, &cart_key)
+            .await?;
+    Ok(([(header::ETAG, cart_etag(version))], Json(summary)).into_response())
+}
+
+/// Cart expiry and abandoned-cart recovery figures, for admins.
+///
+/// GET `/api/admin/carts/metrics`
+#[utoipa::path(
+    get,
+    path = "/api/admin/carts/metrics",
+    tag = "cart",
+    responses(
+        (status = 200, description = "Cart counters since the server started", body = CartMetrics),
+        (status = 500, description = "Cart storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn cart_metrics(
+    Extension(cart_service): Extension<CartService>,
+) -> Result<Json<CartMetrics>, CartError> {
+    Ok(Json(cart_service.metrics()?))
+}
+
+/// Starts an anonymous cart for a visitor who hasn't signed in. Cart requests carrying
+/// the token in `X-Cart-Token` work on that cart.
+///
+/// POST `/api/cart/guest`
+#[utoipa::path(
+    post,
+    path = "/api/cart/guest",
+    tag = "cart",
+    responses((status = 201, description = "Token of the new guest cart", body = GuestCart))
+)]
+async fn create_guest_cart() -> (StatusCode, Json<GuestCart>) {
+    let cart_token = Uuid::new_v4().to_string();
+    (StatusCode::CREATED, Json(GuestCart { cart_token }))
+}
+
+/// Moves the guest cart named by `X-Cart-Token` into the signed-in user's cart, to be
+/// called right after sign-in. Quantities of products in both carts are summed, capped
+/// at the stock available; products no longer sold are dropped. The guest cart is
+/// deleted, so the token can be forgotten.
+///
+/// POST `/api/cart/merge`
+#[utoipa::path(
+    post,
+    path = "/api/cart/merge",
+    tag = "cart",
+    params(("X-Cart-Token" = String, Header, description = "Guest cart token")),
+    responses(
+        (status = 200, description = "The user's cart after the merge", body = Vec<CartItem>),
+        (status = 400, description = "Missing or malformed guest cart token", body = ErrorMessage, content_type = "text/plain"),
+        (status = 500, description = "Cart storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn merge_guest_cart(
+    Extension(cart_service): Extension<CartService>,
+    Extension(product_service): Extension<ProductService>,
+    headers: HeaderMap,
 ) -> Result<Json<Vec<CartItem>>, CartError> {
     let user_id = "user123".to_string();
-    let cart = cart_service.get_cart(user_id)?;
+    let token = headers
+        .get(CART_TOKEN_HEADER)
+        .and_then(|token| token.to_str().ok())
+        .ok_or(CartError::InvalidCartToken)?;
+    let CartOwner(guest_key) = CartOwner::guest(token)?;
+
+    let mut limits = HashMap::new();
+    for item in cart_service.get_cart(guest_key.clone())? {
+        let limit = match product_service.get_product_by_id(&item.product_id).await {
+            Ok(product) => product
+                .available_stock(item.variant_sku.as_deref())
+                .unwrap_or(Some(0)),
+            Err(_) => Some(0),
+        };
+        limits.insert((item.product_id, item.variant_sku), limit);
+    }
+    let cart = cart_service.merge_carts(&guest_key, user_id, |item| {
+        limits
+            .get(&(item.product_id.clone(), item.variant_sku.clone()))
+            .copied()
+            .unwrap_or(Some(0))
+    })?;
     Ok(Json(cart))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        api::model::ProductService,
+        api::model::{Product, ProductService, ProductVariant},
+        middleware::locale::LocalizedMessage,
         services::{checkout_service::CheckoutService, payment_service::PaymentService},
     };
 
@@ -115,12 +659,17 @@ mod tests {
     use tower::ServiceExt; // for `oneshot`
 
     fn app() -> Router {
+        app_with_products(ProductService::new())
+    }
+
+    fn app_with_products(product_service: ProductService) -> Router {
         let cart_service = CartService::new();
         let appstate = AppState {
             cart_service: cart_service.clone(),
             checkout_service: CheckoutService::new(),
-            product_service: ProductService::new(),
+            product_service,
             payment_service: PaymentService::new(),
+            ..Default::default()
         };
         Router::new().merge(cart_routes(Arc::new(appstate)))
     }
@@ -187,7 +736,7 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
 
         let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
-        let cart: Vec<CartItem> = serde_json::from_slice(&body).unwrap();
+        let cart = serde_json::from_slice::<CartSummary>(&body).unwrap().lines;
 
         assert_eq!(cart.len(), 1);
         assert_eq!(cart[0].product_id, "3");
@@ -229,6 +778,7 @@ mod tests {
                     .method("PUT")
                     .uri("/api/cart/update")
                     .header("content-type", "application/json")
+                    .header("if-match", "*")
                     .body(Body::from(update_payload.to_string()))
                     .unwrap(),
             )
@@ -250,7 +800,7 @@ mod tests {
             .unwrap();
 
         let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
-        let cart: Vec<CartItem> = serde_json::from_slice(&body).unwrap();
+        let cart = serde_json::from_slice::<CartSummary>(&body).unwrap().lines;
 
         assert_eq!(cart[0].quantity, 5);
     }
@@ -289,6 +839,7 @@ mod tests {
                     .method("DELETE")
                     .uri("/api/cart/remove")
                     .header("content-type", "application/json")
+                    .header("if-match", "*")
                     .body(Body::from(remove_payload.to_string()))
                     .unwrap(),
             )
@@ -310,8 +861,377 @@ mod tests {
             .unwrap();
 
         let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
-        let cart: Vec<CartItem> = serde_json::from_slice(&body).unwrap();
+        let cart = serde_json::from_slice::<CartSummary>(&body).unwrap().lines;
 
         assert!(cart.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_products_with_variants_require_a_valid_sku() {
+        let product_service = ProductService::new();
+        product_service
+            .create_product(Product {
+                id: "10".to_string(),
+                name: "Toghu shirt".to_string(),
+                price: 12000.0,
+                category: "clothing".to_string(),
+                variants: vec![
+                    ProductVariant {
+                        sku: "TOGHU-M".to_string(),
+                        size: Some("M".to_string()),
+                        price: 12000.0,
+                        stock: 3,
+                        ..ProductVariant::default()
+                    },
+                    ProductVariant {
+                        sku: "TOGHU-XL".to_string(),
+                        size: Some("XL".to_string()),
+                        price: 14000.0,
+                        stock: 1,
+                        ..ProductVariant::default()
+                    },
+                ],
+                ..Product::default()
+            })
+            .await
+            .unwrap();
+        let app = app_with_products(product_service);
+
+        let add = |payload: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/cart/add")
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(add(json!({ "product_id": "10" })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let message = response.extensions().get::<LocalizedMessage>().unwrap();
+        assert_eq!(
+            message.fr,
+            "Ce produit existe en plusieurs variantes ; choisissez-en une"
+        );
+
+        let response = app
+            .clone()
+            .oneshot(add(json!({ "product_id": "10", "variant_sku": "TOGHU-S" })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        assert_eq!(&body[..], b"Unknown product variant: TOGHU-S");
+
+        for sku in ["TOGHU-M", "TOGHU-XL"] {
+            let response = app
+                .clone()
+                .oneshot(add(json!({ "product_id": "10", "variant_sku": sku })))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        // Each variant is its own cart line.
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/api/cart")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let cart = serde_json::from_slice::<CartSummary>(&body).unwrap().lines;
+        assert_eq!(cart.len(), 2);
+        assert_eq!(cart[1].variant_sku.as_deref(), Some("TOGHU-XL"));
+    }
+
+    #[tokio::test]
+    async fn test_guest_cart_merges_into_account_cart_on_login() {
+        let app = app();
+        let request = |method: &str, uri: &str, token: Option<&str>, payload: serde_json::Value| {
+            let mut builder = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json");
+            if let Some(token) = token {
+                builder = builder.header(CART_TOKEN_HEADER, token);
+            }
+            builder.body(Body::from(payload.to_string())).unwrap()
+        };
+        let cart = |response: axum::response::Response| async move {
+            let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            serde_json::from_slice::<Vec<CartItem>>(&body).unwrap()
+        };
+        let summary = |response: axum::response::Response| async move {
+            let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            serde_json::from_slice::<CartSummary>(&body).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/cart/guest", None, json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), 1024).await.unwrap();
+        let token = serde_json::from_slice::<GuestCart>(&body).unwrap().cart_token;
+
+        for (product_id, quantity, token) in [
+            ("2", 3, Some(token.as_str())),
+            ("3", 1, Some(token.as_str())),
+            ("2", 2, None),
+        ] {
+            let payload = json!({ "product_id": product_id, "quantity": quantity });
+            let response = app
+                .clone()
+                .oneshot(request("POST", "/api/cart/add", token, payload))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/api/cart", None, json!({})))
+            .await
+            .unwrap();
+        assert_eq!(summary(response).await.lines.len(), 1);
+
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/cart/merge", Some(&token), json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let merged = cart(response).await;
+        assert_eq!(merged.len(), 2);
+        // 2 + 3 units wanted, but only 4 stools are in stock.
+        assert_eq!((merged[0].product_id.as_str(), merged[0].quantity), ("2", 4));
+        assert_eq!((merged[1].product_id.as_str(), merged[1].quantity), ("3", 1));
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/api/cart", Some(&token), json!({})))
+            .await
+            .unwrap();
+        assert!(summary(response).await.lines.is_empty());
+
+        let response = app
+            .oneshot(request("GET", "/api/cart", Some("not-a-token"), json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_cart_rejects_unknown_products_and_bad_quantities() {
+        let app = app();
+        let request = |method: &str, uri: &str, payload: serde_json::Value| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header("if-match", "*")
+                .body(Body::from(payload.to_string()))
+                .unwrap()
+        };
+        let status = |payload: (&'static str, &'static str, serde_json::Value)| {
+            let app = app.clone();
+            async move {
+                let (method, uri, payload) = payload;
+                app.oneshot(request(method, uri, payload))
+                    .await
+                    .unwrap()
+                    .status()
+            }
+        };
+
+        let add = |product_id: &str, quantity: u32| {
+            ("POST", "/api/cart/add", json!({ "product_id": product_id, "quantity": quantity }))
+        };
+        let update = |product_id: &str, quantity: u32| {
+            ("PUT", "/api/cart/update", json!({ "product_id": product_id, "quantity": quantity }))
+        };
+
+        assert_eq!(status(add("404", 1)).await, StatusCode::NOT_FOUND);
+        assert_eq!(status(add("2", 0)).await, StatusCode::BAD_REQUEST);
+        assert_eq!(status(add("2", 100)).await, StatusCode::BAD_REQUEST);
+        assert_eq!(status(update("2", 1)).await, StatusCode::NOT_FOUND);
+
+        assert_eq!(status(add("2", 60)).await, StatusCode::OK);
+        // 60 + 60 would exceed the per-item maximum; u32::MAX would overflow.
+        assert_eq!(status(add("2", 60)).await, StatusCode::BAD_REQUEST);
+        assert_eq!(status(add("2", u32::MAX)).await, StatusCode::BAD_REQUEST);
+        assert_eq!(status(update("2", 99)).await, StatusCode::OK);
+
+        assert_eq!(status(update("2", 0)).await, StatusCode::OK);
+        assert_eq!(
+            status(("DELETE", "/api/cart/remove", json!({ "product_id": "2" }))).await,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stale_cart_writes_are_refused_with_the_current_cart() {
+        let app = app();
+        let send = |method: &str, uri: &str, if_match: Option<&str>, payload: serde_json::Value| {
+            let mut request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json");
+            if let Some(etag) = if_match {
+                request = request.header("if-match", etag);
+            }
+            app.clone()
+                .oneshot(request.body(Body::from(payload.to_string())).unwrap())
+        };
+        let etag = |response: &Response| {
+            response.headers()[header::ETAG].to_str().unwrap().to_string()
+        };
+
+        let add = json!({ "product_id": "2", "quantity": 1 });
+        let added = send("POST", "/api/cart/add", None, add).await.unwrap();
+        let read = send("GET", "/api/cart", None, json!({})).await.unwrap();
+        assert_eq!(etag(&read), etag(&added));
+        let seen = etag(&read);
+
+        let update = json!({ "product_id": "2", "quantity": 3 });
+        let response = send("PUT", "/api/cart/update", None, update.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_REQUIRED);
+
+        // One device updates the cart; a write from another still based on `seen` is
+        // then refused.
+        let response = send("PUT", "/api/cart/update", Some(&seen), update).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let current = etag(&response);
+        assert_ne!(current, seen);
+
+        let remove = json!({ "product_id": "2" });
+        let response = send("DELETE", "/api/cart/remove", Some(&seen), remove.clone())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(etag(&response), current);
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let cart = serde_json::from_slice::<CartSummary>(&body).unwrap();
+        assert_eq!(cart.lines[0].quantity, 3);
+
+        let response = send("DELETE", "/api/cart/remove", Some(&current), remove).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_offline_operations_sync_in_time_order_with_last_change_winning() {
+        let app = app();
+        let add = json!({ "product_id": "2", "quantity": 1 });
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/cart/add")
+                    .header("content-type", "application/json")
+                    .body(Body::from(add.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let now = Utc::now();
+        let op = |op_id: &str, kind: &str, product_id: &str, quantity: Option<u32>, hours: i64| {
+            json!({
+                "op_id": op_id,
+                "kind": kind,
+                "product_id": product_id,
+                "quantity": quantity,
+                "made_at": now + chrono::TimeDelta::hours(hours),
+            })
+        };
+        let payload = json!({ "operations": [
+            // Older than the server's add of product 2.
+            op("a", "update", "2", Some(5), -1),
+            op("b", "add", "3", Some(2), -1),
+            op("c", "add", "404", None, -1),
+            // Made before `b`, so applied first, when 3 isn't in the cart yet.
+            op("d", "remove", "3", None, -2),
+            // A clock running ahead counts as now.
+            op("e", "update", "2", Some(4), 24),
+        ]});
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/cart/sync")
+                    .header("content-type", "application/json")
+                    .body(Body::from(payload.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key(header::ETAG));
+
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let synced = serde_json::from_slice::<CartSyncResponse>(&body).unwrap();
+        let statuses: Vec<_> = synced
+            .outcomes
+            .iter()
+            .map(|outcome| (outcome.op_id.as_str(), outcome.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("a", OperationStatus::Superseded),
+                ("b", OperationStatus::Applied),
+                ("c", OperationStatus::Rejected),
+                ("d", OperationStatus::Rejected),
+                ("e", OperationStatus::Applied),
+            ]
+        );
+        assert_eq!(synced.outcomes[2].reason.as_deref(), Some("Product not found"));
+        assert_eq!(synced.dropped, ["a", "c", "d"]);
+        let lines: Vec<_> = synced
+            .cart
+            .lines
+            .iter()
+            .map(|line| (line.product_id.as_str(), line.quantity))
+            .collect();
+        assert_eq!(lines, [("2", 4), ("3", 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_offline_sync_applying_nothing_creates_no_cart() {
+        let app = app();
+        let payload = json!({ "operations": [
+            { "op_id": "a", "kind": "add", "product_id": "404", "made_at": Utc::now() },
+            { "op_id": "b", "kind": "remove", "product_id": "2", "made_at": Utc::now() },
+        ]});
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/cart/sync")
+                    .header("content-type", "application/json")
+                    .body(Body::from(payload.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        // Version 0: there is still no cart.
+        assert_eq!(response.headers()[header::ETAG], "\"0\"");
+
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let synced = serde_json::from_slice::<CartSyncResponse>(&body).unwrap();
+        assert_eq!(synced.dropped, ["a", "b"]);
+        assert!(synced.cart.lines.is_empty());
+    }
 }
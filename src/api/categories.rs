@@ -0,0 +1,249 @@
+use axum::{
+    Router,
+    extract::{Extension, Json, Path},
+    http::{HeaderMap, StatusCode},
+    response::Response,
+    routing::{get, post, put},
+};
+
+use crate::{
+    api::{caching::cached_json, openapi::ErrorMessage},
+    models::category::{AttributeSchema, Category},
+    services::category_service::CategoryError,
+    state::AppState,
+};
+
+pub fn category_routes() -> Router {
+    Router::new()
+        .route("/api/categories", get(list_categories))
+        .route(
+            "/api/categories/{slug}/attributes",
+            get(category_attributes),
+        )
+        .route("/api/admin/categories", post(create_category))
+        .route(
+            "/api/admin/categories/{slug}",
+            put(update_category).delete(delete_category),
+        )
+}
+
+/// The category tree as a flat list, each parent before its children.
+///
+/// GET `/api/categories`
+#[utoipa::path(
+    get,
+    path = "/api/categories",
+    tag = "categories",
+    responses(
+        (status = 200, description = "All categories", body = Vec<Category>),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+    )
+)]
+pub async fn list_categories(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, CategoryError> {
+    let categories = state.product_service.categories().list()?;
+    Ok(cached_json(&headers, &categories))
+}
+
+/// Every attribute a product in this category is described with, including those
+/// inherited from its ancestors, root first. Vendors use it to build the listing form.
+///
+/// GET `/api/categories/{slug}/attributes`
+#[utoipa::path(
+    get,
+    path = "/api/categories/{slug}/attributes",
+    tag = "categories",
+    params(("slug" = String, Path, description = "Category slug")),
+    responses(
+        (status = 200, description = "Effective attribute schema", body = Vec<AttributeSchema>),
+        (status = 404, description = "Category not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn category_attributes(
+    Extension(state): Extension<AppState>,
+    Path(slug): Path<String>,
+) -> Result<Json<Vec<AttributeSchema>>, CategoryError> {
+    Ok(Json(
+        state.product_service.categories().attribute_schema(&slug)?,
+    ))
+}
+
+/// POST `/api/admin/categories`
+#[utoipa::path(
+    post,
+    path = "/api/admin/categories",
+    tag = "categories",
+    request_body = Category,
+    responses(
+        (status = 201, description = "Category created", body = Category),
+        (status = 400, description = "Invalid slug, names, parent or attributes", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Slug already taken", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn create_category(
+    Extension(state): Extension<AppState>,
+    Json(category): Json<Category>,
+) -> Result<(StatusCode, Json<Category>), CategoryError> {
+    let category = state.product_service.categories().create(category)?;
+    Ok((StatusCode::CREATED, Json(category)))
+}
+
+/// Renames, moves or changes the attributes of a category. Existing products keep their
+/// attribute values; the new schema applies when they are next updated.
+///
+/// PUT `/api/admin/categories/{slug}`
+#[utoipa::path(
+    put,
+    path = "/api/admin/categories/{slug}",
+    tag = "categories",
+    params(("slug" = String, Path, description = "Category slug")),
+    request_body = Category,
+    responses(
+        (status = 200, description = "Category updated", body = Category),
+        (status = 400, description = "Invalid names, parent or attributes", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Category not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn update_category(
+    Extension(state): Extension<AppState>,
+    Path(slug): Path<String>,
+    Json(mut category): Json<Category>,
+) -> Result<Json<Category>, CategoryError> {
+    category.slug = slug;
+    Ok(Json(state.product_service.categories().update(category)?))
+}
+
+/// Deletes a category that has neither subcategories nor products.
+///
+/// DELETE `/api/admin/categories/{slug}`
+#[utoipa::path(
+    delete,
+    path = "/api/admin/categories/{slug}",
+    tag = "categories",
+    params(("slug" = String, Path, description = "Category slug")),
+    responses(
+        (status = 204, description = "Category deleted"),
+        (status = 404, description = "Category not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Category still in use", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn delete_category(
+    Extension(state): Extension<AppState>,
+    Path(slug): Path<String>,
+) -> Result<StatusCode, CategoryError> {
+    let in_use = state
+        .product_service
+        .search(None)
+        .await
+        .iter()
+        .any(|(product, _)| product.category_slug == slug);
+    if in_use {
+        return Err(CategoryError::InUse(slug));
+    }
+    state.product_service.categories().delete(&slug)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Extension, Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+        routing::get,
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use super::category_routes;
+    use crate::{
+        api::{handler::products_by_category, listings::listing_routes},
+        state::AppState,
+    };
+
+    fn request(method: &str, uri: &str, payload: Option<Value>) -> Request<Body> {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        match payload {
+            Some(payload) => builder.body(Body::from(payload.to_string())).unwrap(),
+            None => builder.body(Body::empty()).unwrap(),
+        }
+    }
+
+    async fn json_body(response: axum::response::Response) -> Value {
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_category_listing_includes_descendants() {
+        let app = Router::new()
+            .route("/api/products/category/{slug}", get(products_by_category))
+            .merge(category_routes())
+            .merge(listing_routes())
+            .layer(Extension(AppState::default()));
+
+        let pepper = json!({
+            "id": "30", "name": "Poivre blanc de Penja", "price": 6000.0,
+            "category": "Poivre", "region": "Littoral", "certified": false,
+            "attributes": { "net_weight_g": "100", "color": "white" }
+        });
+        let response = app
+            .clone()
+            .oneshot(request(
+                "POST",
+                "/api/vendor/products",
+                Some(pepper.clone()),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let entry = json_body(response).await;
+        assert_eq!(entry["product"]["category"], "Pepper");
+        assert_eq!(entry["product"]["category_slug"], "pepper");
+
+        // Unknown categories and attributes missing from the schema are refused.
+        let mut unknown = pepper.clone();
+        unknown["id"] = json!("31");
+        unknown["category"] = json!("Electronics");
+        let mut incomplete = pepper.clone();
+        incomplete["id"] = json!("32");
+        incomplete["attributes"] = json!({});
+        for listing in [unknown, incomplete] {
+            let response = app
+                .clone()
+                .oneshot(request("POST", "/api/vendor/products", Some(listing)))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/api/products/category/food", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let products = json_body(response).await["products"].clone();
+        assert_eq!(products.as_array().unwrap().len(), 1);
+        assert_eq!(products[0]["id"], "30");
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/api/products/category/toys", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // A category with products can't be deleted.
+        let response = app
+            .oneshot(request("DELETE", "/api/admin/categories/pepper", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+}
@@ -0,0 +1,336 @@
+use axum::{
+    Router,
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    routing::{get, post},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::openapi::ErrorMessage,
+    models::certification::{Certification, CertificationEvidence, CertificationStatus},
+    services::certification_service::{CertificationError, DEFAULT_VALIDITY_DAYS},
+    state::AppState,
+};
+
+/// Reviewer recorded in the audit trail until admin accounts exist.
+const ADMIN_ID: &str = "admin";
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ApproveRequest {
+    /// How long the certification stays valid (default 365 days).
+    pub valid_for_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DecisionRequest {
+    /// Shown to the vendor and kept in the audit trail.
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CertificationFilter {
+    /// Only certifications in this status, e.g. `pending` for the review queue.
+    pub status: Option<CertificationStatus>,
+}
+
+pub fn certification_routes() -> Router {
+    Router::new()
+        .route(
+            "/api/products/{product_id}/certifications",
+            get(product_certifications).post(submit_certification),
+        )
+        .route("/api/admin/certifications", get(list_certifications))
+        .route(
+            "/api/admin/certifications/{certification_id}/approve",
+            post(approve_certification),
+        )
+        .route(
+            "/api/admin/certifications/{certification_id}/reject",
+            post(reject_certification),
+        )
+        .route(
+            "/api/admin/certifications/{certification_id}/revoke",
+            post(revoke_certification),
+        )
+}
+
+/// Submits evidence that a product is made in Cameroon, for admin review.
+///
+/// POST `/api/products/{product_id}/certifications`
+#[utoipa::path(
+    post,
+    path = "/api/products/{product_id}/certifications",
+    tag = "certifications",
+    params(("product_id" = String, Path, description = "Product identifier")),
+    request_body = CertificationEvidence,
+    responses(
+        (status = 201, description = "Request queued for review", body = Certification),
+        (status = 400, description = "Missing origin or workshop location", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Product not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "A request is already pending", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn submit_certification(
+    Extension(state): Extension<AppState>,
+    Path(product_id): Path<String>,
+    Json(evidence): Json<CertificationEvidence>,
+) -> Result<(StatusCode, Json<Certification>), CertificationError> {
+    let product = state.product_service.get_product_by_id(&product_id).await?;
+    let certification = state.certification_service.submit(
+        &product.id,
+        &product.vendor_name,
+        evidence,
+        Utc::now(),
+    )?;
+    Ok((StatusCode::CREATED, Json(certification)))
+}
+
+/// Lists a product's certification requests with their audit trail, oldest first.
+///
+/// GET `/api/products/{product_id}/certifications`
+#[utoipa::path(
+    get,
+    path = "/api/products/{product_id}/certifications",
+    tag = "certifications",
+    params(("product_id" = String, Path, description = "Product identifier")),
+    responses(
+        (status = 200, description = "Certifications of the product", body = Vec<Certification>),
+    )
+)]
+pub async fn product_certifications(
+    Extension(state): Extension<AppState>,
+    Path(product_id): Path<String>,
+) -> Result<Json<Vec<Certification>>, CertificationError> {
+    let certifications = state
+        .certification_service
+        .for_product(&product_id, Utc::now())?;
+    Ok(Json(certifications))
+}
+
+/// Review queue: lists certifications, optionally by status.
+///
+/// GET `/api/admin/certifications`
+#[utoipa::path(
+    get,
+    path = "/api/admin/certifications",
+    tag = "certifications",
+    params(CertificationFilter),
+    responses(
+        (status = 200, description = "Matching certifications, oldest first", body = Vec<Certification>),
+    )
+)]
+pub async fn list_certifications(
+    Extension(state): Extension<AppState>,
+    Query(filter): Query<CertificationFilter>,
+) -> Result<Json<Vec<Certification>>, CertificationError> {
+    let certifications = state
+        .certification_service
+        .list(filter.status, Utc::now())?;
+    Ok(Json(certifications))
+}
+
+/// Approves a pending request; the product counts as certified until it expires.
+///
+/// POST `/api/admin/certifications/{certification_id}/approve`
+#[utoipa::path(
+    post,
+    path = "/api/admin/certifications/{certification_id}/approve",
+    tag = "certifications",
+    params(("certification_id" = String, Path, description = "Certification identifier")),
+    request_body = ApproveRequest,
+    responses(
+        (status = 200, description = "Certification approved", body = Certification),
+        (status = 404, description = "Certification not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Not pending review", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn approve_certification(
+    Extension(state): Extension<AppState>,
+    Path(certification_id): Path<String>,
+    Json(payload): Json<ApproveRequest>,
+) -> Result<Json<Certification>, CertificationError> {
+    let certification = state.certification_service.approve(
+        &certification_id,
+        ADMIN_ID,
+        payload.valid_for_days.unwrap_or(DEFAULT_VALIDITY_DAYS),
+        Utc::now(),
+    )?;
+    Ok(Json(certification))
+}
+
+/// Rejects a pending request with a reason.
+///
+/// POST `/api/admin/certifications/{certification_id}/reject`
+#[utoipa::path(
+    post,
+    path = "/api/admin/certifications/{certification_id}/reject",
+    tag = "certifications",
+    params(("certification_id" = String, Path, description = "Certification identifier")),
+    request_body = DecisionRequest,
+    responses(
+        (status = 200, description = "Certification rejected", body = Certification),
+        (status = 400, description = "Missing reason", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Certification not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Not pending review", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn reject_certification(
+    Extension(state): Extension<AppState>,
+    Path(certification_id): Path<String>,
+    Json(payload): Json<DecisionRequest>,
+) -> Result<Json<Certification>, CertificationError> {
+    let certification = state.certification_service.reject(
+        &certification_id,
+        ADMIN_ID,
+        &payload.reason,
+        Utc::now(),
+    )?;
+    Ok(Json(certification))
+}
+
+/// Revokes an approved certification with a reason.
+///
+/// POST `/api/admin/certifications/{certification_id}/revoke`
+#[utoipa::path(
+    post,
+    path = "/api/admin/certifications/{certification_id}/revoke",
+    tag = "certifications",
+    params(("certification_id" = String, Path, description = "Certification identifier")),
+    request_body = DecisionRequest,
+    responses(
+        (status = 200, description = "Certification revoked", body = Certification),
+        (status = 400, description = "Missing reason", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Certification not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Not currently approved", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn revoke_certification(
+    Extension(state): Extension<AppState>,
+    Path(certification_id): Path<String>,
+    Json(payload): Json<DecisionRequest>,
+) -> Result<Json<Certification>, CertificationError> {
+    let certification = state.certification_service.revoke(
+        &certification_id,
+        ADMIN_ID,
+        &payload.reason,
+        Utc::now(),
+    )?;
+    Ok(Json(certification))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Extension, Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+        routing::get,
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use super::certification_routes;
+    use crate::{api::handler::search_products, state::AppState};
+
+    fn app() -> Router {
+        Router::new()
+            .route("/api/products", get(search_products))
+            .merge(certification_routes())
+            .layer(Extension(AppState::default()))
+    }
+
+    fn post(uri: &str, payload: Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap()
+    }
+
+    async fn json_body(response: axum::response::Response) -> Value {
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    async fn certified_ids(app: &Router) -> Vec<String> {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products?certified=true")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        json_body(response).await["products"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_certified_filter_follows_review_decisions() {
+        let app = app();
+        assert_eq!(certified_ids(&app).await, ["2"]);
+
+        let response = app
+            .clone()
+            .oneshot(post(
+                "/api/products/3/certifications",
+                json!({ "origin": "Printed in Yaoundé", "workshop_location": "Mokolo" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let id = json_body(response).await["certification_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // Pending requests don't certify anything yet.
+        assert_eq!(certified_ids(&app).await, ["2"]);
+
+        let response = app
+            .clone()
+            .oneshot(post(
+                &format!("/api/admin/certifications/{id}/approve"),
+                json!({ "valid_for_days": 90 }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut ids = certified_ids(&app).await;
+        ids.sort();
+        assert_eq!(ids, ["2", "3"]);
+
+        let response = app
+            .clone()
+            .oneshot(post(
+                &format!("/api/admin/certifications/{id}/revoke"),
+                json!({ "reason": "Shirts are imported blanks" }),
+            ))
+            .await
+            .unwrap();
+        let revoked = json_body(response).await;
+        assert_eq!(revoked["status"], "revoked");
+        assert_eq!(revoked["history"].as_array().unwrap().len(), 3);
+        assert_eq!(certified_ids(&app).await, ["2"]);
+
+        // A revoked certification can't be approved again.
+        let response = app
+            .oneshot(post(
+                &format!("/api/admin/certifications/{id}/approve"),
+                json!({}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+}
@@ -1,4 +1,10 @@
-use crate::models::order::OrderStatus;
+use crate::api::cart::priced_cart;
+use crate::api::model::PURCHASE_POPULARITY;
+use crate::api::openapi::ErrorMessage;
+use crate::models::cart_summary::CartWarningKind;
+use crate::models::locale::{Locale, Translated};
+use crate::models::order::{OrderLine, OrderStatus};
+use crate::services::promotion_service::PromotionError;
 use crate::{services::cart_services::CartError, state::AppState};
 use axum::{
     Router,
@@ -7,13 +13,14 @@ use axum::{
 };
 
 use serde::Deserialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CheckoutRequest {
     pub payment_method: String, // "MTN" or "Orange"
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct PaymentCallback {
     pub order_id: String,
     pub payment_status: String, // "success" or "failure"
@@ -25,38 +32,142 @@ pub fn checkout_routes() -> Router {
         .route("/api/payment-callback", post(payment_callback))
 }
 
-async fn checkout(Extension(state): Extension<AppState>) -> Result<Json<&'static str>, CartError> {
+/// Turns the user's cart into an order and starts the mobile money payment. The order is
+/// priced like the cart summary: the lines, less the discounts of the promotions the cart
+/// gets, plus the delivery fee.
+///
+/// POST `/api/checkout`
+#[utoipa::path(
+    post,
+    path = "/api/checkout",
+    tag = "checkout",
+    request_body = CheckoutRequest,
+    responses(
+        (status = 200, description = "Order created, awaiting payment", body = String),
+        (status = 400, description = "Empty cart or unknown product", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "An item is out of stock, or a promotion in the cart was used up meanwhile", body = ErrorMessage, content_type = "text/plain"),
+        (status = 500, description = "Storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn checkout(
+    Extension(state): Extension<AppState>,
+    locale: Locale,
+) -> Result<Json<&'static str>, PromotionError> {
     let user_id = "user123".to_string();
 
-    let cart_items = state
-        .cart_service
-        .get_cart(user_id.clone())
-        .map_err(|e| e)?;
-    if cart_items.is_empty() {
-        return Err(CartError::GenericError("Cart is empty".to_string())); // can't checkout with empty cart
+    let (summary, _) = priced_cart(
+        &state.cart_service,
+        &state.product_service,
+        &state.promotion_service,
+        locale,
+        &user_id,
+    )
+    .await?;
+    if summary.lines.is_empty() {
+        // can't checkout with empty cart
+        return Err(CartError::GenericError(Translated::new(
+            "Cart is empty",
+            "le panier est vide",
+        ))
+        .into());
     };
-
-    let mut total_amount = 0.0;
-    for item in &cart_items {
-        let product = state
-            .product_service
-            .get_product_by_id(&item.product_id)
-            .await
-            .map_err(|_| CartError::GenericError("product not found".to_owned()))?;
-        total_amount += item.quantity as f64 * product.price;
+    if summary
+        .warnings
+        .iter()
+        .any(|warning| warning.kind == CartWarningKind::Unpublished)
+    {
+        return Err(CartError::GenericError(Translated::new(
+            "product not found",
+            "produit introuvable",
+        ))
+        .into());
     }
+    // Ordering more than is left would sell stock the vendor doesn't have.
+    if let Some(warning) = summary
+        .warnings
+        .iter()
+        .find(|warning| warning.kind == CartWarningKind::OutOfStock)
+    {
+        return Err(CartError::OutOfStock {
+            product_id: warning.product_id.clone(),
+            available: warning.available.unwrap_or(0),
+        }
+        .into());
+    }
+
+    let lines = summary
+        .lines
+        .into_iter()
+        .map(|line| OrderLine {
+            product_id: line.product_id,
+            variant_sku: line.variant_sku,
+            name: line.name,
+            unit_price: line.unit_price,
+            quantity: line.quantity,
+        })
+        .collect();
+    let promotion_ids: Vec<_> = summary
+        .discounts
+        .iter()
+        .map(|discount| discount.promotion_id.clone())
+        .collect();
 
-    let product_ids: Vec<String> = cart_items.into_iter().map(|item| item.product_id).collect();
     let order = state
         .checkout_service
-        .create_order(user_id, product_ids, total_amount)
-        .map_err(|_| CartError::GenericError("Failed to create order".to_string()))?;
+        .create_order(
+            user_id.clone(),
+            lines,
+            summary.discounts,
+            summary.delivery.fee,
+            summary.grand_total,
+        )
+        .map_err(|_| {
+            CartError::GenericError(Translated::new(
+                "Failed to create order",
+                "la commande n'a pas pu être créée",
+            ))
+        })?;
+    // The order counts towards the promotions' per-user limits. Should another checkout
+    // have used one up meanwhile, the order is called off rather than discounted twice.
+    if let Err(err) = state.promotion_service.redeem(&user_id, &promotion_ids) {
+        if let Err(cancel_err) = state.checkout_service.cancel_order(&order.order_id) {
+            eprintln!(
+                "⚠️ Failed to call off order {}: {cancel_err}",
+                order.order_id
+            );
+        }
+        return Err(err);
+    }
+    state.cart_service.record_checkout(&user_id)?;
 
     state.payment_service.initiate_payment(&order);
 
+    for line in &order.items {
+        state
+            .product_service
+            .record_popularity(
+                &line.product_id,
+                PURCHASE_POPULARITY * u64::from(line.quantity),
+            )
+            .await;
+    }
+
     Ok(Json("Checkout started, awaiting payment..."))
 }
 
+/// Receives the payment outcome from the mobile money provider.
+///
+/// POST `/api/payment-callback`
+#[utoipa::path(
+    post,
+    path = "/api/payment-callback",
+    tag = "checkout",
+    request_body = PaymentCallback,
+    responses(
+        (status = 200, description = "Order status updated", body = String),
+        (status = 400, description = "Order status could not be updated", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
 async fn payment_callback(
     Extension(state): Extension<AppState>,
     Json(payload): Json<PaymentCallback>,
@@ -70,6 +181,11 @@ async fn payment_callback(
     state
         .checkout_service
         .update_order_status(payload.order_id, status)
-        .map_err(|_| CartError::GenericError("Failed to update order status".to_string()))?;
+        .map_err(|_| {
+            CartError::GenericError(Translated::new(
+                "Failed to update order status",
+                "le statut de la commande n'a pas pu être mis à jour",
+            ))
+        })?;
     Ok(Json("Payment status updated"))
 }
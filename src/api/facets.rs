@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::model::{Product, ProductQuery};
+
+/// Upper bounds (FCFA, exclusive) of the price buckets; the last bucket is open-ended.
+const PRICE_BUCKET_BOUNDS: [f64; 4] = [5_000.0, 10_000.0, 25_000.0, 50_000.0];
+
+/// A filter dimension of `ProductQuery` that facet counts are computed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FacetField {
+    Category,
+    Region,
+    Certified,
+    Price,
+}
+
+/// Number of products per value of each filterable field.
+///
+/// Each facet applies every active filter except its own, so the filter panel can show how
+/// many results picking another value would give (e.g. the category counts ignore the
+/// `category` filter but respect `region`, `certified` and the price range).
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct Facets {
+    pub category: Vec<FacetCount>,
+    pub region: Vec<FacetCount>,
+    pub certified: Vec<FacetCount>,
+    pub price: Vec<PriceBucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PriceBucket {
+    /// Inclusive lower bound.
+    pub min: f64,
+    /// Exclusive upper bound; `None` for the most expensive bucket.
+    pub max: Option<f64>,
+    pub count: usize,
+}
+
+/// Computes facet counts over `products` (the search results before any filtering).
+pub fn compute_facets<'a>(
+    products: impl Iterator<Item = &'a Product> + Clone,
+    params: &ProductQuery,
+) -> Facets {
+    let matching = |field: FacetField| {
+        products
+            .clone()
+            .filter(move |p| params.matches_filters(p, Some(field)))
+    };
+
+    Facets {
+        category: count_values(matching(FacetField::Category).map(|p| p.category.clone())),
+        region: count_values(matching(FacetField::Region).map(|p| p.region.clone())),
+        certified: count_values(matching(FacetField::Certified).map(|p| p.certified.to_string())),
+        price: price_buckets(matching(FacetField::Price).map(|p| p.price)),
+    }
+}
+
+/// Counts values case-insensitively, keeping the first spelling seen, most frequent first.
+fn count_values(values: impl Iterator<Item = String>) -> Vec<FacetCount> {
+    let mut counts: HashMap<String, FacetCount> = HashMap::new();
+    for value in values {
+        counts
+            .entry(value.to_lowercase())
+            .or_insert(FacetCount { value, count: 0 })
+            .count += 1;
+    }
+
+    let mut counts: Vec<FacetCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    counts
+}
+
+fn price_buckets(prices: impl Iterator<Item = f64>) -> Vec<PriceBucket> {
+    let mut buckets: Vec<PriceBucket> = std::iter::once(0.0)
+        .chain(PRICE_BUCKET_BOUNDS)
+        .zip(PRICE_BUCKET_BOUNDS.map(Some).into_iter().chain([None]))
+        .map(|(min, max)| PriceBucket { min, max, count: 0 })
+        .collect();
+
+    for price in prices {
+        if let Some(bucket) = buckets
+            .iter_mut()
+            .find(|b| price >= b.min && b.max.is_none_or(|max| price < max))
+        {
+            bucket.count += 1;
+        }
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compute_facets;
+    use crate::api::model::{Product, ProductQuery};
+
+    fn product(id: &str, category: &str, region: &str, price: f64, certified: bool) -> Product {
+        Product {
+            id: id.to_string(),
+            category: category.to_string(),
+            region: region.to_string(),
+            price,
+            certified,
+            ..Product::default()
+        }
+    }
+
+    #[test]
+    fn test_facets_ignore_own_filter_but_respect_others() {
+        let products = [
+            product("1", "Food", "Ouest", 2_000.0, true),
+            product("2", "Food", "Centre", 7_500.0, false),
+            product("3", "Clothing", "Ouest", 12_000.0, true),
+            product("4", "Furniture", "Littoral", 80_000.0, true),
+        ];
+        let params = ProductQuery {
+            category: Some("Food".to_string()),
+            region: Some("ouest".to_string()),
+            ..ProductQuery::default()
+        };
+
+        let facets = compute_facets(products.iter(), &params);
+
+        // Category counts ignore `category=Food` but keep `region=ouest`.
+        let categories: Vec<(&str, usize)> = facets
+            .category
+            .iter()
+            .map(|f| (f.value.as_str(), f.count))
+            .collect();
+        assert_eq!(categories, [("Clothing", 1), ("Food", 1)]);
+
+        // Region counts ignore `region` but keep `category=Food`.
+        let regions: Vec<(&str, usize)> = facets
+            .region
+            .iter()
+            .map(|f| (f.value.as_str(), f.count))
+            .collect();
+        assert_eq!(regions, [("Centre", 1), ("Ouest", 1)]);
+
+        // Certified and price facets respect both filters.
+        assert_eq!(facets.certified.len(), 1);
+        assert_eq!(facets.certified[0].value, "true");
+        let prices: Vec<usize> = facets.price.iter().map(|b| b.count).collect();
+        assert_eq!(prices, [1, 0, 0, 0, 0]);
+    }
+}
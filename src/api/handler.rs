@@ -1,105 +1,366 @@
-use axum::{Json, extract::Query, http::StatusCode, response::IntoResponse};
+use axum::{
+    Extension,
+    extract::{Path, Query},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
 
-use super::model::{PaginatedResponse, Product, ProductQuery, mock_products};
+use super::{
+    caching::cached_json,
+    facets::compute_facets,
+    openapi::ErrorMessage,
+    model::{
+        PaginatedResponse, Product, ProductError, ProductQuery, ProductSort, SuggestionQuery,
+        VIEW_POPULARITY,
+    },
+    pagination::{Cursor, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE, sort_products},
+};
+use crate::{
+    models::locale::{Locale, Translated},
+    services::{
+        category_service::CategoryError,
+        geography::{self, Region},
+    },
+    state::AppState,
+};
+
+/// Sets `certified` from the certifications valid right now, so approvals, expiries and
+/// revocations show up immediately in results and in the `certified` filter.
+fn apply_certifications<'a>(
+    state: &AppState,
+    products: impl Iterator<Item = &'a mut Product>,
+) -> Result<(), ProductError> {
+    let certified = state
+        .certification_service
+        .certified_products(Utc::now())
+        .map_err(|_| ProductError::LockError)?;
+    for product in products {
+        product.certified = certified.contains(&product.id);
+    }
+    Ok(())
+}
+
+/// Sets `average_rating` and `rating_count` from the reviews posted so far.
+fn apply_ratings<'a>(
+    state: &AppState,
+    products: impl Iterator<Item = &'a mut Product>,
+) -> Result<(), ProductError> {
+    let summaries = state
+        .review_service
+        .summaries()
+        .map_err(|_| ProductError::LockError)?;
+    for product in products {
+        let summary = summaries.get(&product.id).copied().unwrap_or_default();
+        product.average_rating = summary.average_rating;
+        product.rating_count = summary.rating_count;
+    }
+    Ok(())
+}
 
 /// Handles the GET `/api/products` endpoint.
 ///
 /// Allows buyers to search and filter available products with the following optional query parameters:
-/// - `query`: Search keyword for product name (case-insensitive partial match)
-/// - `category`: Filter by product category
+/// - `query`: Search words, matched against name, description, category, tags and vendor
+///   name in both French and English, with accent folding and stemming
+/// - `category`: Filter by category slug or name, including its subcategories
 /// - `min_price`: Minimum price filter
 /// - `max_price`: Maximum price filter
-/// - `region`: Filter by vendor's region
-/// - `certified`: Filter by "Made in Cameroon" certified status
+/// - `region`: Filter by vendor's region, by code (`CM-OU`) or French/English name
+/// - `department`, `town`: Filter by the vendor's department or town, by code or name
+/// - `near`: Keep vendors within `radius_km` (default 50) of this town
+/// - `certified`: Filter by "Made in Cameroon" certified status, i.e. holding an approved,
+///   unexpired and unrevoked certification
+/// - `min_rating`: Minimum average review score (1 to 5); unreviewed products are excluded
+/// - `facets`: When `true`, include per-value counts for category, region, certified and
+///   price buckets, each computed with every other active filter applied
+/// - `sort`: `relevance` (default), `price_asc`, `price_desc`, `newest` or `rating`
+///   (highest average first)
 /// - `page`: Pagination page number (default = 1)
-/// - `limit`: Number of products per page (default = 10)
+/// - `limit`: Number of products per page (default = 10, at most 50)
+/// - `cursor`: The `next_cursor` of the previous response; takes precedence over `page`
+///   and stays stable when products are added or removed between requests
 ///
-/// Returns a paginated JSON response containing the list of matching products.
+/// Returns a paginated JSON response containing the list of matching products. With a
+/// `query`, products are ranked by relevance and the other filters are applied on top.
+/// Names, descriptions and category names are in the negotiated language (the user's
+/// preference, else `Accept-Language`), falling back to the vendor's text.
 ///
 /// # Example
 ///
-/// ```
+/// ```text
 /// GET /api/products?query=stool&min_price=5000&region=Ouest&page=1&limit=5
 /// ```
 ///
 /// # Response
-/// - `200 OK` with `PaginatedResponse<Product>` body
+/// - `200 OK` with `PaginatedResponse<Product>` body and a weak `ETag`
+/// - `304 Not Modified` when `If-None-Match` matches the current results
 ///
 /// # Errors
-/// - Currently none; always returns 200 even if no products found (empty list)
+/// - `400 Bad Request` for `page=0`, a `limit` outside 1..=50, a cursor issued for
+///   another `sort`, an unknown category, region, department or town, a `radius_km` outside
+///   (0, 500] or a `min_rating` outside 1..=5; an empty result is still `200 OK`
 ///
 /// # Notes
-/// - Currently uses mock products; will later integrate with a database.
-pub async fn search_products(Query(params): Query<ProductQuery>) -> impl IntoResponse {
-    // Todo: implement real database
-    let all_products = mock_products();
+/// - Currently uses the in-memory `ProductService`; will later integrate with a database.
+#[utoipa::path(
+    get,
+    path = "/api/products",
+    tag = "products",
+    params(ProductQuery),
+    responses(
+        (status = 200, description = "Matching products", body = PaginatedResponse<Product>),
+        (status = 304, description = "Results unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 400, description = "Invalid `page`, `limit`, `cursor`, category, location or rating filter", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn search_products(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    locale: Locale,
+    Query(mut params): Query<ProductQuery>,
+) -> Result<Response, ProductError> {
+    if let Some(ref category) = params.category {
+        let categories = state.product_service.categories();
+        let scope = categories
+            .resolve(category)
+            .and_then(|category| categories.subtree(&category.slug))
+            .map_err(|err| match err {
+                CategoryError::LockError => ProductError::LockError,
+                _ => ProductError::InvalidQuery(Translated::new(
+                    format!("unknown category {category:?}"),
+                    format!("catégorie inconnue {category:?}"),
+                )),
+            })?;
+        params.category_scope = Some(scope);
+    }
+    list_products(&state, &headers, locale, params).await
+}
 
-    let filtered: Vec<Product> = all_products
-        .into_iter()
-        .filter(|p| {
-            if let Some(ref query) = params.query {
-                if !p.name.to_lowercase().contains(&query.to_lowercase()) {
-                    return false;
-                }
-            }
-            if let Some(ref category) = params.category {
-                if p.category.to_lowercase() != category.to_lowercase() {
-                    return false;
-                }
-            }
-            if let Some(min_price) = params.min_price {
-                if p.price < min_price {
-                    return false;
-                }
-            }
-            if let Some(max_price) = params.max_price {
-                if p.price > max_price {
-                    return false;
-                }
-            }
-            if let Some(ref region) = params.region {
-                if p.region.to_lowercase() != region.to_lowercase() {
-                    return false;
-                }
-            }
-            if let Some(certified) = params.certified {
-                if p.certified != certified {
-                    return false;
-                }
-            }
-            true
-        })
-        .collect();
+/// Handles the GET `/api/products/category/{slug}` endpoint.
+///
+/// Same as `/api/products?category={slug}`: lists the products of the category and of
+/// all its subcategories (e.g. `food` includes spices and pepper), with the same filters,
+/// sorting and pagination.
+#[utoipa::path(
+    get,
+    path = "/api/products/category/{slug}",
+    tag = "products",
+    params(("slug" = String, Path, description = "Category slug"), ProductQuery),
+    responses(
+        (status = 200, description = "Products of the category and its descendants", body = PaginatedResponse<Product>),
+        (status = 304, description = "Results unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 400, description = "Invalid `page`, `limit`, `cursor`, location or rating filter", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Category not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn products_by_category(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    locale: Locale,
+    Path(slug): Path<String>,
+    Query(mut params): Query<ProductQuery>,
+) -> Result<Response, ProductError> {
+    match state.product_service.categories().subtree(&slug) {
+        Ok(scope) => params.category_scope = Some(scope),
+        Err(err) => return Ok(err.into_response()),
+    }
+    params.category = Some(slug);
+    list_products(&state, &headers, locale, params).await
+}
 
+async fn list_products(
+    state: &AppState,
+    headers: &HeaderMap,
+    locale: Locale,
+    params: ProductQuery,
+) -> Result<Response, ProductError> {
     let page = params.page.unwrap_or(1);
-    let limit = params.limit.unwrap_or(10);
-    let start = (page - 1) * limit;
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if page == 0 {
+        return Err(ProductError::InvalidQuery(Translated::new(
+            "page must be at least 1",
+            "page doit valoir au moins 1",
+        )));
+    }
+    if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+        return Err(ProductError::InvalidQuery(Translated::new(
+            format!("limit must be between 1 and {MAX_PAGE_SIZE}"),
+            format!("limit doit être compris entre 1 et {MAX_PAGE_SIZE}"),
+        )));
+    }
+    params.validate_filters()?;
+    let sort = params.sort.unwrap_or(ProductSort::Relevance);
+    let cursor = params
+        .cursor
+        .as_deref()
+        .map(|token| Cursor::decode(token, sort))
+        .transpose()?;
+
+    let mut ranked = state.product_service.search(params.query.as_deref()).await;
+    apply_certifications(state, ranked.iter_mut().map(|(p, _)| p))?;
+    apply_ratings(state, ranked.iter_mut().map(|(p, _)| p))?;
+
+    let facets = params
+        .facets
+        .unwrap_or(false)
+        .then(|| compute_facets(ranked.iter().map(|(p, _)| p), &params));
+
+    let mut filtered: Vec<(Product, f64)> = ranked
+        .into_iter()
+        .filter(|(p, _)| params.matches_filters(p, None))
+        .collect();
+    sort_products(&mut filtered, sort);
 
-    let paginated_products = filtered.iter().skip(start).take(limit).cloned().collect();
+    let remaining: &[(Product, f64)] = match &cursor {
+        Some(cursor) => {
+            let start = filtered.partition_point(|(p, score)| !cursor.precedes(p, *score));
+            &filtered[start..]
+        }
+        None => {
+            let start = (page - 1).saturating_mul(limit).min(filtered.len());
+            &filtered[start..]
+        }
+    };
+    let page_items = &remaining[..limit.min(remaining.len())];
+    let next_cursor = match page_items.last() {
+        Some((last, score)) if remaining.len() > limit => {
+            Some(Cursor::after(last, *score, sort).encode())
+        }
+        _ => None,
+    };
 
     let response = PaginatedResponse {
         page,
         limit,
         total: filtered.len(),
-        products: paginated_products,
+        products: page_items
+            .iter()
+            .map(|(p, _)| {
+                let mut product = p.clone();
+                state.product_service.localize(&mut product, locale);
+                product
+            })
+            .collect(),
+        next_cursor,
+        facets,
     };
 
-    (StatusCode::OK, Json(response))
+    Ok(cached_json(headers, &response))
+}
+
+/// Handles the GET `/api/products/{product_id}` endpoint.
+///
+/// Returns a single product, in the negotiated language, with a weak `ETag`, or
+/// `304 Not Modified` when the client's `If-None-Match` is still current. Only full
+/// responses count as a view for popularity.
+#[utoipa::path(
+    get,
+    path = "/api/products/{product_id}",
+    tag = "products",
+    params(("product_id" = String, Path, description = "Product identifier")),
+    responses(
+        (status = 200, description = "The product", body = Product),
+        (status = 304, description = "Product unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 404, description = "Product not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn get_product(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    locale: Locale,
+    Path(product_id): Path<String>,
+) -> Result<Response, ProductError> {
+    let mut product = state
+        .product_service
+        .get_product_by_id(&product_id)
+        .await?;
+    apply_certifications(&state, std::iter::once(&mut product))?;
+    apply_ratings(&state, std::iter::once(&mut product))?;
+    state.product_service.localize(&mut product, locale);
+
+    let response = cached_json(&headers, &product);
+    if response.status().is_success() {
+        state
+            .product_service
+            .record_popularity(&product.id, VIEW_POPULARITY)
+            .await;
+    }
+    Ok(response)
+}
+
+/// Handles the GET `/api/products/suggestions` endpoint.
+///
+/// Autocomplete for the search box, cheap enough to call on every keystroke: returns
+/// product and category names in the negotiated language completing `term`
+/// (accent-insensitive, tolerating small typos), most popular first.
+///
+/// # Example
+///
+/// ```text
+/// GET /api/products/suggestions?term=bamil
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/products/suggestions",
+    tag = "products",
+    params(SuggestionQuery),
+    responses(
+        (status = 200, description = "Suggested search terms", body = Vec<String>),
+        (status = 304, description = "Suggestions unchanged since the `ETag` sent in `If-None-Match`"),
+    )
+)]
+pub async fn search_suggestions(
+    Extension(state): Extension<AppState>,
+    headers: HeaderMap,
+    locale: Locale,
+    Query(params): Query<SuggestionQuery>,
+) -> Response {
+    let limit = params.limit.unwrap_or(8).min(20);
+    let suggestions = state
+        .product_service
+        .suggest(&params.term, limit, locale)
+        .await;
+
+    cached_json(&headers, &suggestions)
+}
+
+/// Handles the GET `/api/regions` endpoint.
+///
+/// The region → department → town hierarchy vendors pick their location from, with the
+/// codes and names accepted by the location filters of `/api/products`.
+#[utoipa::path(
+    get,
+    path = "/api/regions",
+    tag = "products",
+    responses(
+        (status = 200, description = "Regions with their departments and towns", body = Vec<Region>),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+    )
+)]
+pub async fn list_regions(headers: HeaderMap) -> Response {
+    cached_json(&headers, &geography::cameroon().regions())
 }
 
 #[cfg(test)]
 mod test {
     use axum::body::to_bytes;
-    use axum::{Router, body::Body, extract::Request, routing::get};
+    use axum::http::header;
+    use axum::{Extension, Router, body::Body, extract::Request, routing::get};
     use hyper::StatusCode;
     use tower::util::ServiceExt;
 
     use crate::api::model::{PaginatedResponse, Product};
+    use crate::state::AppState;
 
-    use super::search_products;
+    use super::{get_product, search_products, search_suggestions};
 
     fn app() -> Router {
-        Router::new().route("/api/products", get(search_products))
+        Router::new()
+            .route("/api/products", get(search_products))
+            .route("/api/products/suggestions", get(search_suggestions))
+            .route("/api/products/{product_id}", get(get_product))
+            .layer(Extension(AppState::default()))
     }
 
     #[tokio::test]
@@ -179,6 +440,292 @@ mod test {
 
         // Only "Bamileke Stool" is certified
         assert_eq!(parsed.total, 1);
-        assert_eq!(parsed.products[0].certified, true);
+        assert!(parsed.products[0].certified);
+    }
+
+    #[tokio::test]
+    async fn test_search_products_not_modified_with_matching_etag() {
+        let app = app();
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/api/products?query=stool").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let etag = response.headers()[header::ETAG].clone();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products?query=stool")
+                    .header(header::IF_NONE_MATCH, etag)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_product_by_id() {
+        let app = app();
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/api/products/2").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key(header::ETAG));
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let product: Product = serde_json::from_slice(&body).unwrap();
+        assert_eq!(product.name, "Bamileke Stool");
+
+        let response = app
+            .oneshot(Request::builder().uri("/api/products/404").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_search_products_matches_description_and_vendor_without_accents() {
+        let app = app();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products?query=yaounde%20cotton&region=Centre")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let parsed: PaginatedResponse<Product> = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(parsed.total, 1);
+        assert_eq!(parsed.products[0].name, "Cameroon T-shirt");
+    }
+
+    #[tokio::test]
+    async fn test_search_products_by_location_hierarchy() {
+        let app = app();
+
+        for (filters, expected) in [
+            ("region=West", vec!["2"]),
+            ("region=CM-CE", vec!["3"]),
+            ("department=Mifi", vec!["2"]),
+            ("town=yaounde", vec!["3"]),
+            ("near=Bandjoun", vec!["2"]),
+            ("near=Foumban&radius_km=20", vec![]),
+            ("near=Douala&radius_km=300", vec!["2", "3"]),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/api/products?sort=price_desc&{filters}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{filters}");
+            let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            let parsed: PaginatedResponse<Product> = serde_json::from_slice(&body).unwrap();
+            let ids: Vec<&str> = parsed.products.iter().map(|p| p.id.as_str()).collect();
+            assert_eq!(ids, expected, "{filters}");
+        }
+
+        for filters in ["town=Atlantis", "department=Nowhere", "near=Douala&radius_km=0"] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/api/products?{filters}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{filters}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_french_search_and_content() {
+        let app = app();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products?query=tabouret%20sculpte")
+                    .header(header::ACCEPT_LANGUAGE, "fr-CM, en;q=0.5")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let parsed: PaginatedResponse<Product> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed.total, 1);
+        assert_eq!(parsed.products[0].name, "Tabouret bamiléké");
+        assert_eq!(parsed.products[0].category_name, "Meubles");
+
+        // French words still match for English clients, who get the vendor's text.
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products?query=meubles")
+                    .header(header::ACCEPT_LANGUAGE, "en-GB")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let parsed: PaginatedResponse<Product> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed.products[0].name, "Bamileke Stool");
+        assert_eq!(parsed.products[0].category_name, "Furniture");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products/suggestions?term=tabou")
+                    .header(header::ACCEPT_LANGUAGE, "fr")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let suggestions: Vec<String> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(suggestions, ["Tabouret bamiléké"]);
+    }
+
+    #[tokio::test]
+    async fn test_suggestions_prefix_and_typo() {
+        let app = app();
+
+        for (term, expected) in [
+            ("bamil", "Bamileke Stool"),
+            ("furn", "Furniture"),
+            ("camroon", "Cameroon T-shirt"),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/api/products/suggestions?term={term}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            let suggestions: Vec<String> = serde_json::from_slice(&body).unwrap();
+            assert_eq!(suggestions, [expected], "term {term:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_products_with_facets() {
+        let app = app();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products?category=Clothing&facets=true")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let parsed: PaginatedResponse<Product> = serde_json::from_slice(&body).unwrap();
+        let facets = parsed.facets.unwrap();
+
+        // The category facet ignores `category=Clothing`; the others respect it.
+        assert_eq!(parsed.total, 1);
+        assert_eq!(facets.category.len(), 2);
+        assert_eq!(facets.region.len(), 1);
+        assert_eq!(facets.region[0].value, "Centre");
+
+        // Facets are omitted unless requested.
+        let response = app
+            .oneshot(Request::builder().uri("/api/products").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(parsed.get("facets").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_products_rejects_invalid_paging() {
+        let app = app();
+
+        for uri in [
+            "/api/products?page=0",
+            "/api/products?limit=0",
+            "/api/products?limit=500",
+            "/api/products?cursor=garbage",
+        ] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_products_sorted_with_cursor() {
+        let app = app();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products?sort=price_asc&limit=1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let first: PaginatedResponse<Product> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(first.products[0].name, "Cameroon T-shirt");
+        let cursor = first.next_cursor.unwrap();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/products?sort=price_asc&limit=1&cursor={cursor}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let second: PaginatedResponse<Product> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(second.products[0].name, "Bamileke Stool");
+        assert!(second.next_cursor.is_none());
     }
 }
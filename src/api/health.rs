@@ -0,0 +1,193 @@
+use axum::{Extension, Json, Router, http::StatusCode, routing::get};
+use serde::{Deserialize, Serialize};
+use tokio::time::timeout;
+use utoipa::ToSchema;
+
+use crate::state::AppState;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub shutting_down: bool,
+    pub storage: CheckResult,
+    pub payment_provider: CheckResult,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CheckResult {
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+impl CheckResult {
+    fn from_result<E: ToString>(result: Result<(), E>) -> Self {
+        match result {
+            Ok(()) => CheckResult {
+                ok: true,
+                error: None,
+            },
+            Err(err) => CheckResult {
+                ok: false,
+                error: Some(err.to_string()),
+            },
+        }
+    }
+}
+
+pub fn health_routes() -> Router {
+    Router::new()
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
+}
+
+/// Liveness probe.
+///
+/// GET `/healthz`
+///
+/// Returns `200 OK` as long as the process is able to serve requests at all.
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    responses((status = 200, description = "Process is alive", body = String))
+)]
+async fn liveness() -> Json<&'static str> {
+    Json("ok")
+}
+
+/// Readiness probe.
+///
+/// GET `/readyz`
+///
+/// Checks that every storage backend can be locked and that the payment provider is
+/// reachable. Returns `503 Service Unavailable` if any check fails or once shutdown has
+/// started, so load balancers stop routing new traffic while in-flight requests drain.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to receive traffic", body = ReadinessReport),
+        (status = 503, description = "A dependency is unavailable or shutdown has started", body = ReadinessReport),
+    )
+)]
+async fn readiness(Extension(state): Extension<AppState>) -> (StatusCode, Json<ReadinessReport>) {
+    let deadline = state.config.readiness_timeout;
+
+    let storage = async {
+        state.cart_service.ping().map_err(|e| e.to_string())?;
+        state.checkout_service.ping().map_err(|e| e.to_string())?;
+        state
+            .product_service
+            .ping()
+            .await
+            .map_err(|e| e.to_string())
+    };
+    let storage = match timeout(deadline, storage).await {
+        Ok(result) => CheckResult::from_result(result),
+        Err(_) => CheckResult::from_result(Err("storage check timed out")),
+    };
+
+    let payment_provider =
+        CheckResult::from_result(state.payment_service.check_reachability(deadline).await);
+
+    let shutting_down = state.shutdown.is_triggered();
+    let ready = !shutting_down && storage.ok && payment_provider.ok;
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadinessReport {
+            ready,
+            shutting_down,
+            storage,
+            payment_provider,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Extension, Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    use super::{ReadinessReport, health_routes};
+    use crate::{services::payment_service::PaymentService, state::AppState};
+
+    fn app(state: AppState) -> Router {
+        Router::new().merge(health_routes()).layer(Extension(state))
+    }
+
+    async fn get(app: Router, uri: &str) -> (StatusCode, ReadinessReport) {
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_healthz_ok() {
+        let response = app(AppState::default())
+            .oneshot(
+                Request::builder()
+                    .uri("/healthz")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_readyz_ok() {
+        let (status, report) = get(app(AppState::default()), "/readyz").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(report.ready);
+        assert!(report.storage.ok);
+        assert!(report.payment_provider.ok);
+    }
+
+    #[tokio::test]
+    async fn test_readyz_fails_during_shutdown() {
+        let state = AppState::default();
+        state.shutdown.trigger();
+
+        let (status, report) = get(app(state), "/readyz").await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(report.shutting_down);
+    }
+
+    #[tokio::test]
+    async fn test_readyz_fails_when_payment_provider_unreachable() {
+        // Bind and immediately drop a listener to get a port nothing is listening on.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let state = AppState {
+            payment_service: PaymentService::with_provider(addr.to_string()),
+            ..Default::default()
+        };
+
+        let (status, report) = get(app(state), "/readyz").await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!report.payment_provider.ok);
+        assert!(report.storage.ok);
+    }
+}
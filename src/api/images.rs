@@ -0,0 +1,270 @@
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, Extension, Json, Multipart, Path},
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{model::ProductImage, openapi::ErrorMessage},
+    models::locale::Translated,
+    services::image_service::ImageError,
+    state::AppState,
+};
+
+/// Stored files never change (every upload gets a new key), so clients and CDNs may keep
+/// them for a year without revalidating.
+const IMAGE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Multipart form accepted by the upload endpoint.
+#[derive(ToSchema)]
+pub struct ImageUpload {
+    /// JPEG, PNG or WebP file.
+    #[schema(value_type = String, format = Binary)]
+    pub image: Vec<u8>,
+    /// Text alternative for screen readers.
+    pub alt: Option<String>,
+}
+
+impl ImageUpload {
+    /// Reads the form's fields, ignoring unknown ones. The body is only read here, so
+    /// handlers can refuse a request before receiving the file.
+    pub async fn read(multipart: &mut Multipart) -> Result<ImageUpload, ImageError> {
+        let mut image = None;
+        let mut alt = None;
+        while let Some(field) = multipart.next_field().await? {
+            match field.name() {
+                Some("image") => image = Some(field.bytes().await?.to_vec()),
+                Some("alt") => alt = Some(field.text().await?),
+                _ => {}
+            }
+        }
+        let image = image.ok_or_else(|| {
+            ImageError::InvalidImage(Translated::new(
+                "missing `image` field",
+                "le champ `image` est manquant",
+            ))
+        })?;
+        Ok(ImageUpload { image, alt })
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImageOrderRequest {
+    /// Every image id of the product, in the new display order.
+    pub image_ids: Vec<String>,
+}
+
+/// `max_upload_request_bytes` raises the body limit of the upload route only.
+pub fn image_routes(max_upload_request_bytes: usize) -> Router {
+    Router::new()
+        .route(
+            "/api/products/{product_id}/images",
+            post(upload_image).layer(DefaultBodyLimit::max(max_upload_request_bytes)),
+        )
+        .route(
+            "/api/products/{product_id}/images/order",
+            put(reorder_images),
+        )
+        .route("/api/images/{*key}", get(serve_image))
+}
+
+/// Uploads a photo of a product and appends it to the product's gallery.
+///
+/// POST `/api/products/{product_id}/images`
+#[utoipa::path(
+    post,
+    path = "/api/products/{product_id}/images",
+    tag = "images",
+    params(("product_id" = String, Path, description = "Product identifier")),
+    request_body(content = ImageUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Image stored, with its thumbnails", body = ProductImage),
+        (status = 400, description = "Missing or corrupt image", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Product not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 413, description = "Image too large", body = ErrorMessage, content_type = "text/plain"),
+        (status = 415, description = "Not a JPEG, PNG or WebP image", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn upload_image(
+    Extension(state): Extension<AppState>,
+    Path(product_id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<ProductImage>), ImageError> {
+    // Fail fast rather than processing an image nobody can attach.
+    state.product_service.get_product_by_id(&product_id).await?;
+
+    let upload = ImageUpload::read(&mut multipart).await?;
+    let image = state
+        .image_service
+        .upload(&product_id, upload.image, upload.alt)
+        .await?;
+    state
+        .product_service
+        .add_image(&product_id, image.clone())
+        .await?;
+    Ok((StatusCode::CREATED, Json(image)))
+}
+
+/// Changes the display order of a product's images.
+///
+/// PUT `/api/products/{product_id}/images/order`
+#[utoipa::path(
+    put,
+    path = "/api/products/{product_id}/images/order",
+    tag = "images",
+    params(("product_id" = String, Path, description = "Product identifier")),
+    request_body = ImageOrderRequest,
+    responses(
+        (status = 200, description = "The reordered gallery", body = Vec<ProductImage>),
+        (status = 400, description = "Ids don't match the product's images", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Product not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn reorder_images(
+    Extension(state): Extension<AppState>,
+    Path(product_id): Path<String>,
+    Json(payload): Json<ImageOrderRequest>,
+) -> Result<Json<Vec<ProductImage>>, ImageError> {
+    let images = state
+        .product_service
+        .reorder_images(&product_id, &payload.image_ids)
+        .await?;
+    Ok(Json(images))
+}
+
+/// Serves a stored image or thumbnail.
+///
+/// GET `/api/images/{key}`
+#[utoipa::path(
+    get,
+    path = "/api/images/{key}",
+    tag = "images",
+    params(("key" = String, Path, description = "Storage key, as found in image URLs")),
+    responses(
+        (status = 200, description = "Image bytes (WebP, JPEG or PNG)", body = [u8], content_type = "application/octet-stream"),
+        (status = 404, description = "Image not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn serve_image(
+    Extension(state): Extension<AppState>,
+    Path(key): Path<String>,
+) -> Result<Response, ImageError> {
+    let (bytes, content_type) = state
+        .image_service
+        .fetch(&key)
+        .await
+        .map_err(|err| match err {
+            // Keys that can't be stored are simply not found.
+            ImageError::Storage(_) => ImageError::NotFound,
+            err => err,
+        })?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (
+                header::CACHE_CONTROL,
+                HeaderValue::from_static(IMAGE_CACHE_CONTROL),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, sync::Arc};
+
+    use axum::{
+        Extension, Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode, header},
+    };
+    use image::{DynamicImage, ImageFormat, RgbImage};
+    use tower::ServiceExt;
+
+    use super::image_routes;
+    use crate::{
+        api::model::ProductImage,
+        services::{blob_store::LocalBlobStore, image_service::ImageService},
+        state::AppState,
+    };
+
+    const BOUNDARY: &str = "XBOUNDARYX";
+
+    fn multipart_body(image: &[u8]) -> Vec<u8> {
+        let mut body = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"alt\"\r\n\r\nStool\r\n\
+             --{BOUNDARY}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"a.png\"\r\n\
+             Content-Type: image/png\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(image);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+        body
+    }
+
+    fn upload(product_id: &str, image: &[u8]) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/products/{product_id}/images"))
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from(multipart_body(image)))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_upload_attaches_image_and_serves_thumbnails() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState {
+            image_service: ImageService::new(Arc::new(LocalBlobStore::new(dir.path())), 1 << 20),
+            ..Default::default()
+        };
+        let app = Router::new()
+            .merge(image_routes(2 << 20))
+            .layer(Extension(state.clone()));
+
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(600, 400))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let response = app.clone().oneshot(upload("2", &png)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let image: ProductImage = serde_json::from_slice(&body).unwrap();
+        assert_eq!(image.alt.as_deref(), Some("Stool"));
+
+        let product = state.product_service.get_product_by_id("2").await.unwrap();
+        assert_eq!(product.images[0].id, image.id);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(&image.thumbnails[0].url)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/jpeg");
+        assert!(
+            response.headers()[header::CACHE_CONTROL]
+                .to_str()
+                .unwrap()
+                .contains("immutable")
+        );
+
+        let response = app.oneshot(upload("2", b"plain text")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
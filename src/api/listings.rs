@@ -0,0 +1,430 @@
+use axum::{
+    Router,
+    extract::{Extension, Json, Path, Query},
+    http::StatusCode,
+    routing::{get, post, put},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::{
+        model::{Product, ProductError},
+        openapi::ErrorMessage,
+        wishlist::notify_wishlists,
+    },
+    models::{
+        locale::Translated,
+        moderation::{Blocklist, ListingAction, ModerationEntry, ModerationStatus},
+    },
+    services::{geography, moderation_service::ModerationError},
+    state::AppState,
+};
+
+/// Reviewer recorded in moderation decisions until admin accounts exist.
+const ADMIN_ID: &str = "admin";
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ModerationDecision {
+    /// Shown to the vendor and kept with the entry.
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ModerationFilter {
+    /// Only entries in this status, e.g. `pending` for the review queue.
+    pub status: Option<ModerationStatus>,
+}
+
+pub fn listing_routes() -> Router {
+    Router::new()
+        .route("/api/vendor/products", post(create_listing))
+        .route("/api/vendor/products/{product_id}", put(update_listing))
+        .route("/api/admin/moderation", get(list_moderation))
+        .route(
+            "/api/admin/moderation/{moderation_id}/approve",
+            post(approve_listing),
+        )
+        .route(
+            "/api/admin/moderation/{moderation_id}/reject",
+            post(reject_listing),
+        )
+        .route(
+            "/api/admin/blocklist",
+            get(get_blocklist).put(set_blocklist),
+        )
+}
+
+/// Screens a submission and publishes it right away if it passes, otherwise queues it.
+async fn submit(
+    state: &AppState,
+    action: ListingAction,
+    mut product: Product,
+) -> Result<(StatusCode, Json<ModerationEntry>), ModerationError> {
+    product.validate()?;
+    geography::cameroon().normalize_location(&mut product)?;
+    state.product_service.check_category(&mut product)?;
+    let exists = state
+        .product_service
+        .get_product_by_id(&product.id)
+        .await
+        .is_ok();
+    match action {
+        ListingAction::Create if exists => {
+            return Err(ProductError::InvalidProduct(Translated::new(
+                format!("a product with id {} already exists", product.id),
+                format!("un produit d'identifiant {} existe déjà", product.id),
+            ))
+            .into());
+        }
+        ListingAction::Update if !exists => return Err(ProductError::ProductNotFound.into()),
+        _ => {}
+    }
+
+    let flags = state.moderation_service.screen(&product)?;
+    if !flags.is_empty() {
+        let entry = state
+            .moderation_service
+            .record(action, product, flags, Utc::now())?;
+        return Ok((StatusCode::ACCEPTED, Json(entry)));
+    }
+
+    let product = publish(state, action, product).await?;
+    let entry = state
+        .moderation_service
+        .record(action, product, Vec::new(), Utc::now())?;
+    let status = match action {
+        ListingAction::Create => StatusCode::CREATED,
+        ListingAction::Update => StatusCode::OK,
+    };
+    Ok((status, Json(entry)))
+}
+
+async fn publish(
+    state: &AppState,
+    action: ListingAction,
+    product: Product,
+) -> Result<Product, ProductError> {
+    match action {
+        ListingAction::Create => state.product_service.create_product(product).await,
+        ListingAction::Update => {
+            let product = state.product_service.update_product(product).await?;
+            notify_wishlists(state, &product);
+            Ok(product)
+        }
+    }
+}
+
+/// Lists a new product. Listings mentioning a blocklisted foreign brand or a suspicious
+/// phrase are held for review instead of being published.
+///
+/// POST `/api/vendor/products`
+#[utoipa::path(
+    post,
+    path = "/api/vendor/products",
+    tag = "listings",
+    request_body = Product,
+    responses(
+        (status = 201, description = "Published", body = ModerationEntry),
+        (status = 202, description = "Flagged and queued for review", body = ModerationEntry),
+        (status = 400, description = "Invalid product or duplicate id", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn create_listing(
+    Extension(state): Extension<AppState>,
+    Json(product): Json<Product>,
+) -> Result<(StatusCode, Json<ModerationEntry>), ModerationError> {
+    submit(&state, ListingAction::Create, product).await
+}
+
+/// Replaces a product listing. A flagged update leaves the current version online until
+/// it is reviewed.
+///
+/// PUT `/api/vendor/products/{product_id}`
+#[utoipa::path(
+    put,
+    path = "/api/vendor/products/{product_id}",
+    tag = "listings",
+    params(("product_id" = String, Path, description = "Product identifier")),
+    request_body = Product,
+    responses(
+        (status = 200, description = "Published", body = ModerationEntry),
+        (status = 202, description = "Flagged and queued for review", body = ModerationEntry),
+        (status = 400, description = "Invalid product", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Product not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn update_listing(
+    Extension(state): Extension<AppState>,
+    Path(product_id): Path<String>,
+    Json(mut product): Json<Product>,
+) -> Result<(StatusCode, Json<ModerationEntry>), ModerationError> {
+    product.id = product_id;
+    submit(&state, ListingAction::Update, product).await
+}
+
+/// Moderation queue and decision history.
+///
+/// GET `/api/admin/moderation`
+#[utoipa::path(
+    get,
+    path = "/api/admin/moderation",
+    tag = "listings",
+    params(ModerationFilter),
+    responses(
+        (status = 200, description = "Matching entries, oldest first", body = Vec<ModerationEntry>),
+    )
+)]
+pub async fn list_moderation(
+    Extension(state): Extension<AppState>,
+    Query(filter): Query<ModerationFilter>,
+) -> Result<Json<Vec<ModerationEntry>>, ModerationError> {
+    Ok(Json(state.moderation_service.list(filter.status)?))
+}
+
+/// Publishes a flagged listing, e.g. a local brand whose name resembles a foreign one.
+///
+/// POST `/api/admin/moderation/{moderation_id}/approve`
+#[utoipa::path(
+    post,
+    path = "/api/admin/moderation/{moderation_id}/approve",
+    tag = "listings",
+    params(("moderation_id" = String, Path, description = "Moderation entry identifier")),
+    request_body = ModerationDecision,
+    responses(
+        (status = 200, description = "Listing published", body = ModerationEntry),
+        (status = 400, description = "Missing reason, or the listing can no longer be published", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Entry not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Already decided", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn approve_listing(
+    Extension(state): Extension<AppState>,
+    Path(moderation_id): Path<String>,
+    Json(payload): Json<ModerationDecision>,
+) -> Result<Json<ModerationEntry>, ModerationError> {
+    let entry = state.moderation_service.decide(
+        &moderation_id,
+        ModerationStatus::Approved,
+        ADMIN_ID,
+        &payload.reason,
+        Utc::now(),
+    )?;
+    // The catalog may have changed since submission (id taken, product deleted).
+    if let Err(err) = publish(&state, entry.action, entry.product.clone()).await {
+        state.moderation_service.reopen(&moderation_id)?;
+        return Err(err.into());
+    }
+    Ok(Json(entry))
+}
+
+/// Refuses a flagged listing with a reason.
+///
+/// POST `/api/admin/moderation/{moderation_id}/reject`
+#[utoipa::path(
+    post,
+    path = "/api/admin/moderation/{moderation_id}/reject",
+    tag = "listings",
+    params(("moderation_id" = String, Path, description = "Moderation entry identifier")),
+    request_body = ModerationDecision,
+    responses(
+        (status = 200, description = "Listing rejected", body = ModerationEntry),
+        (status = 400, description = "Missing reason", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Entry not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Already decided", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn reject_listing(
+    Extension(state): Extension<AppState>,
+    Path(moderation_id): Path<String>,
+    Json(payload): Json<ModerationDecision>,
+) -> Result<Json<ModerationEntry>, ModerationError> {
+    let entry = state.moderation_service.decide(
+        &moderation_id,
+        ModerationStatus::Rejected,
+        ADMIN_ID,
+        &payload.reason,
+        Utc::now(),
+    )?;
+    Ok(Json(entry))
+}
+
+/// GET `/api/admin/blocklist`
+#[utoipa::path(
+    get,
+    path = "/api/admin/blocklist",
+    tag = "listings",
+    responses((status = 200, description = "Current blocklist", body = Blocklist))
+)]
+pub async fn get_blocklist(
+    Extension(state): Extension<AppState>,
+) -> Result<Json<Blocklist>, ModerationError> {
+    Ok(Json(state.moderation_service.blocklist()?))
+}
+
+/// Replaces the blocked brands and suspicious patterns. Applies to new submissions only.
+///
+/// PUT `/api/admin/blocklist`
+#[utoipa::path(
+    put,
+    path = "/api/admin/blocklist",
+    tag = "listings",
+    request_body = Blocklist,
+    responses((status = 200, description = "Blocklist saved", body = Blocklist))
+)]
+pub async fn set_blocklist(
+    Extension(state): Extension<AppState>,
+    Json(blocklist): Json<Blocklist>,
+) -> Result<Json<Blocklist>, ModerationError> {
+    Ok(Json(state.moderation_service.set_blocklist(blocklist)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Extension, Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+    };
+    use chrono::{TimeDelta, Utc};
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use super::listing_routes;
+    use crate::{api::model::ProductImage, state::AppState};
+
+    fn request(method: &str, uri: &str, payload: Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap()
+    }
+
+    async fn json_body(response: axum::response::Response) -> Value {
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_foreign_brand_listing_is_held_until_approved() {
+        let state = AppState::default();
+        let app = Router::new()
+            .merge(listing_routes())
+            .layer(Extension(state.clone()));
+
+        let clean = json!({
+            "id": "20", "name": "Panier tressé", "price": 4000.0,
+            "category": "Crafts", "region": "Ouest", "certified": false
+        });
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/vendor/products", clean))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(state.product_service.get_product_by_id("20").await.is_ok());
+
+        let branded = json!({
+            "id": "21", "name": "Nike Air sneakers", "price": 30000.0,
+            "category": "Shoes", "region": "Littoral", "certified": false
+        });
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/vendor/products", branded))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let entry = json_body(response).await;
+        assert_eq!(entry["status"], "pending");
+        assert_eq!(entry["flags"][0], "name mentions the brand \"Nike\"");
+        assert!(state.product_service.get_product_by_id("21").await.is_err());
+
+        let id = entry["moderation_id"].as_str().unwrap();
+        let response = app
+            .clone()
+            .oneshot(request(
+                "POST",
+                &format!("/api/admin/moderation/{id}/reject"),
+                json!({ "reason": "Resold foreign brand" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let entry = json_body(response).await;
+        assert_eq!(entry["status"], "rejected");
+        assert_eq!(entry["decision_reason"], "Resold foreign brand");
+        assert!(state.product_service.get_product_by_id("21").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_listing_publication_date_is_set_by_the_server() {
+        let state = AppState::default();
+        let app = Router::new()
+            .merge(listing_routes())
+            .layer(Extension(state.clone()));
+
+        let listing = json!({
+            "id": "20", "name": "Panier tressé", "price": 4000.0,
+            "category": "Crafts", "region": "Ouest", "certified": false,
+            "created_at": "2099-01-01T00:00:00Z"
+        });
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/vendor/products", listing.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created_at = state
+            .product_service
+            .get_product_by_id("20")
+            .await
+            .unwrap()
+            .created_at;
+        assert!(Utc::now() - created_at < TimeDelta::minutes(1));
+        let entry = json_body(response).await;
+        assert_eq!(entry["product"]["created_at"], json!(created_at));
+
+        let mut update = listing;
+        update["price"] = json!(4500.0);
+        update["created_at"] = json!("1970-01-01T00:00:00Z");
+        let response = app
+            .clone()
+            .oneshot(request("PUT", "/api/vendor/products/20", update))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let stored = state.product_service.get_product_by_id("20").await.unwrap();
+        assert_eq!(stored.price, 4500.0);
+        assert_eq!(stored.created_at, created_at);
+    }
+
+    #[tokio::test]
+    async fn test_listing_update_keeps_uploaded_images() {
+        let state = AppState::default();
+        let app = Router::new()
+            .merge(listing_routes())
+            .layer(Extension(state.clone()));
+        let image = ProductImage {
+            id: "img-1".to_string(),
+            url: "/api/images/products/2/img-1/original.jpg".to_string(),
+            ..ProductImage::default()
+        };
+        state.product_service.add_image("2", image).await.unwrap();
+
+        let update = json!({
+            "id": "2", "name": "Bamileke Stool", "price": 14000.0,
+            "category": "Furniture", "region": "Ouest", "certified": true
+        });
+        let response = app
+            .oneshot(request("PUT", "/api/vendor/products/2", update))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let stored = state.product_service.get_product_by_id("2").await.unwrap();
+        assert_eq!(stored.price, 14000.0);
+        assert_eq!(stored.images.last().unwrap().id, "img-1");
+    }
+}
@@ -2,4 +2,19 @@ pub mod handler;
 pub mod model;
 pub mod cart;
 pub mod checkout;
-pub mod orders;
\ No newline at end of file
+pub mod orders;
+pub mod health;
+pub mod openapi;
+pub mod caching;
+pub mod facets;
+pub mod pagination;
+pub mod images;
+pub mod categories;
+pub mod certifications;
+pub mod listings;
+pub mod preferences;
+
+pub mod reviews;
+pub mod wishlist;
+pub mod notifications;
+pub mod promotions;
\ No newline at end of file
@@ -1,16 +1,284 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
 
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use super::facets::{FacetField, Facets};
+use crate::{
+    middleware::locale::localized_error,
+    models::locale::{Locale, Localize, Translated},
+};
+use crate::services::{
+    category_service::{CategoryError, CategoryService},
+    geography::{self, DEFAULT_NEAR_RADIUS_KM, MAX_NEAR_RADIUS_KM},
+    search::{SearchIndex, fold, max_typos, within_typos},
+};
+
+/// Popularity points for a product detail view and for each unit purchased.
+pub const VIEW_POPULARITY: u64 = 1;
+pub const PURCHASE_POPULARITY: u64 = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
 pub struct Product {
     pub id: String,
     pub name: String,
     pub price: f64,
+    /// English name of the category the product is filed under, e.g. `Clothing`. Vendors
+    /// may submit a category's slug or its French or English name.
     pub category: String,
+    /// Slug of `category`, e.g. `clothing`. Set on submission; stored values are ignored.
+    #[serde(default)]
+    pub category_slug: String,
+    /// French name of the vendor's region, e.g. `Ouest`.
     pub region: String,
+    /// Code of the vendor's town in the region → department → town hierarchy, e.g.
+    /// `CM-OU-BAFOUSSAM`; required for department, town and distance filters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub town: Option<String>,
+    /// Holds a currently valid "Made in Cameroon" certification. Derived from the
+    /// certification workflow when the product is served; stored values are ignored.
     pub certified: bool,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub vendor_name: String,
+    /// Brand the vendor sells under, if any; screened against the foreign-brand blocklist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand: Option<String>,
+    /// When the product was first published. Set by the server; submitted values are
+    /// ignored.
+    #[serde(default)]
+    pub created_at: DateTime<Utc>,
+    /// Mean review score (1–5, one decimal); `None` until the product has been reviewed.
+    /// Derived from verified buyers' reviews when the product is served; stored values
+    /// are ignored.
+    #[serde(default)]
+    pub average_rating: Option<f64>,
+    /// Number of reviews behind `average_rating`.
+    #[serde(default)]
+    pub rating_count: u32,
+    /// Photos in display order; the first one is the cover image. Ignored on listing
+    /// updates: upload and reorder them through `/api/products/{id}/images`.
+    #[serde(default)]
+    pub images: Vec<ProductImage>,
+    #[serde(default)]
+    pub materials: Vec<String>,
+    #[serde(default)]
+    pub dimensions: Option<Dimensions>,
+    #[serde(default)]
+    pub weight_grams: Option<u32>,
+    /// Units in stock for products sold without variants; `None` means stock isn't tracked.
+    #[serde(default)]
+    pub stock: Option<u32>,
+    /// Purchasable variations (size, colour...). When non-empty, cart items and order
+    /// lines must name one of them by SKU, and its price and stock apply.
+    #[serde(default)]
+    pub variants: Vec<ProductVariant>,
+    /// Values for the attributes defined by the category and its ancestors, e.g.
+    /// `net_weight_g` for spices.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+    /// Name and description in other languages. `name` and `description` are shown when
+    /// the requested language has no entry.
+    #[serde(default)]
+    pub translations: BTreeMap<Locale, ProductText>,
+    /// Name of `category` in the response language. Derived when the product is served;
+    /// stored values are ignored.
+    #[serde(default)]
+    pub category_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct ProductText {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct ProductImage {
+    /// Assigned on upload; images added by other means may leave it empty.
+    #[serde(default)]
+    pub id: String,
+    /// The full-size image, with its metadata stripped.
+    pub url: String,
+    #[serde(default)]
+    pub alt: Option<String>,
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+    /// Smaller renditions for slow connections, smallest first.
+    #[serde(default)]
+    pub thumbnails: Vec<Thumbnail>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageFormatKind,
+    pub url: String,
+}
+
+/// Image encodings the marketplace stores and serves.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormatKind {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl ImageFormatKind {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormatKind::Jpeg => "jpg",
+            ImageFormatKind::Png => "png",
+            ImageFormatKind::Webp => "webp",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ImageFormatKind::Jpeg => "image/jpeg",
+            ImageFormatKind::Png => "image/png",
+            ImageFormatKind::Webp => "image/webp",
+        }
+    }
+
+    /// The format of a stored file, from its key's extension.
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.rsplit_once('.')?.1 {
+            "jpg" => Some(ImageFormatKind::Jpeg),
+            "png" => Some(ImageFormatKind::Png),
+            "webp" => Some(ImageFormatKind::Webp),
+            _ => None,
+        }
+    }
+}
+
+/// Package size in centimetres.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, ToSchema)]
+pub struct Dimensions {
+    pub length_cm: f64,
+    pub width_cm: f64,
+    pub height_cm: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct ProductVariant {
+    pub sku: String,
+    #[serde(default)]
+    pub size: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    pub price: f64,
+    pub stock: u32,
+}
+
+impl Product {
+    /// Name and description to show in `locale`.
+    pub fn text(&self, locale: Locale) -> (&str, &str) {
+        match self.translations.get(&locale) {
+            Some(text) if !text.name.trim().is_empty() => {
+                let description = if text.description.trim().is_empty() {
+                    &self.description
+                } else {
+                    &text.description
+                };
+                (&text.name, description)
+            }
+            _ => (&self.name, &self.description),
+        }
+    }
+
+    /// Looks up the purchasable unit for `variant_sku`: the named variant for products with
+    /// variants, or `None` for products sold as a single item.
+    pub fn resolve_variant(
+        &self,
+        variant_sku: Option<&str>,
+    ) -> Result<Option<&ProductVariant>, ProductError> {
+        match (self.variants.is_empty(), variant_sku) {
+            (true, None) => Ok(None),
+            (true, Some(sku)) => Err(ProductError::VariantNotFound(sku.to_string())),
+            (false, None) => Err(ProductError::VariantRequired),
+            (false, Some(sku)) => self
+                .variants
+                .iter()
+                .find(|v| v.sku == sku)
+                .map(Some)
+                .ok_or_else(|| ProductError::VariantNotFound(sku.to_string())),
+        }
+    }
+
+    /// Unit price of the given variant, or of the product itself when it has none.
+    pub fn unit_price(&self, variant_sku: Option<&str>) -> Result<f64, ProductError> {
+        Ok(self
+            .resolve_variant(variant_sku)?
+            .map_or(self.price, |variant| variant.price))
+    }
+
+    /// Units available for the given variant; `None` when stock isn't tracked.
+    pub fn available_stock(&self, variant_sku: Option<&str>) -> Result<Option<u32>, ProductError> {
+        Ok(match self.resolve_variant(variant_sku)? {
+            Some(variant) => Some(variant.stock),
+            None => self.stock,
+        })
+    }
+
+    /// Checks the listing is well-formed (required fields, prices, unique variant SKUs).
+    pub fn validate(&self) -> Result<(), ProductError> {
+        let invalid = |en: String, fr: String| {
+            Err(ProductError::InvalidProduct(Translated::new(en, fr)))
+        };
+
+        if self.id.trim().is_empty() || self.name.trim().is_empty() {
+            return invalid(
+                "id and name are required".to_string(),
+                "l'identifiant et le nom sont obligatoires".to_string(),
+            );
+        }
+        if !(self.price.is_finite() && self.price >= 0.0) {
+            return invalid(
+                "price must be a non-negative number".to_string(),
+                "le prix doit être un nombre positif ou nul".to_string(),
+            );
+        }
+        let mut skus = std::collections::HashSet::new();
+        for variant in &self.variants {
+            if variant.sku.trim().is_empty() {
+                return invalid(
+                    "every variant needs a SKU".to_string(),
+                    "chaque variante doit avoir une référence (SKU)".to_string(),
+                );
+            }
+            if !skus.insert(variant.sku.as_str()) {
+                return invalid(
+                    format!("duplicate variant SKU {}", variant.sku),
+                    format!("référence de variante en double : {}", variant.sku),
+                );
+            }
+            if !(variant.price.is_finite() && variant.price >= 0.0) {
+                return invalid(
+                    format!("variant {} has an invalid price", variant.sku),
+                    format!("le prix de la variante {} est invalide", variant.sku),
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -19,11 +287,69 @@ pub enum ProductError {
     LockError,
     #[error("Product not found")]
     ProductNotFound,
+    #[error("Invalid query: {0}")]
+    InvalidQuery(Translated),
+    #[error("Invalid or expired cursor")]
+    InvalidCursor,
+    #[error("This product comes in several variants; choose one")]
+    VariantRequired,
+    #[error("Unknown product variant: {0}")]
+    VariantNotFound(String),
+    #[error("Invalid product: {0}")]
+    InvalidProduct(Translated),
+}
+impl Localize for ProductError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, ProductError::LockError) => {
+                "Impossible d'accéder au stockage des produits".to_string()
+            }
+            (Locale::Fr, ProductError::ProductNotFound) => "Produit introuvable".to_string(),
+            (Locale::Fr, ProductError::InvalidQuery(detail)) => {
+                format!("Requête invalide : {}", detail.localized(locale))
+            }
+            (Locale::Fr, ProductError::InvalidCursor) => "Curseur invalide ou expiré".to_string(),
+            (Locale::Fr, ProductError::VariantRequired) => {
+                "Ce produit existe en plusieurs variantes ; choisissez-en une".to_string()
+            }
+            (Locale::Fr, ProductError::VariantNotFound(sku)) => {
+                format!("Variante de produit inconnue : {sku}")
+            }
+            (Locale::Fr, ProductError::InvalidProduct(detail)) => {
+                format!("Produit invalide : {}", detail.localized(locale))
+            }
+        }
+    }
+}
+
+impl IntoResponse for ProductError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ProductError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+            ProductError::ProductNotFound => StatusCode::NOT_FOUND,
+            ProductError::InvalidQuery(_)
+            | ProductError::InvalidCursor
+            | ProductError::VariantRequired
+            | ProductError::VariantNotFound(_)
+            | ProductError::InvalidProduct(_) => StatusCode::BAD_REQUEST,
+        };
+        localized_error(status, &self)
+    }
+}
+
+/// Products together with the search index kept in sync with them.
+struct Catalog {
+    products: HashMap<String, Product>,
+    index: SearchIndex,
+    /// product id -> accumulated views and purchases, used to rank suggestions
+    popularity: HashMap<String, u64>,
 }
 
 #[derive(Clone)]
 pub struct ProductService {
-    products: Arc<Mutex<HashMap<String, Product>>>,
+    catalog: Arc<Mutex<Catalog>>,
+    categories: CategoryService,
 }
 impl ProductService {
     pub fn new() -> Self {
@@ -31,39 +357,484 @@ impl ProductService {
         for p in mock_products() {
             map.insert(p.id.clone(), p);
         }
+        let categories = CategoryService::new();
+        let mut index = SearchIndex::new();
+        for product in map.values() {
+            index_product(&mut index, &categories, product);
+        }
 
         ProductService {
-            products: Arc::new(Mutex::new(map)),
+            catalog: Arc::new(Mutex::new(Catalog {
+                products: map,
+                index,
+                popularity: HashMap::new(),
+            })),
+            categories,
+        }
+    }
+
+    /// Fills in the language-dependent fields: the translated name and description, if
+    /// any, and the category name.
+    pub fn localize(&self, product: &mut Product, locale: Locale) {
+        let (name, description) = product.text(locale);
+        let (name, description) = (name.to_string(), description.to_string());
+        product.name = name;
+        product.description = description;
+        if let Ok(category) = self.categories.get(&product.category_slug) {
+            product.category_name = locale.pick(&category.name_fr, &category.name_en).to_string();
+            product.category = category.name_en;
+        } else {
+            product.category_name = product.category.clone();
         }
     }
 
+    /// The category tree products must be filed under.
+    pub fn categories(&self) -> &CategoryService {
+        &self.categories
+    }
+
+    /// Resolves the submitted category, filling in its slug and English name, and checks the
+    /// attributes against its schema.
+    pub fn check_category(&self, product: &mut Product) -> Result<(), ProductError> {
+        let to_product_error = |err: CategoryError| match err {
+            CategoryError::LockError => ProductError::LockError,
+            CategoryError::NotFound => ProductError::InvalidProduct(Translated::new(
+                format!("unknown category {:?}", product.category),
+                format!("catégorie inconnue {:?}", product.category),
+            )),
+            err => ProductError::InvalidProduct(Translated::of(&err)),
+        };
+        let category = self
+            .categories
+            .resolve(&product.category)
+            .map_err(to_product_error)?;
+        self.categories
+            .validate_attributes(&category.slug, &product.attributes)
+            .map_err(to_product_error)?;
+        product.category = category.name_en;
+        product.category_slug = category.slug;
+        Ok(())
+    }
+
     pub async fn get_product_by_id(&self, product_id: &str) -> Result<Product, ProductError> {
-        let products = self.products.lock().await;
-        products.get(product_id)
+        let catalog = self.catalog.lock().await;
+        catalog.products.get(product_id)
             .cloned()
             .ok_or(ProductError::ProductNotFound)
     }
+
+    /// Adds a new product to the catalog and the search index.
+    pub async fn create_product(&self, mut product: Product) -> Result<Product, ProductError> {
+        product.validate()?;
+        self.check_category(&mut product)?;
+        let mut catalog = self.catalog.lock().await;
+        if catalog.products.contains_key(&product.id) {
+            return Err(ProductError::InvalidProduct(Translated::new(
+                format!("a product with id {} already exists", product.id),
+                format!("un produit d'identifiant {} existe déjà", product.id),
+            )));
+        }
+        product.created_at = Utc::now();
+        index_product(&mut catalog.index, &self.categories, &product);
+        catalog.products.insert(product.id.clone(), product.clone());
+        Ok(product)
+    }
+
+    /// Replaces an existing product and re-indexes it, keeping its publication date and
+    /// its gallery, which is managed through the image endpoints.
+    pub async fn update_product(&self, mut product: Product) -> Result<Product, ProductError> {
+        product.validate()?;
+        self.check_category(&mut product)?;
+        let mut catalog = self.catalog.lock().await;
+        let Some(stored) = catalog.products.get(&product.id) else {
+            return Err(ProductError::ProductNotFound);
+        };
+        product.created_at = stored.created_at;
+        product.images = stored.images.clone();
+        index_product(&mut catalog.index, &self.categories, &product);
+        catalog.products.insert(product.id.clone(), product.clone());
+        Ok(product)
+    }
+
+    /// Appends an uploaded image to the product's gallery.
+    pub async fn add_image(
+        &self,
+        product_id: &str,
+        image: ProductImage,
+    ) -> Result<Vec<ProductImage>, ProductError> {
+        let mut catalog = self.catalog.lock().await;
+        let product = catalog
+            .products
+            .get_mut(product_id)
+            .ok_or(ProductError::ProductNotFound)?;
+        product.images.push(image);
+        Ok(product.images.clone())
+    }
+
+    /// Reorders the product's gallery; `image_ids` must list every image exactly once.
+    pub async fn reorder_images(
+        &self,
+        product_id: &str,
+        image_ids: &[String],
+    ) -> Result<Vec<ProductImage>, ProductError> {
+        let mut catalog = self.catalog.lock().await;
+        let product = catalog
+            .products
+            .get_mut(product_id)
+            .ok_or(ProductError::ProductNotFound)?;
+
+        let mut remaining = std::mem::take(&mut product.images);
+        let mut reordered = Vec::with_capacity(remaining.len());
+        for id in image_ids {
+            match remaining.iter().position(|image| &image.id == id) {
+                Some(index) => reordered.push(remaining.remove(index)),
+                None => {
+                    remaining.extend(reordered);
+                    product.images = remaining;
+                    return Err(ProductError::InvalidProduct(Translated::new(
+                        format!("unknown image id {id}"),
+                        format!("identifiant d'image inconnu : {id}"),
+                    )));
+                }
+            }
+        }
+        if !remaining.is_empty() {
+            reordered.extend(remaining);
+            product.images = reordered;
+            return Err(ProductError::InvalidProduct(Translated::new(
+                "the new order must list every image",
+                "le nouvel ordre doit reprendre toutes les images",
+            )));
+        }
+        product.images = reordered;
+        Ok(product.images.clone())
+    }
+
+    /// Returns products matching `query` with their relevance score, best match first, or
+    /// the whole catalog (all scored `0.0`) ordered by id when there is no query.
+    pub async fn search(&self, query: Option<&str>) -> Vec<(Product, f64)> {
+        let catalog = self.catalog.lock().await;
+        match query.filter(|q| !q.trim().is_empty()) {
+            Some(query) => catalog
+                .index
+                .search(query)
+                .into_iter()
+                .filter_map(|(id, score)| Some((catalog.products.get(&id)?.clone(), score)))
+                .collect(),
+            None => {
+                let mut products: Vec<(Product, f64)> = catalog
+                    .products
+                    .values()
+                    .map(|p| (p.clone(), 0.0))
+                    .collect();
+                products.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+                products
+            }
+        }
+    }
+
+    /// Autocomplete for the search box: product and category names in `locale` starting
+    /// with `term` (at a word boundary, ignoring accents), then close misspellings of it,
+    /// each group ordered by popularity.
+    pub async fn suggest(&self, term: &str, limit: usize, locale: Locale) -> Vec<String> {
+        let term = fold(term.trim());
+        if term.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let catalog = self.catalog.lock().await;
+        let popularity = |id: &str| catalog.popularity.get(id).copied().unwrap_or(0);
+
+        let mut candidates: HashMap<String, u64> = HashMap::new();
+        for product in catalog.products.values() {
+            let mut product = product.clone();
+            self.localize(&mut product, locale);
+            for text in [product.name, product.category_name] {
+                *candidates.entry(text).or_default() += popularity(&product.id);
+            }
+        }
+
+        let mut matches: Vec<(u8, u64, String)> = candidates
+            .into_iter()
+            .filter_map(|(text, score)| Some((suggestion_rank(&text, &term)?, score, text)))
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then_with(|| a.2.cmp(&b.2)));
+
+        matches
+            .into_iter()
+            .map(|(_, _, text)| text)
+            .take(limit)
+            .collect()
+    }
+
+    /// Records interest in a product (see `VIEW_POPULARITY` and `PURCHASE_POPULARITY`).
+    pub async fn record_popularity(&self, product_id: &str, points: u64) {
+        let mut catalog = self.catalog.lock().await;
+        if catalog.products.contains_key(product_id) {
+            *catalog.popularity.entry(product_id.to_string()).or_default() += points;
+        }
+    }
+
+    /// Checks that the product storage is reachable (used by readiness probes).
+    pub async fn ping(&self) -> Result<(), ProductError> {
+        let _catalog = self.catalog.lock().await;
+        Ok(())
+    }
+}
+
+/// Indexes `product` under its category's names as well as its own text.
+fn index_product(index: &mut SearchIndex, categories: &CategoryService, product: &Product) {
+    let names = categories
+        .get(&product.category_slug)
+        .map(|c| [c.name_fr, c.name_en])
+        .unwrap_or_default();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    index.insert_with_categories(product, &names);
 }
 
+/// How well `text` completes the folded search `term`: `0` if it starts with it, `1` if
+/// one of its words does, `2` if the last word typed is a near-miss of a word's prefix.
+fn suggestion_rank(text: &str, term: &str) -> Option<u8> {
+    let folded = fold(text);
+    if folded.starts_with(term) {
+        return Some(0);
+    }
+
+    let mut previous_alphanumeric = false;
+    let starts_a_word_with_term = folded.char_indices().any(|(i, c)| {
+        let word_start = c.is_alphanumeric() && !previous_alphanumeric;
+        previous_alphanumeric = c.is_alphanumeric();
+        word_start && folded[i..].starts_with(term)
+    });
+    if starts_a_word_with_term {
+        return Some(1);
+    }
 
-#[derive(Debug, Deserialize, Serialize)]
+    let last_word = term.rsplit(|c: char| !c.is_alphanumeric()).next()?;
+    let typos = max_typos(last_word);
+    if typos == 0 {
+        return None;
+    }
+    let typed_len = last_word.chars().count();
+    folded
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| {
+            let prefix: String = word.chars().take(typed_len).collect();
+            within_typos(last_word, &prefix, typos) || within_typos(last_word, word, typos)
+        })
+        .then_some(2)
+}
+
+impl Default for ProductService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// Result ordering for product listings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductSort {
+    /// Best match for `query` first (the default); catalog order when there is no `query`.
+    Relevance,
+    PriceAsc,
+    PriceDesc,
+    Newest,
+    /// Highest average review score first; unreviewed products last.
+    Rating,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ProductQuery {
     pub query: Option<String>,
+    /// Category slug or name; products in its subcategories match too.
     pub category: Option<String>,
+    /// Slugs of `category` and its descendants, resolved by the handler.
+    #[serde(skip)]
+    #[param(ignore)]
+    pub category_scope: Option<HashSet<String>>,
     pub min_price: Option<f64>,
     pub max_price: Option<f64>,
+    /// Region code or French/English name, e.g. `CM-OU`, `Ouest` or `West`.
     pub region: Option<String>,
+    /// Department code or name, e.g. `Mifi`.
+    pub department: Option<String>,
+    /// Town code or name, e.g. `Bafoussam`.
+    pub town: Option<String>,
+    /// Town code or name; keeps products sold within `radius_km` of it.
+    pub near: Option<String>,
+    /// Search radius for `near`, up to 500 km (default 50).
+    pub radius_km: Option<f64>,
     pub certified: Option<bool>,
+    /// Minimum average review score, 1 to 5; unreviewed products are left out.
+    pub min_rating: Option<f64>,
+    pub sort: Option<ProductSort>,
+    /// 1-based page number; ignored when `cursor` is set.
     pub page: Option<usize>,
+    /// Page size, 1 to 50 (default 10).
+    pub limit: Option<usize>,
+    /// `next_cursor` from the previous response, for stable infinite scrolling.
+    pub cursor: Option<String>,
+    /// Include per-value counts for the filter panel (`facets=true`).
+    pub facets: Option<bool>,
+}
+
+impl ProductQuery {
+    /// Rejects location filters that name no known region, department or town, and
+    /// out-of-range radius or rating filters.
+    pub fn validate_filters(&self) -> Result<(), ProductError> {
+        let geography = geography::cameroon();
+        let unknown = |kind: &str, unknown_fr: &str, value: &str| {
+            ProductError::InvalidQuery(Translated::new(
+                format!("unknown {kind} {value:?}"),
+                format!("{unknown_fr} {value:?}"),
+            ))
+        };
+        if let Some(ref region) = self.region
+            && geography.region(region).is_none()
+        {
+            return Err(unknown("region", "région inconnue", region));
+        }
+        if let Some(ref department) = self.department
+            && geography.department(department).is_none()
+        {
+            return Err(unknown("department", "département inconnu", department));
+        }
+        for town in [&self.town, &self.near].into_iter().flatten() {
+            if geography.town(town).is_none() {
+                return Err(unknown("town", "ville inconnue", town));
+            }
+        }
+        if let Some(radius) = self.radius_km
+            && !(radius > 0.0 && radius <= MAX_NEAR_RADIUS_KM)
+        {
+            return Err(ProductError::InvalidQuery(Translated::new(
+                format!("radius_km must be greater than 0 and at most {MAX_NEAR_RADIUS_KM}"),
+                format!("radius_km doit être supérieur à 0 et au plus {MAX_NEAR_RADIUS_KM}"),
+            )));
+        }
+        if let Some(min_rating) = self.min_rating
+            && !(1.0..=5.0).contains(&min_rating)
+        {
+            return Err(ProductError::InvalidQuery(Translated::new(
+                "min_rating must be between 1 and 5",
+                "min_rating doit être compris entre 1 et 5",
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether `product` passes the category, price, location, certified and rating
+    /// filters. The filter for `except`, if any, is skipped (used to compute facet counts).
+    pub fn matches_filters(&self, product: &Product, except: Option<FacetField>) -> bool {
+        let applies = |field| except != Some(field);
+
+        if applies(FacetField::Category)
+            && let Some(ref category) = self.category
+        {
+            let matches = match self.category_scope {
+                Some(ref scope) => scope.contains(&product.category_slug),
+                None => product.category.to_lowercase() == category.to_lowercase(),
+            };
+            if !matches {
+                return false;
+            }
+        }
+        if applies(FacetField::Price)
+            && let Some(min_price) = self.min_price
+            && product.price < min_price
+        {
+            return false;
+        }
+        if applies(FacetField::Price)
+            && let Some(max_price) = self.max_price
+            && product.price > max_price
+        {
+            return false;
+        }
+        if applies(FacetField::Region)
+            && let Some(ref region) = self.region
+            && !same_region(&product.region, region)
+        {
+            return false;
+        }
+        if self.department.is_some() || self.town.is_some() || self.near.is_some() {
+            let geography = geography::cameroon();
+            let Some(locality) = geography.locate(product) else {
+                return false;
+            };
+            if let Some(ref department) = self.department
+                && geography
+                    .department(department)
+                    .is_none_or(|(_, d)| d.code != locality.department.code)
+            {
+                return false;
+            }
+            if let Some(ref town) = self.town
+                && geography
+                    .town(town)
+                    .is_none_or(|t| t.town.code != locality.town.code)
+            {
+                return false;
+            }
+            if let Some(ref near) = self.near {
+                let radius = self.radius_km.unwrap_or(DEFAULT_NEAR_RADIUS_KM);
+                if geography
+                    .town(near)
+                    .is_none_or(|t| t.town.distance_km(locality.town) > radius)
+                {
+                    return false;
+                }
+            }
+        }
+        if applies(FacetField::Certified)
+            && let Some(certified) = self.certified
+            && product.certified != certified
+        {
+            return false;
+        }
+        if let Some(min_rating) = self.min_rating
+            && product.average_rating.is_none_or(|rating| rating < min_rating)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Compares a product's region with a region filter by code or either name, falling
+/// back to a case-insensitive comparison for regions outside the hierarchy.
+fn same_region(product_region: &str, filter: &str) -> bool {
+    let geography = geography::cameroon();
+    match (geography.region(product_region), geography.region(filter)) {
+        (Some(a), Some(b)) => a.code == b.code,
+        _ => product_region.to_lowercase() == filter.to_lowercase(),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SuggestionQuery {
+    /// What the buyer has typed so far.
+    pub term: String,
+    /// Maximum number of suggestions (default 8, at most 20).
     pub limit: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PaginatedResponse<T> {
     pub page: usize,
     pub limit: usize,
     pub total: usize,
     pub products: Vec<T>,
+    /// Token for the page after this one; absent on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Present only when requested with `facets=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facets: Option<Facets>,
 }
 
 // mock_product
@@ -74,16 +845,55 @@ pub fn mock_products() -> Vec<Product> {
             name: "Bamileke Stool".to_string(),
             price: 15000.0,
             category: "Furniture".to_string(),
+            category_slug: "furniture".to_string(),
             region: "Ouest".to_string(),
+            town: Some("CM-OU-BAFOUSSAM".to_string()),
             certified: true,
+            description: "Hand-carved wooden stool from the Grassfields, decorated with traditional Bamiléké motifs.".to_string(),
+            tags: vec!["wood".to_string(), "carving".to_string(), "artisanat".to_string()],
+            vendor_name: "Atelier Fotso".to_string(),
+            created_at: Utc.with_ymd_and_hms(2025, 3, 14, 9, 0, 0).unwrap(),
+            materials: vec!["iroko wood".to_string()],
+            dimensions: Some(Dimensions {
+                length_cm: 40.0,
+                width_cm: 40.0,
+                height_cm: 45.0,
+            }),
+            weight_grams: Some(6_500),
+            stock: Some(4),
+            translations: BTreeMap::from([(
+                Locale::Fr,
+                ProductText {
+                    name: "Tabouret bamiléké".to_string(),
+                    description: "Tabouret en bois sculpté à la main dans les Grassfields, orné de motifs bamiléké traditionnels.".to_string(),
+                },
+            )]),
+            ..Product::default()
         },
         Product {
             id: "3".to_string(),
             name: "Cameroon T-shirt".to_string(),
             price: 5000.0,
             category: "Clothing".to_string(),
+            category_slug: "clothing".to_string(),
             region: "Centre".to_string(),
+            town: Some("CM-CE-YAOUNDE".to_string()),
             certified: false,
+            description: "Cotton T-shirt printed in Yaoundé with the national colours.".to_string(),
+            tags: vec!["cotton".to_string(), "apparel".to_string()],
+            vendor_name: "Yaoundé Prints".to_string(),
+            created_at: Utc.with_ymd_and_hms(2025, 4, 2, 15, 30, 0).unwrap(),
+            materials: vec!["cotton".to_string()],
+            weight_grams: Some(180),
+            stock: Some(50),
+            translations: BTreeMap::from([(
+                Locale::Fr,
+                ProductText {
+                    name: "T-shirt Cameroun".to_string(),
+                    description: "T-shirt en coton imprimé à Yaoundé aux couleurs nationales.".to_string(),
+                },
+            )]),
+            ..Product::default()
         },
     ]
 }
@@ -0,0 +1,32 @@
+use axum::{
+    Router,
+    extract::{Extension, Json},
+    routing::get,
+};
+
+use crate::{
+    api::openapi::ErrorMessage, models::notification::Notification,
+    services::notification_service::NotificationError, state::AppState,
+};
+
+pub fn notification_routes() -> Router {
+    Router::new().route("/api/me/notifications", get(list_notifications))
+}
+
+/// GET `/api/me/notifications`
+#[utoipa::path(
+    get,
+    path = "/api/me/notifications",
+    tag = "notifications",
+    responses(
+        (status = 200, description = "The current user's notifications, newest first", body = Vec<Notification>),
+        (status = 500, description = "Notification storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn list_notifications(
+    Extension(state): Extension<AppState>,
+) -> Result<Json<Vec<Notification>>, NotificationError> {
+    let user_id = "user123".to_string();
+
+    Ok(Json(state.notification_service.for_user(&user_id)?))
+}
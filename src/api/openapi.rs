@@ -0,0 +1,297 @@
+use axum::{Json, Router, routing::get};
+use utoipa::{OpenApi, ToSchema};
+
+use super::{
+    cart::{CartItem, CartRequest, CouponRequest, GuestCart},
+    checkout::{CheckoutRequest, PaymentCallback},
+    health::{CheckResult, ReadinessReport},
+    certifications::{ApproveRequest, DecisionRequest},
+    facets::{FacetCount, Facets, PriceBucket},
+    images::{ImageOrderRequest, ImageUpload},
+    listings::ModerationDecision,
+    preferences::Preferences,
+    reviews::{ProductReviews, ReplyRequest, ReviewPhotoUpload, ReviewRequest},
+    wishlist::WishlistRequest,
+    promotions::PromotionRequest,
+    model::{
+        Dimensions, ImageFormatKind, PaginatedResponse, Product, ProductImage, ProductText,
+        ProductVariant, Thumbnail,
+    },
+};
+use crate::models::{
+    category::{AttributeKind, AttributeSchema, Category},
+    locale::Locale,
+    certification::{
+        Certification, CertificationEvent, CertificationEvidence, CertificationStatus,
+    },
+    moderation::{Blocklist, ListingAction, ModerationEntry, ModerationStatus},
+    order::{Order, OrderLine, OrderStatus},
+    review::{RatingSummary, Review, VendorReply},
+    cart_summary::{
+        CartLine, CartSummary, CartWarning, CartWarningKind, DeliveryEstimate, Discount,
+    },
+    wishlist::WishlistItem,
+    cart_metrics::CartMetrics,
+    promotion::{DiscountKind, Promotion},
+    cart_sync::{
+        CartOperation, CartOperationKind, CartSyncRequest, CartSyncResponse, OperationOutcome,
+        OperationStatus,
+    },
+    notification::{Notification, NotificationKind},
+};
+use crate::services::geography::{Department, Region, Town};
+
+/// Body of error responses: a plain-text (`text/plain`), human-readable message in the
+/// negotiated language, which `Content-Language` names. Clients branch on the status code
+/// and show the text as is; the few errors with a JSON body (a stale cart's 412, an
+/// unready 503) document their own schema.
+#[derive(ToSchema)]
+#[schema(example = "Product not found")]
+pub struct ErrorMessage(pub String);
+
+/// OpenAPI 3 description of the public HTTP API, generated from the handler annotations.
+///
+/// The committed copy lives in `openapi.json` at the repository root; regenerate it with
+/// `UPDATE_OPENAPI=1 cargo test openapi` after changing any annotated type or handler.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Made in Cameroon Marketplace API",
+        description = "Marketplace for locally made products, paid with MTN and Orange mobile money."
+    ),
+    paths(
+        super::handler::search_products,
+        super::handler::get_product,
+        super::handler::search_suggestions,
+        super::handler::products_by_category,
+        super::handler::list_regions,
+        super::categories::list_categories,
+        super::categories::category_attributes,
+        super::categories::create_category,
+        super::categories::update_category,
+        super::categories::delete_category,
+        super::cart::get_cart,
+        super::cart::add_to_cart,
+        super::cart::update_cart,
+        super::cart::remove_from_cart,
+        super::cart::create_guest_cart,
+        super::cart::merge_guest_cart,
+        super::cart::sync_cart,
+        super::cart::apply_coupon,
+        super::cart::remove_coupon,
+        super::cart::cart_metrics,
+        super::checkout::checkout,
+        super::checkout::payment_callback,
+        super::orders::list_orders,
+        super::orders::view_order,
+        super::orders::cancel_order,
+        super::orders::ship_order,
+        super::orders::deliver_order,
+        super::images::upload_image,
+        super::images::reorder_images,
+        super::images::serve_image,
+        super::certifications::submit_certification,
+        super::certifications::product_certifications,
+        super::certifications::list_certifications,
+        super::certifications::approve_certification,
+        super::certifications::reject_certification,
+        super::certifications::revoke_certification,
+        super::listings::create_listing,
+        super::listings::update_listing,
+        super::listings::list_moderation,
+        super::listings::approve_listing,
+        super::listings::reject_listing,
+        super::listings::get_blocklist,
+        super::listings::set_blocklist,
+        super::preferences::get_preferences,
+        super::preferences::set_preferences,
+        super::reviews::list_reviews,
+        super::reviews::create_review,
+        super::reviews::upload_review_photo,
+        super::reviews::reply_to_review,
+        super::wishlist::get_wishlist,
+        super::wishlist::add_to_wishlist,
+        super::wishlist::remove_from_wishlist,
+        super::wishlist::move_to_cart,
+        super::wishlist::move_from_cart,
+        super::notifications::list_notifications,
+        super::promotions::create_vendor_promotion,
+        super::promotions::create_platform_promotion,
+        super::promotions::list_promotions,
+        super::health::liveness,
+        super::health::readiness,
+    ),
+    components(schemas(
+        ErrorMessage,
+        Product,
+        ProductImage,
+        Thumbnail,
+        ImageFormatKind,
+        ImageUpload,
+        ImageOrderRequest,
+        ProductVariant,
+        Dimensions,
+        PaginatedResponse<Product>,
+        Facets,
+        FacetCount,
+        PriceBucket,
+        Category,
+        AttributeSchema,
+        AttributeKind,
+        Region,
+        Department,
+        Town,
+        CartItem,
+        CartRequest,
+        GuestCart,
+        CartSummary,
+        CartLine,
+        Discount,
+        DeliveryEstimate,
+        CartWarning,
+        CartWarningKind,
+        CartMetrics,
+        CouponRequest,
+        Promotion,
+        DiscountKind,
+        PromotionRequest,
+        CartOperation,
+        CartOperationKind,
+        CartSyncRequest,
+        CartSyncResponse,
+        OperationOutcome,
+        OperationStatus,
+        CheckoutRequest,
+        PaymentCallback,
+        Order,
+        OrderLine,
+        OrderStatus,
+        Certification,
+        CertificationEvidence,
+        CertificationEvent,
+        CertificationStatus,
+        ApproveRequest,
+        DecisionRequest,
+        ModerationEntry,
+        ModerationStatus,
+        ModerationDecision,
+        ListingAction,
+        Blocklist,
+        Preferences,
+        Locale,
+        ProductText,
+        Review,
+        VendorReply,
+        RatingSummary,
+        ProductReviews,
+        ReviewRequest,
+        ReplyRequest,
+        ReviewPhotoUpload,
+        WishlistItem,
+        WishlistRequest,
+        Notification,
+        NotificationKind,
+        ReadinessReport,
+        CheckResult,
+    )),
+    tags(
+        (name = "products", description = "Catalog search"),
+        (name = "cart", description = "Shopping cart"),
+        (name = "checkout", description = "Checkout and mobile money payment"),
+        (name = "orders", description = "Order history"),
+        (name = "categories", description = "Category tree and attribute schemas"),
+        (name = "images", description = "Product image upload and delivery"),
+        (name = "listings", description = "Vendor listings and foreign-brand moderation"),
+        (name = "certifications", description = "\"Made in Cameroon\" certification review"),
+        (name = "reviews", description = "Verified-buyer reviews, ratings and vendor replies"),
+        (name = "wishlist", description = "Saved-for-later items and moves to and from the cart"),
+        (name = "notifications", description = "Price-drop and back-in-stock alerts"),
+        (name = "promotions", description = "Coupons and automatic discounts run by vendors or the platform"),
+        (name = "preferences", description = "User settings such as the preferred language"),
+        (name = "health", description = "Liveness and readiness probes"),
+    )
+)]
+pub struct ApiDoc;
+
+pub fn openapi_routes() -> Router {
+    Router::new().route("/api/openapi.json", get(openapi_json))
+}
+
+/// GET `/api/openapi.json`
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+    use utoipa::OpenApi;
+
+    use super::{ApiDoc, openapi_routes};
+
+    const SPEC_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+    #[test]
+    fn test_committed_spec_matches_code() {
+        let generated = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(SPEC_PATH, &generated).unwrap();
+            return;
+        }
+
+        let committed = std::fs::read_to_string(SPEC_PATH).unwrap_or_default();
+        assert!(
+            committed == generated,
+            "openapi.json is out of date; run `UPDATE_OPENAPI=1 cargo test openapi` and commit the result"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_serves_openapi_json() {
+        let response = openapi_routes()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/openapi.json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        let spec: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(spec["paths"]["/api/products"]["get"].is_object());
+        assert!(spec["components"]["schemas"]["Product"].is_object());
+    }
+
+    #[test]
+    fn test_error_responses_reference_error_message() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let error_message = &spec["components"]["schemas"]["ErrorMessage"];
+        assert_eq!(error_message["type"], "string");
+        assert!(error_message["example"].is_string());
+
+        for (path, operations) in spec["paths"].as_object().unwrap() {
+            for (method, operation) in operations.as_object().unwrap() {
+                for (status, response) in operation["responses"].as_object().unwrap() {
+                    let text = &response["content"]["text/plain"]["schema"]["$ref"];
+                    if status.starts_with(['4', '5']) && !text.is_null() {
+                        assert_eq!(
+                            text, "#/components/schemas/ErrorMessage",
+                            "{method} {path} {status}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -4,52 +4,127 @@ use axum::{
     Router,
     Json,
 };
+use crate::api::openapi::ErrorMessage;
 use crate::state::AppState;
-use crate::models::order::Order;
+use crate::models::order::{Order, OrderStatus};
 use crate::services::checkout_service::CheckoutError;
-use axum::http::StatusCode;
 
 pub fn order_routes() -> Router {
     Router::new()
         .route("/api/orders", get(list_orders))
-        .route("/api/orders/:order_id", get(view_order))
-        .route("/api/orders/:order_id/cancel", post(cancel_order))
+        .route("/api/orders/{order_id}", get(view_order))
+        .route("/api/orders/{order_id}/cancel", post(cancel_order))
+        .route("/api/admin/orders/{order_id}/ship", post(ship_order))
+        .route("/api/admin/orders/{order_id}/deliver", post(deliver_order))
 }
 
+/// GET `/api/orders`
+#[utoipa::path(
+    get,
+    path = "/api/orders",
+    tag = "orders",
+    responses(
+        (status = 200, description = "Orders of the current user", body = Vec<Order>),
+        (status = 500, description = "Order storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
 async fn list_orders(
     Extension(state): Extension<AppState>,
-) -> Result<Json<Vec<Order>>, StatusCode> {
+) -> Result<Json<Vec<Order>>, CheckoutError> {
     let user_id = "user123".to_string(); // dummy for now
 
-    let orders = state.checkout_service.get_user_orders(&user_id)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let orders = state.checkout_service.get_user_orders(&user_id)?;
 
     Ok(Json(orders))
 }
 
+/// GET `/api/orders/{order_id}`
+#[utoipa::path(
+    get,
+    path = "/api/orders/{order_id}",
+    tag = "orders",
+    params(("order_id" = String, Path, description = "Order identifier")),
+    responses(
+        (status = 200, description = "The order", body = Order),
+        (status = 404, description = "Order not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 500, description = "Order storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
 async fn view_order(
     Extension(state): Extension<AppState>,
     Path(order_id): Path<String>,
-) -> Result<Json<Order>, StatusCode> {
-    let order = state.checkout_service.get_order_by_id(&order_id)
-        .map_err(|err| match err {
-            CheckoutError::OrderNotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })?;
+) -> Result<Json<Order>, CheckoutError> {
+    let order = state.checkout_service.get_order_by_id(&order_id)?;
 
     Ok(Json(order))
 }
 
+/// POST `/api/orders/{order_id}/cancel`
+#[utoipa::path(
+    post,
+    path = "/api/orders/{order_id}/cancel",
+    tag = "orders",
+    params(("order_id" = String, Path, description = "Order identifier")),
+    responses(
+        (status = 200, description = "Order cancelled", body = String),
+        (status = 400, description = "Order is no longer pending payment", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Order not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 500, description = "Order storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
 async fn cancel_order(
     Extension(state): Extension<AppState>,
     Path(order_id): Path<String>,
-) -> Result<Json<&'static str>, StatusCode> {
-    state.checkout_service.cancel_order(&order_id)
-        .map_err(|err| match err {
-            CheckoutError::OrderNotFound => StatusCode::NOT_FOUND,
-            CheckoutError::CannotCancelOrder => StatusCode::BAD_REQUEST,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })?;
+) -> Result<Json<&'static str>, CheckoutError> {
+    state.checkout_service.cancel_order(&order_id)?;
 
     Ok(Json("Order cancelled"))
 }
+
+/// Marks a paid order as handed to the courier.
+///
+/// POST `/api/admin/orders/{order_id}/ship`
+#[utoipa::path(
+    post,
+    path = "/api/admin/orders/{order_id}/ship",
+    tag = "orders",
+    params(("order_id" = String, Path, description = "Order identifier")),
+    responses(
+        (status = 200, description = "Order shipped", body = Order),
+        (status = 404, description = "Order not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Order is not paid", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn ship_order(
+    Extension(state): Extension<AppState>,
+    Path(order_id): Path<String>,
+) -> Result<Json<Order>, CheckoutError> {
+    let order = state
+        .checkout_service
+        .advance_fulfilment(&order_id, OrderStatus::Shipped)?;
+    Ok(Json(order))
+}
+
+/// Marks a paid or shipped order as received by the buyer, who can then review it.
+///
+/// POST `/api/admin/orders/{order_id}/deliver`
+#[utoipa::path(
+    post,
+    path = "/api/admin/orders/{order_id}/deliver",
+    tag = "orders",
+    params(("order_id" = String, Path, description = "Order identifier")),
+    responses(
+        (status = 200, description = "Order delivered", body = Order),
+        (status = 404, description = "Order not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Order is neither paid nor shipped", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn deliver_order(
+    Extension(state): Extension<AppState>,
+    Path(order_id): Path<String>,
+) -> Result<Json<Order>, CheckoutError> {
+    let order = state
+        .checkout_service
+        .advance_fulfilment(&order_id, OrderStatus::Delivered)?;
+    Ok(Json(order))
+}
@@ -0,0 +1,139 @@
+use std::cmp::Ordering;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+
+use super::model::{Product, ProductError, ProductSort};
+
+pub const DEFAULT_PAGE_SIZE: usize = 10;
+pub const MAX_PAGE_SIZE: usize = 50;
+
+/// Position in a sorted listing: the sort key and id of the last product already returned.
+///
+/// Resuming "strictly after this key" (rather than skipping N rows) keeps infinite scroll
+/// from skipping or repeating items when products are added or removed between pages.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cursor {
+    sort: ProductSort,
+    key: f64,
+    id: String,
+}
+
+impl Cursor {
+    pub fn after(product: &Product, score: f64, sort: ProductSort) -> Self {
+        Cursor {
+            sort,
+            key: sort_key(product, score, sort),
+            id: product.id.clone(),
+        }
+    }
+
+    /// Opaque, URL-safe token for `next_cursor`.
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).expect("cursor serializes"))
+    }
+
+    /// Parses a token from `encode`, rejecting tokens issued for a different sort order.
+    pub fn decode(token: &str, sort: ProductSort) -> Result<Self, ProductError> {
+        let cursor: Cursor = URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or(ProductError::InvalidCursor)?;
+        if cursor.sort != sort {
+            return Err(ProductError::InvalidCursor);
+        }
+        Ok(cursor)
+    }
+
+    /// Whether `product` comes strictly after this cursor in the listing order.
+    pub fn precedes(&self, product: &Product, score: f64) -> bool {
+        compare_keys(
+            self.sort,
+            (sort_key(product, score, self.sort), &product.id),
+            (self.key, &self.id),
+        ) == Ordering::Greater
+    }
+}
+
+/// The value a listing is ordered by; `score` is the search relevance of `product`.
+fn sort_key(product: &Product, score: f64, sort: ProductSort) -> f64 {
+    match sort {
+        ProductSort::Relevance => score,
+        ProductSort::PriceAsc | ProductSort::PriceDesc => product.price,
+        ProductSort::Newest => product.created_at.timestamp_millis() as f64,
+        ProductSort::Rating => product.average_rating.unwrap_or(0.0),
+    }
+}
+
+fn compare_keys(sort: ProductSort, a: (f64, &String), b: (f64, &String)) -> Ordering {
+    let by_key = match sort {
+        ProductSort::PriceAsc => a.0.total_cmp(&b.0),
+        ProductSort::Relevance
+        | ProductSort::PriceDesc
+        | ProductSort::Newest
+        | ProductSort::Rating => b.0.total_cmp(&a.0),
+    };
+    // Ties are broken by id so the order is total and cursors are unambiguous.
+    by_key.then_with(|| a.1.cmp(b.1))
+}
+
+/// Sorts `(product, relevance score)` pairs into listing order.
+pub fn sort_products(products: &mut [(Product, f64)], sort: ProductSort) {
+    products.sort_by(|(a, a_score), (b, b_score)| {
+        compare_keys(
+            sort,
+            (sort_key(a, *a_score, sort), &a.id),
+            (sort_key(b, *b_score, sort), &b.id),
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cursor, sort_products};
+    use crate::api::model::{Product, ProductSort};
+
+    fn product(id: &str, price: f64) -> (Product, f64) {
+        let product = Product {
+            id: id.to_string(),
+            price,
+            ..Product::default()
+        };
+        (product, 0.0)
+    }
+
+    #[test]
+    fn test_cursor_resumes_after_last_item_despite_inserts() {
+        let mut products = vec![
+            product("a", 300.0),
+            product("b", 100.0),
+            product("c", 200.0),
+        ];
+        sort_products(&mut products, ProductSort::PriceAsc);
+        let ids: Vec<&str> = products.iter().map(|(p, _)| p.id.as_str()).collect();
+        assert_eq!(ids, ["b", "c", "a"]);
+
+        let token = Cursor::after(&products[1].0, 0.0, ProductSort::PriceAsc).encode();
+
+        // A cheaper product is added before the next page is fetched: it must not shift
+        // the next page, which resumes strictly after "c".
+        products.push(product("d", 50.0));
+        let cursor = Cursor::decode(&token, ProductSort::PriceAsc).unwrap();
+        let rest: Vec<&str> = products
+            .iter()
+            .filter(|(p, score)| cursor.precedes(p, *score))
+            .map(|(p, _)| p.id.as_str())
+            .collect();
+        assert_eq!(rest, ["a"]);
+    }
+
+    #[test]
+    fn test_cursor_rejects_other_sort_and_garbage() {
+        let (p, _) = product("a", 100.0);
+        let token = Cursor::after(&p, 0.0, ProductSort::PriceAsc).encode();
+
+        assert!(Cursor::decode(&token, ProductSort::PriceDesc).is_err());
+        assert!(Cursor::decode("not-a-cursor", ProductSort::PriceAsc).is_err());
+    }
+}
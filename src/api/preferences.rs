@@ -0,0 +1,62 @@
+use axum::{
+    Router,
+    extract::{Extension, Json},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    models::locale::Locale, services::preference_service::PreferenceError, state::AppState,
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Preferences {
+    /// Language for product content and messages; `null` follows `Accept-Language`.
+    pub language: Option<Locale>,
+}
+
+pub fn preference_routes() -> Router {
+    Router::new().route(
+        "/api/me/preferences",
+        get(get_preferences).put(set_preferences),
+    )
+}
+
+/// GET `/api/me/preferences`
+#[utoipa::path(
+    get,
+    path = "/api/me/preferences",
+    tag = "preferences",
+    responses((status = 200, description = "The current user's preferences", body = Preferences))
+)]
+pub async fn get_preferences(
+    Extension(state): Extension<AppState>,
+) -> Result<Json<Preferences>, PreferenceError> {
+    let user_id = "user123".to_string();
+
+    let language = state.preference_service.language(&user_id)?;
+    Ok(Json(Preferences { language }))
+}
+
+/// Saves the user's language; it then takes precedence over `Accept-Language`.
+///
+/// PUT `/api/me/preferences`
+#[utoipa::path(
+    put,
+    path = "/api/me/preferences",
+    tag = "preferences",
+    request_body = Preferences,
+    responses((status = 200, description = "Preferences saved", body = Preferences))
+)]
+pub async fn set_preferences(
+    Extension(state): Extension<AppState>,
+    Json(preferences): Json<Preferences>,
+) -> Result<Json<Preferences>, PreferenceError> {
+    let user_id = "user123".to_string();
+
+    state
+        .preference_service
+        .set_language(&user_id, preferences.language)?;
+    Ok(Json(preferences))
+}
@@ -0,0 +1,383 @@
+use std::collections::BTreeSet;
+
+use axum::{
+    Router,
+    extract::{Extension, Json},
+    http::StatusCode,
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::{model::ProductError, openapi::ErrorMessage},
+    models::{
+        locale::Translated,
+        promotion::{DiscountKind, Promotion},
+    },
+    services::{category_service::CategoryError, promotion_service::PromotionError},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PromotionRequest {
+    /// 3 to 32 letters, digits or dashes. Leave out for a promotion that applies without
+    /// a code.
+    #[serde(default)]
+    pub code: Option<String>,
+    pub label: String,
+    pub kind: DiscountKind,
+    /// Percent off (up to 100) or FCFA off.
+    pub value: f64,
+    /// Required from vendors; admins leave it out for a platform-wide promotion.
+    #[serde(default)]
+    pub vendor_name: Option<String>,
+    #[serde(default)]
+    pub min_spend: Option<f64>,
+    #[serde(default)]
+    pub per_user_limit: Option<u32>,
+    /// Defaults to now.
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: DateTime<Utc>,
+    /// Category slugs; their subcategories are included.
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub product_ids: Vec<String>,
+}
+
+pub fn promotion_routes() -> Router {
+    Router::new()
+        .route("/api/vendor/promotions", post(create_vendor_promotion))
+        .route(
+            "/api/admin/promotions",
+            get(list_promotions).post(create_platform_promotion),
+        )
+}
+
+/// Starts a promotion on the vendor's products, e.g. for a festival.
+///
+/// POST `/api/vendor/promotions`
+#[utoipa::path(
+    post,
+    path = "/api/vendor/promotions",
+    tag = "promotions",
+    request_body = PromotionRequest,
+    responses(
+        (status = 201, description = "Promotion created", body = Promotion),
+        (status = 400, description = "Missing vendor, invalid terms or targets not sold by the vendor", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Coupon code taken", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn create_vendor_promotion(
+    Extension(state): Extension<AppState>,
+    Json(payload): Json<PromotionRequest>,
+) -> Result<(StatusCode, Json<Promotion>), PromotionError> {
+    if payload.vendor_name.is_none() {
+        return Err(PromotionError::InvalidPromotion(Translated::new(
+            "vendor_name is required",
+            "vendor_name est obligatoire",
+        )));
+    }
+    create_promotion(&state, payload).await
+}
+
+/// Starts a promotion run by the platform, on any vendor's products unless one is named.
+///
+/// POST `/api/admin/promotions`
+#[utoipa::path(
+    post,
+    path = "/api/admin/promotions",
+    tag = "promotions",
+    request_body = PromotionRequest,
+    responses(
+        (status = 201, description = "Promotion created", body = Promotion),
+        (status = 400, description = "Invalid terms or unknown targets", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Coupon code taken", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn create_platform_promotion(
+    Extension(state): Extension<AppState>,
+    Json(payload): Json<PromotionRequest>,
+) -> Result<(StatusCode, Json<Promotion>), PromotionError> {
+    create_promotion(&state, payload).await
+}
+
+/// GET `/api/admin/promotions`
+#[utoipa::path(
+    get,
+    path = "/api/admin/promotions",
+    tag = "promotions",
+    responses(
+        (status = 200, description = "All promotions, in the order they apply", body = [Promotion]),
+    )
+)]
+pub async fn list_promotions(
+    Extension(state): Extension<AppState>,
+) -> Result<Json<Vec<Promotion>>, PromotionError> {
+    Ok(Json(state.promotion_service.list()?))
+}
+
+/// Resolves the targeted categories to their subtrees and checks the targeted products
+/// exist and, for a vendor promotion, belong to the vendor.
+async fn create_promotion(
+    state: &AppState,
+    payload: PromotionRequest,
+) -> Result<(StatusCode, Json<Promotion>), PromotionError> {
+    let mut categories = BTreeSet::new();
+    for slug in &payload.categories {
+        match state.product_service.categories().subtree(slug) {
+            Ok(subtree) => categories.extend(subtree),
+            Err(CategoryError::NotFound) => {
+                return Err(PromotionError::InvalidPromotion(Translated::new(
+                    format!("unknown category {slug}"),
+                    format!("catégorie inconnue : {slug}"),
+                )));
+            }
+            Err(_) => return Err(PromotionError::LockError),
+        }
+    }
+    for product_id in &payload.product_ids {
+        let product = match state.product_service.get_product_by_id(product_id).await {
+            Ok(product) => product,
+            Err(ProductError::ProductNotFound) => {
+                return Err(PromotionError::InvalidPromotion(Translated::new(
+                    format!("unknown product {product_id}"),
+                    format!("produit inconnu : {product_id}"),
+                )));
+            }
+            Err(_) => return Err(PromotionError::LockError),
+        };
+        if let Some(vendor) = &payload.vendor_name
+            && product.vendor_name != *vendor
+        {
+            return Err(PromotionError::InvalidPromotion(Translated::new(
+                format!("product {product_id} is not sold by {vendor}"),
+                format!("le produit {product_id} n'est pas vendu par {vendor}"),
+            )));
+        }
+    }
+
+    let promotion = state.promotion_service.create(Promotion {
+        promotion_id: Uuid::new_v4().to_string(),
+        code: payload.code,
+        label: payload.label,
+        kind: payload.kind,
+        value: payload.value,
+        vendor_name: payload.vendor_name,
+        min_spend: payload.min_spend,
+        per_user_limit: payload.per_user_limit,
+        starts_at: payload.starts_at.unwrap_or_else(Utc::now),
+        ends_at: payload.ends_at,
+        categories: categories.into_iter().collect(),
+        product_ids: payload.product_ids,
+    })?;
+    Ok((StatusCode::CREATED, Json(promotion)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        Extension, Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+    };
+    use chrono::{TimeDelta, Utc};
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use super::promotion_routes;
+    use crate::{
+        api::{
+            cart::{CART_TOKEN_HEADER, cart_routes},
+            checkout::checkout_routes,
+        },
+        middleware::locale::LocalizedMessage,
+        state::AppState,
+    };
+
+    fn post(uri: &str, payload: Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap()
+    }
+
+    async fn json_body(response: axum::response::Response) -> Value {
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_coupon_discounts_cart_and_order_within_its_limits() {
+        let state = AppState::default();
+        let app = Router::new()
+            .merge(cart_routes(Arc::new(state.clone())))
+            .merge(checkout_routes())
+            .merge(promotion_routes())
+            .layer(Extension(state.clone()));
+        let ends_at = Utc::now() + TimeDelta::days(7);
+
+        let stools = json!({
+            "code": "fotso10",
+            "label": "Fête des stools -10%",
+            "kind": "percentage",
+            "value": 10.0,
+            "min_spend": 20000.0,
+            "per_user_limit": 1,
+            "ends_at": ends_at,
+            "product_ids": ["2"],
+        });
+        // Vendors must name themselves, and only target their own products.
+        let response = app
+            .clone()
+            .oneshot(post("/api/vendor/promotions", stools.clone()))
+            .await;
+        assert_eq!(response.unwrap().status(), StatusCode::BAD_REQUEST);
+        let mut stools = stools;
+        stools["vendor_name"] = json!("Yaoundé Prints");
+        let response = app
+            .clone()
+            .oneshot(post("/api/vendor/promotions", stools.clone()))
+            .await;
+        assert_eq!(response.unwrap().status(), StatusCode::BAD_REQUEST);
+        stools["vendor_name"] = json!("Atelier Fotso");
+        let response = app
+            .clone()
+            .oneshot(post("/api/vendor/promotions", stools))
+            .await;
+        assert_eq!(response.unwrap().status(), StatusCode::CREATED);
+
+        let add = json!({ "product_id": "2", "quantity": 1 });
+        app.clone()
+            .oneshot(post("/api/cart/add", add))
+            .await
+            .unwrap();
+        let coupon = json!({ "code": "FOTSO10" });
+        let response = app
+            .clone()
+            .oneshot(post("/api/cart/coupon", coupon.clone()))
+            .await;
+        let cart = json_body(response.unwrap()).await;
+        assert_eq!(cart["coupon_code"], "FOTSO10");
+        // One stool is short of the minimum spend.
+        assert_eq!(cart["discount_total"], 0.0);
+
+        let add = json!({ "product_id": "2", "quantity": 1 });
+        app.clone()
+            .oneshot(post("/api/cart/add", add))
+            .await
+            .unwrap();
+        let response = app
+            .clone()
+            .oneshot(post("/api/cart/coupon", coupon.clone()))
+            .await;
+        let cart = json_body(response.unwrap()).await;
+        assert_eq!(cart["discount_total"], 3000.0);
+        assert_eq!(cart["grand_total"], 27000.0 + 1500.0);
+
+        let response = app.clone().oneshot(post("/api/checkout", json!({}))).await;
+        assert_eq!(response.unwrap().status(), StatusCode::OK);
+        let order = &state.checkout_service.get_user_orders("user123").unwrap()[0];
+        // What the cart summary showed, delivery included.
+        assert_eq!(order.total_amount, 27000.0 + 1500.0);
+        assert_eq!(order.discounts[0].code.as_deref(), Some("FOTSO10"));
+
+        // Used once already: the coupon can't be applied again and no longer discounts.
+        let response = app
+            .clone()
+            .oneshot(post("/api/cart/coupon", coupon))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let message = response.extensions().get::<LocalizedMessage>().unwrap();
+        assert_eq!(
+            message.fr,
+            "Vous avez déjà utilisé ce code promo autant de fois que permis"
+        );
+        // Nor on a guest cart: the limit is the user's, not the cart's.
+        let response = app
+            .clone()
+            .oneshot(post("/api/cart/guest", json!({})))
+            .await;
+        let guest = json_body(response.unwrap()).await;
+        let mut request = post("/api/cart/coupon", json!({ "code": "FOTSO10" }));
+        request.headers_mut().insert(
+            CART_TOKEN_HEADER,
+            guest["cart_token"].as_str().unwrap().parse().unwrap(),
+        );
+        let response = app.clone().oneshot(request).await;
+        assert_eq!(response.unwrap().status(), StatusCode::CONFLICT);
+        let response = app.clone().oneshot(post("/api/checkout", json!({}))).await;
+        assert_eq!(response.unwrap().status(), StatusCode::OK);
+        let orders = state.checkout_service.get_user_orders("user123").unwrap();
+        assert!(
+            orders
+                .iter()
+                .any(|o| o.discounts.is_empty() && o.total_amount == 30000.0 + 1500.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guest_cart_leaves_out_promotions_the_user_used_up() {
+        let state = AppState::default();
+        let app = Router::new()
+            .merge(cart_routes(Arc::new(state.clone())))
+            .merge(checkout_routes())
+            .merge(promotion_routes())
+            .layer(Extension(state.clone()));
+        let promotion = json!({
+            "label": "Bienvenue -5%",
+            "kind": "percentage",
+            "value": 5.0,
+            "per_user_limit": 1,
+            "ends_at": Utc::now() + TimeDelta::days(7),
+            "product_ids": ["2"],
+        });
+        let response = app
+            .clone()
+            .oneshot(post("/api/admin/promotions", promotion))
+            .await;
+        assert_eq!(response.unwrap().status(), StatusCode::CREATED);
+
+        let add = json!({ "product_id": "2", "quantity": 1 });
+        app.clone()
+            .oneshot(post("/api/cart/add", add.clone()))
+            .await
+            .unwrap();
+        let response = app.clone().oneshot(post("/api/checkout", json!({}))).await;
+        assert_eq!(response.unwrap().status(), StatusCode::OK);
+        let order = &state.checkout_service.get_user_orders("user123").unwrap()[0];
+        assert_eq!(order.discounts.len(), 1);
+
+        // Browsing as a guest, the user no longer gets the promotion they used.
+        let response = app
+            .clone()
+            .oneshot(post("/api/cart/guest", json!({})))
+            .await;
+        let token = json_body(response.unwrap()).await["cart_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut request = post("/api/cart/add", add);
+        request
+            .headers_mut()
+            .insert(CART_TOKEN_HEADER, token.parse().unwrap());
+        app.clone().oneshot(request).await.unwrap();
+        let request = Request::builder()
+            .uri("/api/cart")
+            .header(CART_TOKEN_HEADER, token)
+            .body(Body::empty())
+            .unwrap();
+        let cart = json_body(app.oneshot(request).await.unwrap()).await;
+        assert_eq!(cart["discount_total"], 0.0);
+        assert_eq!(cart["discounts"], json!([]));
+    }
+}
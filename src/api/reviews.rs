@@ -0,0 +1,379 @@
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, Extension, Json, Multipart, Path},
+    http::StatusCode,
+    routing::{get, post},
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{images::ImageUpload, openapi::ErrorMessage},
+    models::review::{RatingSummary, Review},
+    services::{image_service::ImageError, review_service::ReviewError},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewRequest {
+    /// 1 to 5 stars.
+    pub rating: u8,
+    /// Up to 2000 characters; may be left empty.
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReplyRequest {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductReviews {
+    pub summary: RatingSummary,
+    /// Newest first.
+    pub reviews: Vec<Review>,
+}
+
+/// Multipart form accepted by the review photo endpoint.
+#[derive(ToSchema)]
+pub struct ReviewPhotoUpload {
+    /// JPEG, PNG or WebP file.
+    #[schema(value_type = String, format = Binary)]
+    pub image: Vec<u8>,
+}
+
+impl ReviewPhotoUpload {
+    /// Reads the form like a product image upload; review photos have no alt text.
+    pub async fn read(multipart: &mut Multipart) -> Result<ReviewPhotoUpload, ImageError> {
+        let upload = ImageUpload::read(multipart).await?;
+        Ok(ReviewPhotoUpload {
+            image: upload.image,
+        })
+    }
+}
+
+/// `max_upload_request_bytes` raises the body limit of the photo upload route only.
+pub fn review_routes(max_upload_request_bytes: usize) -> Router {
+    Router::new()
+        .route(
+            "/api/products/{product_id}/reviews",
+            get(list_reviews).post(create_review),
+        )
+        .route(
+            "/api/reviews/{review_id}/photos",
+            post(upload_review_photo).layer(DefaultBodyLimit::max(max_upload_request_bytes)),
+        )
+        .route(
+            "/api/vendor/reviews/{review_id}/reply",
+            post(reply_to_review),
+        )
+}
+
+/// GET `/api/products/{product_id}/reviews`
+#[utoipa::path(
+    get,
+    path = "/api/products/{product_id}/reviews",
+    tag = "reviews",
+    params(("product_id" = String, Path, description = "Product identifier")),
+    responses(
+        (status = 200, description = "The product's rating and reviews", body = ProductReviews),
+        (status = 404, description = "Product not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn list_reviews(
+    Extension(state): Extension<AppState>,
+    Path(product_id): Path<String>,
+) -> Result<Json<ProductReviews>, ReviewError> {
+    state.product_service.get_product_by_id(&product_id).await?;
+
+    let summary = state
+        .review_service
+        .summaries()?
+        .remove(&product_id)
+        .unwrap_or_default();
+    let reviews = state.review_service.for_product(&product_id)?;
+    Ok(Json(ProductReviews { summary, reviews }))
+}
+
+/// Reviews a product. Only buyers with a delivered order containing it may, once each.
+///
+/// POST `/api/products/{product_id}/reviews`
+#[utoipa::path(
+    post,
+    path = "/api/products/{product_id}/reviews",
+    tag = "reviews",
+    params(("product_id" = String, Path, description = "Product identifier")),
+    request_body = ReviewRequest,
+    responses(
+        (status = 201, description = "Review published", body = Review),
+        (status = 400, description = "Rating outside 1..=5 or text too long", body = ErrorMessage, content_type = "text/plain"),
+        (status = 403, description = "No delivered order of this product", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Product not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 409, description = "Product already reviewed by the user", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn create_review(
+    Extension(state): Extension<AppState>,
+    Path(product_id): Path<String>,
+    Json(payload): Json<ReviewRequest>,
+) -> Result<(StatusCode, Json<Review>), ReviewError> {
+    let user_id = "user123".to_string();
+
+    state.product_service.get_product_by_id(&product_id).await?;
+    let order_id = state
+        .checkout_service
+        .delivered_order_with(&user_id, &product_id)?
+        .ok_or(ReviewError::NotVerifiedBuyer)?;
+    let review = state.review_service.submit(
+        &product_id,
+        &user_id,
+        &order_id,
+        payload.rating,
+        &payload.text,
+        Utc::now(),
+    )?;
+    Ok((StatusCode::CREATED, Json(review)))
+}
+
+/// Attaches a photo to the user's own review (at most 4).
+///
+/// POST `/api/reviews/{review_id}/photos`
+#[utoipa::path(
+    post,
+    path = "/api/reviews/{review_id}/photos",
+    tag = "reviews",
+    params(("review_id" = String, Path, description = "Review identifier")),
+    request_body(content = ReviewPhotoUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Photo stored and attached", body = Review),
+        (status = 400, description = "Missing or corrupt image, or the review already has 4 photos", body = ErrorMessage, content_type = "text/plain"),
+        (status = 403, description = "Not the author of the review", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Review not found", body = ErrorMessage, content_type = "text/plain"),
+        (status = 413, description = "Image too large", body = ErrorMessage, content_type = "text/plain"),
+        (status = 415, description = "Not a JPEG, PNG or WebP image", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn upload_review_photo(
+    Extension(state): Extension<AppState>,
+    Path(review_id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<Review>), ReviewError> {
+    let user_id = "user123".to_string();
+
+    // Fail fast rather than processing a photo that can't be attached.
+    let review = state.review_service.get(&review_id)?;
+    if review.user_id != user_id {
+        return Err(ReviewError::NotAuthor);
+    }
+
+    let upload = ReviewPhotoUpload::read(&mut multipart).await?;
+    let photo = state
+        .image_service
+        .upload_review_photo(&review.product_id, upload.image)
+        .await?;
+    let review = state
+        .review_service
+        .add_photo(&review_id, &user_id, photo)?;
+    Ok((StatusCode::CREATED, Json(review)))
+}
+
+/// Publishes the vendor's answer below a review of one of their products, replacing any
+/// earlier one.
+///
+/// POST `/api/vendor/reviews/{review_id}/reply`
+#[utoipa::path(
+    post,
+    path = "/api/vendor/reviews/{review_id}/reply",
+    tag = "reviews",
+    params(("review_id" = String, Path, description = "Review identifier")),
+    request_body = ReplyRequest,
+    responses(
+        (status = 200, description = "The review with its reply", body = Review),
+        (status = 400, description = "Empty or too long reply", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Review not found", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+pub async fn reply_to_review(
+    Extension(state): Extension<AppState>,
+    Path(review_id): Path<String>,
+    Json(payload): Json<ReplyRequest>,
+) -> Result<Json<Review>, ReviewError> {
+    let review = state.review_service.get(&review_id)?;
+    let product = state
+        .product_service
+        .get_product_by_id(&review.product_id)
+        .await?;
+    let review =
+        state
+            .review_service
+            .reply(&review_id, &product.vendor_name, &payload.text, Utc::now())?;
+    Ok(Json(review))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Extension, Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+        routing::get,
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use super::review_routes;
+    use crate::{
+        api::{handler::search_products, orders::order_routes},
+        middleware::locale::LocalizedMessage,
+        models::order::{OrderLine, OrderStatus},
+        state::AppState,
+    };
+
+    fn post(uri: &str, payload: Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap()
+    }
+
+    async fn json_body(response: axum::response::Response) -> Value {
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_only_delivered_buyers_review_and_ratings_filter_search() {
+        let state = AppState::default();
+        let app = Router::new()
+            .route("/api/products", get(search_products))
+            .merge(review_routes(1024 * 1024))
+            .merge(order_routes())
+            .layer(Extension(state.clone()));
+
+        let line = OrderLine {
+            product_id: "2".to_string(),
+            variant_sku: None,
+            name: "Bamileke Stool".to_string(),
+            unit_price: 15000.0,
+            quantity: 1,
+        };
+        let order = state
+            .checkout_service
+            .create_order("user123".to_string(), vec![line], Vec::new(), 0.0, 15000.0)
+            .unwrap();
+        state
+            .checkout_service
+            .update_order_status(order.order_id.clone(), OrderStatus::Paid)
+            .unwrap();
+
+        // Paid but not yet delivered.
+        let review = json!({ "rating": 4, "text": "Solide et bien sculpté" });
+        let response = app
+            .clone()
+            .oneshot(post("/api/products/2/reviews", review.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let message = response.extensions().get::<LocalizedMessage>().unwrap();
+        assert_eq!(
+            message.fr,
+            "Seuls les acheteurs dont une commande de ce produit a été livrée peuvent le noter"
+        );
+
+        let response = app
+            .clone()
+            .oneshot(post(
+                &format!("/api/admin/orders/{}/deliver", order.order_id),
+                json!({}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/products/2/reviews", json!({ "rating": 6 })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let message = response.extensions().get::<LocalizedMessage>().unwrap();
+        assert_eq!(
+            message.fr,
+            "Avis invalide : la note doit être comprise entre 1 et 5 étoiles"
+        );
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/products/2/reviews", review.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let review_id = json_body(response).await["review_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/products/2/reviews", review))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // Never ordered.
+        let response = app
+            .clone()
+            .oneshot(post("/api/products/3/reviews", json!({ "rating": 5 })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app
+            .clone()
+            .oneshot(post(
+                &format!("/api/vendor/reviews/{review_id}/reply"),
+                json!({ "text": "Merci beaucoup !" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products/2/reviews")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = json_body(response).await;
+        assert_eq!(body["summary"]["average_rating"], 4.0);
+        assert_eq!(body["summary"]["rating_count"], 1);
+        assert_eq!(
+            body["reviews"][0]["vendor_reply"]["text"],
+            "Merci beaucoup !"
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/products?min_rating=4&sort=rating")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = json_body(response).await;
+        let products = body["products"].as_array().unwrap();
+        assert_eq!(products.len(), 1);
+        assert_eq!(products[0]["id"], "2");
+        assert_eq!(products[0]["average_rating"], 4.0);
+        assert_eq!(products[0]["rating_count"], 1);
+    }
+}
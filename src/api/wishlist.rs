@@ -0,0 +1,485 @@
+use axum::{
+    Router,
+    extract::{Extension, Json},
+    http::{HeaderMap, header},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        cart::{CartItem, cart_etag, expected_version, stale_cart_response},
+        model::Product,
+        openapi::ErrorMessage,
+    },
+    models::{cart_summary::CartSummary, locale::Locale, wishlist::WishlistItem},
+    services::{cart_services::CartError, wishlist_service::WishlistError},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct WishlistRequest {
+    pub product_id: String,
+    /// Required when the product has variants.
+    #[serde(default)]
+    pub variant_sku: Option<String>,
+    /// Units to put in the cart when moving an item there (default 1).
+    #[serde(default)]
+    pub quantity: Option<u32>,
+}
+
+pub fn wishlist_routes() -> Router {
+    Router::new().nest(
+        "/api/wishlist",
+        Router::new()
+            .route("/", get(get_wishlist))
+            .route("/add", post(add_to_wishlist))
+            .route("/remove", delete(remove_from_wishlist))
+            .route("/move-to-cart", post(move_to_cart))
+            .route("/move-from-cart", post(move_from_cart)),
+    )
+}
+
+/// Sends the notifications due to buyers who wishlisted `product` after it changed.
+/// Failures are only logged: they must not undo the change itself.
+pub fn notify_wishlists(state: &AppState, product: &Product) {
+    let result = state
+        .wishlist_service
+        .watch(product, Utc::now())
+        .and_then(|due| {
+            due.into_iter().try_for_each(|(user_id, notification)| {
+                state.notification_service.notify(&user_id, notification)
+            })?;
+            Ok(())
+        });
+    if let Err(err) = result {
+        eprintln!(
+            "⚠️ Failed to notify wishlists of product {}: {err}",
+            product.id
+        );
+    }
+}
+
+/// GET `/api/wishlist`
+#[utoipa::path(
+    get,
+    path = "/api/wishlist",
+    tag = "wishlist",
+    responses(
+        (status = 200, description = "Saved items, oldest first", body = Vec<WishlistItem>),
+        (status = 500, description = "Wishlist storage unavailable", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn get_wishlist(
+    Extension(state): Extension<AppState>,
+) -> Result<Json<Vec<WishlistItem>>, WishlistError> {
+    let user_id = "user123".to_string();
+
+    Ok(Json(state.wishlist_service.get(&user_id)?))
+}
+
+/// Saves a product for later. The user is notified when it drops in price or comes back
+/// in stock.
+///
+/// POST `/api/wishlist/add`
+#[utoipa::path(
+    post,
+    path = "/api/wishlist/add",
+    tag = "wishlist",
+    request_body = WishlistRequest,
+    responses(
+        (status = 200, description = "Item saved (or already saved)", body = WishlistItem),
+        (status = 400, description = "Missing or unknown variant", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Unknown product", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn add_to_wishlist(
+    Extension(state): Extension<AppState>,
+    Json(payload): Json<WishlistRequest>,
+) -> Result<Json<WishlistItem>, WishlistError> {
+    let user_id = "user123".to_string();
+
+    let product = state
+        .product_service
+        .get_product_by_id(&payload.product_id)
+        .await?;
+    let item = state.wishlist_service.add(
+        &user_id,
+        &product,
+        payload.variant_sku.as_deref(),
+        Utc::now(),
+    )?;
+    Ok(Json(item))
+}
+
+/// DELETE `/api/wishlist/remove`
+#[utoipa::path(
+    delete,
+    path = "/api/wishlist/remove",
+    tag = "wishlist",
+    request_body = WishlistRequest,
+    responses(
+        (status = 200, description = "Item removed", body = String),
+        (status = 404, description = "Item not in the wishlist", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn remove_from_wishlist(
+    Extension(state): Extension<AppState>,
+    Json(payload): Json<WishlistRequest>,
+) -> Result<Json<&'static str>, WishlistError> {
+    let user_id = "user123".to_string();
+
+    state.wishlist_service.remove(
+        &user_id,
+        &payload.product_id,
+        payload.variant_sku.as_deref(),
+    )?;
+    Ok(Json("Item removed from wishlist"))
+}
+
+/// Moves a saved item into the cart, with `quantity` units (default 1). Like the cart's
+/// own writes, `If-Match` must carry the cart's `ETag`.
+///
+/// POST `/api/wishlist/move-to-cart`
+#[utoipa::path(
+    post,
+    path = "/api/wishlist/move-to-cart",
+    tag = "wishlist",
+    params(("If-Match" = String, Header, description = "`ETag` of the cart the change is based on, or `*`")),
+    request_body = WishlistRequest,
+    responses(
+        (status = 200, description = "Item moved to the cart; `ETag` holds the cart's new version", body = String),
+        (status = 400, description = "Variant no longer sold, invalid quantity or cart limits exceeded", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Unknown product, or item not in the wishlist", body = ErrorMessage, content_type = "text/plain"),
+        (status = 412, description = "The cart changed since; the current cart, with its `ETag`", body = CartSummary),
+        (status = 428, description = "Missing If-Match", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn move_to_cart(
+    Extension(state): Extension<AppState>,
+    locale: Locale,
+    headers: HeaderMap,
+    Json(payload): Json<WishlistRequest>,
+) -> Result<Response, WishlistError> {
+    let user_id = "user123";
+    let moved = add_saved_item_to_cart(&state, user_id, &headers, payload).await;
+    stale_cart_or(&state, locale, user_id, moved).await
+}
+
+async fn add_saved_item_to_cart(
+    state: &AppState,
+    user_id: &str,
+    headers: &HeaderMap,
+    payload: WishlistRequest,
+) -> Result<Response, WishlistError> {
+    let expected = expected_version(headers)?;
+
+    let product = state
+        .product_service
+        .get_product_by_id(&payload.product_id)
+        .await?;
+    let unit_price = product.unit_price(payload.variant_sku.as_deref())?;
+    let saved = state
+        .wishlist_service
+        .get(user_id)?
+        .iter()
+        .any(|i| i.is_same(&payload.product_id, payload.variant_sku.as_deref()));
+    if !saved {
+        return Err(WishlistError::NotInWishlist);
+    }
+    // Only drop the item from the wishlist once the cart has accepted it.
+    let version = state.cart_service.add_item(
+        user_id.to_string(),
+        CartItem {
+            product_id: payload.product_id.clone(),
+            variant_sku: payload.variant_sku.clone(),
+            quantity: payload.quantity.unwrap_or(1),
+            added_unit_price: Some(unit_price),
+        },
+        expected,
+    )?;
+    state
+        .wishlist_service
+        .remove(user_id, &payload.product_id, payload.variant_sku.as_deref())?;
+    Ok((
+        [(header::ETAG, cart_etag(version))],
+        Json("Item moved to cart"),
+    )
+        .into_response())
+}
+
+/// Takes an item out of the cart and saves it in the wishlist instead. `If-Match` must
+/// carry the cart's `ETag`.
+///
+/// POST `/api/wishlist/move-from-cart`
+#[utoipa::path(
+    post,
+    path = "/api/wishlist/move-from-cart",
+    tag = "wishlist",
+    params(("If-Match" = String, Header, description = "`ETag` of the cart the change is based on, or `*`")),
+    request_body = WishlistRequest,
+    responses(
+        (status = 200, description = "Item saved for later; `ETag` holds the cart's new version", body = WishlistItem),
+        (status = 400, description = "Product or variant no longer sold", body = ErrorMessage, content_type = "text/plain"),
+        (status = 404, description = "Item not in the cart", body = ErrorMessage, content_type = "text/plain"),
+        (status = 412, description = "The cart changed since; the current cart, with its `ETag`", body = CartSummary),
+        (status = 428, description = "Missing If-Match", body = ErrorMessage, content_type = "text/plain"),
+    )
+)]
+async fn move_from_cart(
+    Extension(state): Extension<AppState>,
+    locale: Locale,
+    headers: HeaderMap,
+    Json(payload): Json<WishlistRequest>,
+) -> Result<Response, WishlistError> {
+    let user_id = "user123";
+    let saved = save_cart_item_for_later(&state, user_id, &headers, payload).await;
+    stale_cart_or(&state, locale, user_id, saved).await
+}
+
+async fn save_cart_item_for_later(
+    state: &AppState,
+    user_id: &str,
+    headers: &HeaderMap,
+    payload: WishlistRequest,
+) -> Result<Response, WishlistError> {
+    let expected = expected_version(headers)?;
+
+    let cart = state.cart_service.snapshot(user_id)?;
+    if expected.is_some_and(|version| version != cart.version) {
+        return Err(CartError::VersionMismatch.into());
+    }
+    let in_cart = cart
+        .items
+        .iter()
+        .any(|i| i.product_id == payload.product_id && i.variant_sku == payload.variant_sku);
+    if !in_cart {
+        return Err(WishlistError::NotInCart);
+    }
+    let product = state
+        .product_service
+        .get_product_by_id(&payload.product_id)
+        .await?;
+    // Save the item before taking it out of the cart, so it's never lost. Should the cart
+    // change in between, the removal fails and the item is merely in both.
+    let item = state.wishlist_service.add(
+        user_id,
+        &product,
+        payload.variant_sku.as_deref(),
+        Utc::now(),
+    )?;
+    let version = state.cart_service.remove_item(
+        user_id.to_string(),
+        payload.product_id,
+        payload.variant_sku,
+        expected,
+    )?;
+    Ok(([(header::ETAG, cart_etag(version))], Json(item)).into_response())
+}
+
+/// Answers a move refused because the cart changed since the client read it like the
+/// cart's own writes do: `412` with the current cart and its `ETag`.
+async fn stale_cart_or(
+    state: &AppState,
+    locale: Locale,
+    user_id: &str,
+    result: Result<Response, WishlistError>,
+) -> Result<Response, WishlistError> {
+    match result {
+        Err(WishlistError::Cart(CartError::VersionMismatch)) => Ok(stale_cart_response(
+            &state.cart_service,
+            &state.product_service,
+            &state.promotion_service,
+            locale,
+            user_id,
+        )
+        .await?),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Extension, Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode, header},
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    use super::wishlist_routes;
+    use crate::{
+        api::{listings::listing_routes, notifications::notification_routes},
+        middleware::locale::LocalizedMessage,
+        state::AppState,
+    };
+
+    fn request(method: &str, uri: &str, payload: Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap()
+    }
+
+    fn if_match(mut request: Request<Body>, etag: &str) -> Request<Body> {
+        request
+            .headers_mut()
+            .insert(header::IF_MATCH, etag.parse().unwrap());
+        request
+    }
+
+    async fn json_body(response: axum::response::Response) -> Value {
+        let body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_wishlist_alerts_and_moves_to_cart() {
+        let state = AppState::default();
+        let app = Router::new()
+            .merge(wishlist_routes())
+            .merge(listing_routes())
+            .merge(notification_routes())
+            .layer(Extension(state.clone()));
+        let listing = |price: f64, stock: u32| {
+            json!({
+                "id": "30", "name": "Sac en raphia", "price": price, "stock": stock,
+                "category": "crafts", "region": "Ouest", "certified": false
+            })
+        };
+        let notifications = || async {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/api/me/notifications")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            json_body(response).await
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/vendor/products", listing(5000.0, 0)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let response = app
+            .clone()
+            .oneshot(request(
+                "POST",
+                "/api/wishlist/add",
+                json!({ "product_id": "30" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json_body(response).await["seen_in_stock"], false);
+
+        app.clone()
+            .oneshot(request(
+                "PUT",
+                "/api/vendor/products/30",
+                listing(5000.0, 3),
+            ))
+            .await
+            .unwrap();
+        app.clone()
+            .oneshot(request(
+                "PUT",
+                "/api/vendor/products/30",
+                listing(4500.0, 3),
+            ))
+            .await
+            .unwrap();
+        // A price increase isn't worth a notification.
+        app.clone()
+            .oneshot(request(
+                "PUT",
+                "/api/vendor/products/30",
+                listing(4800.0, 3),
+            ))
+            .await
+            .unwrap();
+        let inbox = notifications().await;
+        let inbox = inbox.as_array().unwrap();
+        assert_eq!(inbox.len(), 2);
+        assert_eq!(inbox[0]["kind"], "price_drop");
+        assert_eq!(inbox[0]["previous_price"], 5000.0);
+        assert_eq!(inbox[0]["price"], 4500.0);
+        assert_eq!(inbox[1]["kind"], "back_in_stock");
+
+        let move_to_cart = || {
+            request(
+                "POST",
+                "/api/wishlist/move-to-cart",
+                json!({ "product_id": "30", "quantity": 2 }),
+            )
+        };
+        let response = app.clone().oneshot(move_to_cart()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_REQUIRED);
+        // No cart yet: its version is 0.
+        let response = app
+            .clone()
+            .oneshot(if_match(move_to_cart(), "\"0\""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let cart = state.cart_service.get_cart("user123".to_string()).unwrap();
+        assert_eq!(cart.len(), 1);
+        assert_eq!(cart[0].quantity, 2);
+        assert!(state.wishlist_service.get("user123").unwrap().is_empty());
+
+        let move_from_cart = || {
+            request(
+                "POST",
+                "/api/wishlist/move-from-cart",
+                json!({ "product_id": "30" }),
+            )
+        };
+        // Based on a cart read before the move: refused with the current cart.
+        let response = app
+            .clone()
+            .oneshot(if_match(move_from_cart(), "\"0\""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        assert_eq!(json_body(response).await["lines"][0]["quantity"], 2);
+        assert!(state.wishlist_service.get("user123").unwrap().is_empty());
+
+        let response = app
+            .clone()
+            .oneshot(if_match(move_from_cart(), &etag))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag.as_str());
+        assert!(
+            state
+                .cart_service
+                .get_cart("user123".to_string())
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(state.wishlist_service.get("user123").unwrap().len(), 1);
+
+        let response = app.oneshot(if_match(move_from_cart(), "*")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let message = response.extensions().get::<LocalizedMessage>().unwrap();
+        assert_eq!(message.fr, "Article introuvable dans le panier");
+    }
+}
@@ -0,0 +1,180 @@
+use std::{env, net::SocketAddr, path::PathBuf, time::Duration};
+
+/// Runtime configuration, read from environment variables with local-development defaults.
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    /// `BIND_ADDR`, default `127.0.0.1:8000`.
+    pub bind_addr: SocketAddr,
+    /// `SHUTDOWN_TIMEOUT_SECS`: how long in-flight requests and workers get to finish.
+    pub shutdown_timeout: Duration,
+    /// `PAYMENT_PROVIDER_ADDR`: `host:port` of the mobile money gateway, probed by `/readyz`.
+    pub payment_provider_addr: Option<String>,
+    /// `READINESS_TIMEOUT_MS`: per-dependency deadline for readiness checks.
+    pub readiness_timeout: Duration,
+    pub cors: CorsConfig,
+    /// `HSTS_MAX_AGE_SECS`: `Strict-Transport-Security` max-age. `0` disables the header.
+    pub hsts_max_age: Duration,
+    /// `MAX_BODY_BYTES`: largest request body accepted before answering `413`.
+    pub max_body_bytes: usize,
+    /// `RATE_LIMIT_ENABLED`: per-client request throttling.
+    pub rate_limit_enabled: bool,
+    /// `IMAGE_STORAGE_DIR`: where uploaded product images and thumbnails are stored.
+    pub image_storage_dir: PathBuf,
+    /// `MAX_IMAGE_BYTES`: largest product image accepted by the upload endpoint.
+    pub max_image_bytes: usize,
+    /// `CART_TTL_HOURS`: carts untouched for this long are deleted.
+    pub cart_ttl: Duration,
+    /// `ABANDONED_CART_HOURS`: idle time after which signed-in users get an SMS reminder.
+    pub abandoned_cart_after: Duration,
+    /// `CART_SWEEP_INTERVAL_SECS`: how often expired and abandoned carts are looked for.
+    pub cart_sweep_interval: Duration,
+}
+
+/// Cross-origin policy for the browser frontend.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// `CORS_ALLOWED_ORIGINS`: comma-separated origins, or `*` for any origin.
+    pub allowed_origins: Vec<String>,
+    /// `CORS_ALLOWED_METHODS`: comma-separated HTTP methods.
+    pub allowed_methods: Vec<String>,
+    /// `CORS_ALLOW_CREDENTIALS`: whether browsers may send cookies/authorization headers.
+    pub allow_credentials: bool,
+    /// `CORS_MAX_AGE_SECS`: how long browsers may cache preflight responses.
+    pub max_age: Duration,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            bind_addr: SocketAddr::from(([127, 0, 0, 1], 8000)),
+            shutdown_timeout: Duration::from_secs(30),
+            payment_provider_addr: None,
+            readiness_timeout: Duration::from_millis(500),
+            cors: CorsConfig::default(),
+            hsts_max_age: Duration::from_secs(365 * 24 * 60 * 60),
+            max_body_bytes: 1024 * 1024,
+            rate_limit_enabled: true,
+            image_storage_dir: PathBuf::from("data/images"),
+            max_image_bytes: 5 * 1024 * 1024,
+            cart_ttl: Duration::from_secs(30 * 24 * 60 * 60),
+            abandoned_cart_after: Duration::from_secs(24 * 60 * 60),
+            cart_sweep_interval: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            // Vite dev server
+            allowed_origins: vec!["http://localhost:5173".to_string()],
+            allowed_methods: ["GET", "POST", "PUT", "DELETE", "OPTIONS"]
+                .map(String::from)
+                .to_vec(),
+            allow_credentials: false,
+            max_age: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn from_env() -> Self {
+        let defaults = AppConfig::default();
+        AppConfig {
+            bind_addr: parse_var("BIND_ADDR").unwrap_or(defaults.bind_addr),
+            shutdown_timeout: parse_var("SHUTDOWN_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.shutdown_timeout),
+            payment_provider_addr: env::var("PAYMENT_PROVIDER_ADDR")
+                .ok()
+                .filter(|addr| !addr.is_empty()),
+            readiness_timeout: parse_var("READINESS_TIMEOUT_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.readiness_timeout),
+            cors: CorsConfig::from_env(),
+            hsts_max_age: parse_var("HSTS_MAX_AGE_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.hsts_max_age),
+            max_body_bytes: parse_var("MAX_BODY_BYTES").unwrap_or(defaults.max_body_bytes),
+            rate_limit_enabled: parse_var("RATE_LIMIT_ENABLED")
+                .unwrap_or(defaults.rate_limit_enabled),
+            image_storage_dir: parse_var("IMAGE_STORAGE_DIR").unwrap_or(defaults.image_storage_dir),
+            max_image_bytes: parse_var("MAX_IMAGE_BYTES").unwrap_or(defaults.max_image_bytes),
+            cart_ttl: parse_hours("CART_TTL_HOURS").unwrap_or(defaults.cart_ttl),
+            abandoned_cart_after: parse_hours("ABANDONED_CART_HOURS")
+                .unwrap_or(defaults.abandoned_cart_after),
+            cart_sweep_interval: parse_positive("CART_SWEEP_INTERVAL_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.cart_sweep_interval),
+        }
+    }
+
+    /// Largest request body any route accepts: image uploads plus their multipart framing.
+    pub fn max_upload_request_bytes(&self) -> usize {
+        self.max_image_bytes.saturating_add(MULTIPART_OVERHEAD_BYTES)
+    }
+}
+
+/// Room for multipart boundaries and small form fields (alt text) around an uploaded file.
+const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
+impl CorsConfig {
+    pub fn from_env() -> Self {
+        let defaults = CorsConfig::default();
+        CorsConfig {
+            allowed_origins: parse_list("CORS_ALLOWED_ORIGINS").unwrap_or(defaults.allowed_origins),
+            allowed_methods: parse_list("CORS_ALLOWED_METHODS").unwrap_or(defaults.allowed_methods),
+            allow_credentials: parse_var("CORS_ALLOW_CREDENTIALS")
+                .unwrap_or(defaults.allow_credentials),
+            max_age: parse_var("CORS_MAX_AGE_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.max_age),
+        }
+    }
+}
+
+fn parse_var<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let raw = env::var(name).ok()?;
+    match raw.parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            eprintln!("⚠️ Ignoring invalid value for {name}: {raw:?}");
+            None
+        }
+    }
+}
+
+/// Like `parse_var`, but also ignores zero, which would make a timer fire continuously
+/// (`tokio::time::interval` panics on it) or expire carts as soon as they're created.
+fn parse_positive(name: &str) -> Option<u64> {
+    match parse_var(name)? {
+        0 => {
+            eprintln!("⚠️ Ignoring invalid value for {name}: must be at least 1");
+            None
+        }
+        value => Some(value),
+    }
+}
+
+/// A positive number of hours, ignoring values too large to represent in seconds.
+fn parse_hours(name: &str) -> Option<Duration> {
+    let hours: u64 = parse_positive(name)?;
+    match hours.checked_mul(60 * 60) {
+        Some(secs) => Some(Duration::from_secs(secs)),
+        None => {
+            eprintln!("⚠️ Ignoring invalid value for {name}: {hours} hours is too long");
+            None
+        }
+    }
+}
+
+fn parse_list(name: &str) -> Option<Vec<String>> {
+    let raw = env::var(name).ok()?;
+    Some(
+        raw.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect(),
+    )
+}
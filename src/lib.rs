@@ -1,5 +1,10 @@
+#![allow(non_snake_case)]
+
 pub mod api;
+pub mod auth;
+pub mod config;
+pub mod middleware;
+pub mod models;
 pub mod services;
+pub mod shutdown;
 pub mod state;
-pub mod models;
-pub mod auth;
\ No newline at end of file
@@ -1,45 +1,151 @@
+#![allow(non_snake_case)]
+
 use Vendor_MarketPlace::{
     api::{
-        cart::cart_routes, checkout::checkout_routes, handler::search_products,
-        model::ProductService, orders::order_routes,
+        cart::cart_routes, categories::category_routes, certifications::certification_routes, checkout::checkout_routes, handler::{get_product, list_regions, products_by_category, search_products, search_suggestions},
+        health::health_routes, images::image_routes, listings::listing_routes, model::ProductService, openapi::openapi_routes,
+        orders::order_routes, preferences::preference_routes, reviews::review_routes,
+        wishlist::wishlist_routes, notifications::notification_routes,
+        promotions::promotion_routes,
+    },
+    config::AppConfig,
+    middleware::{
+        locale::negotiate_locale,
+        rate_limit::{InMemoryRateLimitStore, RateLimiter, rate_limit},
+        security::apply_security_layers,
     },
     services::{
-        cart_services::CartService, checkout_service::CheckoutService,
+        cart_services::CartService,
+        cart_sweeper::run_cart_sweeper,
+        certification_service::CertificationService,
+        checkout_service::CheckoutService, image_service::ImageService,
+        moderation_service::ModerationService,
         payment_service::PaymentService,
+        preference_service::PreferenceService,
+        review_service::ReviewService,
+        wishlist_service::WishlistService,
+        notification_service::NotificationService,
+        promotion_service::PromotionService,
     },
+    shutdown::{Shutdown, wait_for_signal},
     state::AppState,
 };
-use axum::{Extension, Router, routing::get};
+use axum::{Extension, Router, middleware::from_fn_with_state, routing::get};
 use std::{net::SocketAddr, sync::Arc};
+use tower_http::compression::CompressionLayer;
 
 #[tokio::main]
 async fn main() {
+    let config = AppConfig::from_env();
+    let shutdown = Shutdown::new();
+
     let cart_service = CartService::new();
     let checkout_service = CheckoutService::new();
-    let payment_service = PaymentService::new();
+    let payment_service = match &config.payment_provider_addr {
+        Some(addr) => PaymentService::with_provider(addr.clone()),
+        None => PaymentService::new(),
+    };
     let product_service = ProductService::new();
+    let preference_service = PreferenceService::new();
+    let image_service = ImageService::from_config(&config);
 
-    let app_state = Arc::new(AppState {
+    let app_state = AppState {
         cart_service,
         checkout_service,
         payment_service,
         product_service,
+        image_service,
+        certification_service: CertificationService::new(),
+        moderation_service: ModerationService::new(),
+        preference_service: preference_service.clone(),
+        review_service: ReviewService::new(),
+        wishlist_service: WishlistService::new(),
+        notification_service: NotificationService::new(),
+        promotion_service: PromotionService::new(),
+        config: config.clone(),
+        shutdown: shutdown.clone(),
+    };
+
+    shutdown.spawn_worker({
+        let state = app_state.clone();
+        |token| run_cart_sweeper(token, state)
     });
 
-    let app = Router::new()
+    let mut app = Router::new()
         .route("/api/products", get(search_products))
-        .merge(cart_routes(app_state.clone()))
+        .route("/api/products/suggestions", get(search_suggestions))
+        .route("/api/products/{product_id}", get(get_product))
+        .route("/api/products/category/{slug}", get(products_by_category))
+        .route("/api/regions", get(list_regions))
+        .merge(health_routes())
+        .merge(openapi_routes())
+        .merge(cart_routes(Arc::new(app_state.clone())))
         .merge(checkout_routes())
         .merge(order_routes())
-        .layer(Extension(app_state));
+        .merge(image_routes(config.max_upload_request_bytes()))
+        .merge(category_routes())
+        .merge(certification_routes())
+        .merge(listing_routes())
+        .merge(preference_routes())
+        .merge(review_routes(config.max_upload_request_bytes()))
+        .merge(wishlist_routes())
+        .merge(notification_routes())
+        .merge(promotion_routes())
+        .layer(Extension(app_state))
+        .layer(from_fn_with_state(preference_service, negotiate_locale));
+    if config.rate_limit_enabled {
+        let limiter = RateLimiter::with_default_policies(Arc::new(InMemoryRateLimitStore::new()));
+        app = app.layer(from_fn_with_state(limiter, rate_limit));
+    }
+    let app = apply_security_layers(app.layer(CompressionLayer::new()), &config);
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], 8000));
+    let addr = config.bind_addr;
     println!("🚀 Server listening on {}", addr);
 
     // Bind the address to a TcpListener
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
 
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            wait_for_signal().await;
+            println!("🛑 Shutdown signal received, draining in-flight requests...");
+            shutdown.trigger();
+        }
+    });
+
+    let mut server = tokio::spawn(
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+            .with_graceful_shutdown(shutdown.signalled())
+            .into_future(),
+    );
+
+    tokio::select! {
+        result = &mut server => {
+            result.unwrap().unwrap();
+            return;
+        }
+        _ = shutdown.signalled() => {}
+    }
+
+    // Give in-flight requests (e.g. checkouts) a bounded amount of time to finish.
+    match tokio::time::timeout(config.shutdown_timeout, &mut server).await {
+        Ok(result) => result.unwrap().unwrap(),
+        Err(_) => {
+            eprintln!(
+                "⚠️ In-flight requests did not finish within {:?}, aborting",
+                config.shutdown_timeout
+            );
+            server.abort();
+        }
+    }
+
+    if !shutdown.stop_workers(config.shutdown_timeout).await {
+        eprintln!("⚠️ Background workers did not stop within {:?}", config.shutdown_timeout);
+    }
+
+    println!("👋 Server stopped");
 }
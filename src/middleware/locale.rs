@@ -0,0 +1,150 @@
+use std::convert::Infallible;
+
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    models::locale::{Locale, Localize},
+    services::preference_service::PreferenceService,
+};
+
+/// User whose language preference applies until requests are authenticated.
+const CURRENT_USER_ID: &str = "user123";
+
+/// Both translations of an error message, attached to error responses so that
+/// `negotiate_locale` can send the one the client asked for.
+#[derive(Debug, Clone)]
+pub struct LocalizedMessage {
+    pub en: String,
+    pub fr: String,
+}
+
+/// An error response with an English plain-text body, switched to French by
+/// `negotiate_locale` when that is the negotiated language.
+pub fn localized_error(status: StatusCode, error: &impl Localize) -> Response {
+    let message = LocalizedMessage {
+        en: error.localized(Locale::En),
+        fr: error.localized(Locale::Fr),
+    };
+    let mut response = (status, message.en.clone()).into_response();
+    response.extensions_mut().insert(message);
+    response
+}
+
+fn from_headers(headers: &HeaderMap) -> Option<Locale> {
+    headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::from_accept_language)
+}
+
+/// Picks the response language: the user's saved preference, else `Accept-Language`,
+/// else English. Handlers read it with the `Locale` extractor; localized error messages
+/// are swapped in on the way out and every response carries `Content-Language`.
+pub async fn negotiate_locale(
+    State(preferences): State<PreferenceService>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let locale = preferences
+        .language(CURRENT_USER_ID)
+        .ok()
+        .flatten()
+        .or_else(|| from_headers(req.headers()))
+        .unwrap_or_default();
+    req.extensions_mut().insert(locale);
+
+    let mut response = next.run(req).await;
+    if let Some(message) = response.extensions_mut().remove::<LocalizedMessage>()
+        && locale == Locale::Fr
+    {
+        response.headers_mut().remove(header::CONTENT_LENGTH);
+        *response.body_mut() = Body::from(message.fr);
+    }
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_LANGUAGE,
+        HeaderValue::from_static(locale.tag()),
+    );
+    headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+    response
+}
+
+/// The negotiated language, or the `Accept-Language` one on routers without
+/// `negotiate_locale`.
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<Locale>()
+            .copied()
+            .or_else(|| from_headers(&parts.headers))
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode, header},
+        middleware::from_fn_with_state,
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    use super::negotiate_locale;
+    use crate::{
+        api::model::ProductError, models::locale::Locale,
+        services::preference_service::PreferenceService,
+    };
+
+    async fn not_found() -> ProductError {
+        ProductError::ProductNotFound
+    }
+
+    #[tokio::test]
+    async fn test_errors_follow_preference_then_accept_language() {
+        let preferences = PreferenceService::new();
+        let app = Router::new()
+            .route("/missing", get(not_found))
+            .layer(from_fn_with_state(preferences.clone(), negotiate_locale));
+        let request = |accept_language: &str| {
+            Request::builder()
+                .uri("/missing")
+                .header(header::ACCEPT_LANGUAGE, accept_language)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let message = |response: axum::response::Response| async move {
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            let body = to_bytes(response.into_body(), 1024).await.unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request("fr-CM,fr;q=0.9"))
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[header::CONTENT_LANGUAGE], "fr");
+        assert_eq!(message(response).await, "Produit introuvable");
+
+        let response = app.clone().oneshot(request("en-US")).await.unwrap();
+        assert_eq!(message(response).await, "Product not found");
+
+        preferences
+            .set_language(super::CURRENT_USER_ID, Some(Locale::Fr))
+            .unwrap();
+        let response = app.oneshot(request("en-US")).await.unwrap();
+        assert_eq!(message(response).await, "Produit introuvable");
+    }
+}
@@ -0,0 +1,3 @@
+pub mod locale;
+pub mod security;
+pub mod rate_limit;
@@ -0,0 +1,367 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tokio::time::Instant;
+
+/// Token bucket parameters: up to `capacity` requests in a burst, refilled at
+/// `refill_per_sec` tokens per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitPolicy {
+    capacity: u32,
+    refill_per_sec: f64,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum RateLimitPolicyError {
+    #[error("A rate limit must allow at least one request")]
+    ZeroCapacity,
+    #[error("A rate limit must refill at a positive, finite rate, not {0}")]
+    InvalidRefillRate(f64),
+}
+
+impl RateLimitPolicy {
+    /// Rejects policies a bucket could never recover from: no capacity, or a rate at which
+    /// the wait for the next token can't be computed.
+    pub fn new(capacity: u32, refill_per_sec: f64) -> Result<Self, RateLimitPolicyError> {
+        if capacity == 0 {
+            return Err(RateLimitPolicyError::ZeroCapacity);
+        }
+        if !(refill_per_sec.is_finite() && refill_per_sec > 0.0) {
+            return Err(RateLimitPolicyError::InvalidRefillRate(refill_per_sec));
+        }
+        Ok(RateLimitPolicy {
+            capacity,
+            refill_per_sec,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitDecision {
+    Allowed,
+    /// The caller must wait at least this long before retrying.
+    Limited {
+        retry_after: Duration,
+    },
+}
+
+/// Storage for token buckets. The in-memory implementation is enough for a single
+/// instance; a shared store (e.g. Redis) can implement this trait once we scale out.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Takes one token from the bucket identified by `key`, creating it full if missing.
+    async fn acquire(&self, key: &str, policy: RateLimitPolicy) -> RateLimitDecision;
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+    /// The policy the bucket was created under, so pruning judges it by its own refill rate.
+    policy: RateLimitPolicy,
+}
+
+impl Bucket {
+    fn refilled(&self, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        (self.tokens + elapsed * self.policy.refill_per_sec).min(f64::from(self.policy.capacity))
+    }
+}
+
+#[derive(Default)]
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    /// Size at which idle buckets are next pruned; raised when a prune frees too little so
+    /// a crowd of active callers doesn't trigger a full scan on every request.
+    prune_at: usize,
+}
+
+/// Process-local token buckets.
+#[derive(Clone, Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+/// Above this many buckets, idle (fully refilled) ones are pruned on the next request.
+const MAX_IDLE_BUCKETS: usize = 10_000;
+
+impl InMemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn acquire(&self, key: &str, policy: RateLimitPolicy) -> RateLimitDecision {
+        let now = Instant::now();
+        // A poisoned lock only means another request panicked mid-update; the counters are still usable.
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let Buckets { by_key, prune_at } = &mut *buckets;
+
+        if by_key.len() > (*prune_at).max(MAX_IDLE_BUCKETS) {
+            by_key.retain(|_, bucket| bucket.refilled(now) < f64::from(bucket.policy.capacity));
+            *prune_at = by_key.len() * 2;
+        }
+
+        let bucket = by_key.entry(key.to_string()).or_insert(Bucket {
+            tokens: f64::from(policy.capacity),
+            last_refill: now,
+            policy,
+        });
+
+        bucket.tokens = bucket.refilled(now);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            RateLimitDecision::Allowed
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            RateLimitDecision::Limited {
+                retry_after: Duration::from_secs_f64(missing / bucket.policy.refill_per_sec),
+            }
+        }
+    }
+}
+
+/// Per-route rate limiting, keyed by client IP.
+#[derive(Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    /// Path prefixes and their policy; the longest matching prefix wins.
+    routes: Vec<(String, RateLimitPolicy)>,
+    default_policy: RateLimitPolicy,
+}
+
+impl RateLimiter {
+    pub fn new(store: Arc<dyn RateLimitStore>, default_policy: RateLimitPolicy) -> Self {
+        RateLimiter {
+            store,
+            routes: Vec::new(),
+            default_policy,
+        }
+    }
+
+    pub fn route(mut self, prefix: impl Into<String>, policy: RateLimitPolicy) -> Self {
+        self.routes.push((prefix.into(), policy));
+        self
+    }
+
+    /// Tight limits on money- and auth-related routes, loose ones on catalog browsing.
+    pub fn with_default_policies(store: Arc<dyn RateLimitStore>) -> Self {
+        let policy = |capacity, refill_per_sec| {
+            RateLimitPolicy::new(capacity, refill_per_sec).expect("built-in policies are valid")
+        };
+        RateLimiter::new(store, policy(60, 1.0))
+            .route("/api/checkout", policy(5, 0.1))
+            .route("/api/payment-callback", policy(30, 1.0))
+            .route("/api/auth", policy(3, 1.0 / 60.0))
+            .route("/api/products", policy(120, 5.0))
+    }
+
+    fn policy_for(&self, path: &str) -> (&str, RateLimitPolicy) {
+        self.routes
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, policy)| (prefix.as_str(), *policy))
+            .unwrap_or(("*", self.default_policy))
+    }
+}
+
+/// Identifies the caller by peer address. Client-supplied headers such as `x-user-id` are
+/// ignored: anyone could send a fresh value per request. Key on the verified identity once
+/// the auth layer sets one.
+fn client_key(req: &Request) -> String {
+    match req.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+        None => "ip:unknown".to_string(),
+    }
+}
+
+/// Middleware rejecting requests over their route's budget with `429 Too Many Requests`.
+pub async fn rate_limit(State(limiter): State<RateLimiter>, req: Request, next: Next) -> Response {
+    let (scope, policy) = limiter.policy_for(req.uri().path());
+    let key = format!("{scope}|{}", client_key(&req));
+
+    match limiter.store.acquire(&key, policy).await {
+        RateLimitDecision::Allowed => next.run(req).await,
+        RateLimitDecision::Limited { retry_after } => {
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            let mut response = (StatusCode::TOO_MANY_REQUESTS, "Too many requests").into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+    use axum::{
+        Router,
+        body::Body,
+        extract::ConnectInfo,
+        http::{Request, StatusCode, header},
+        middleware::from_fn_with_state,
+        routing::{get, post},
+    };
+    use tower::ServiceExt;
+
+    use super::{
+        InMemoryRateLimitStore, MAX_IDLE_BUCKETS, RateLimitDecision, RateLimitPolicy,
+        RateLimitPolicyError, RateLimitStore, RateLimiter, rate_limit,
+    };
+
+    fn app() -> Router {
+        let limiter = RateLimiter::new(
+            Arc::new(InMemoryRateLimitStore::new()),
+            RateLimitPolicy::new(100, 10.0).unwrap(),
+        )
+        .route("/api/checkout", RateLimitPolicy::new(2, 0.5).unwrap());
+
+        Router::new()
+            .route("/api/checkout", post(|| async { "ok" }))
+            .route("/api/products", get(|| async { "ok" }))
+            .layer(from_fn_with_state(limiter, rate_limit))
+    }
+
+    fn request(method: &str, uri: &str, ip: [u8; 4]) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .extension(ConnectInfo(SocketAddr::from((ip, 40000))))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    const ALICE: [u8; 4] = [10, 0, 0, 1];
+    const BOB: [u8; 4] = [10, 0, 0, 2];
+
+    #[tokio::test]
+    async fn test_checkout_limited_after_burst() {
+        let app = app();
+
+        for _ in 0..2 {
+            let response = app
+                .clone()
+                .oneshot(request("POST", "/api/checkout", ALICE))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/checkout", ALICE))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+
+        // Other clients and other routes have their own buckets.
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/checkout", BOB))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .oneshot(request("GET", "/api/products", ALICE))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bucket_refills_over_time() {
+        let store = InMemoryRateLimitStore::new();
+        let policy = RateLimitPolicy::new(1, 1.0).unwrap();
+
+        assert_eq!(store.acquire("k", policy).await, RateLimitDecision::Allowed);
+        assert!(matches!(
+            store.acquire("k", policy).await,
+            RateLimitDecision::Limited { .. }
+        ));
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+
+        assert_eq!(store.acquire("k", policy).await, RateLimitDecision::Allowed);
+    }
+
+    #[test]
+    fn test_policy_rejects_rates_it_could_never_refill_at() {
+        for rate in [0.0, -1.0, f64::INFINITY] {
+            assert_eq!(
+                RateLimitPolicy::new(1, rate),
+                Err(RateLimitPolicyError::InvalidRefillRate(rate))
+            );
+        }
+        assert!(RateLimitPolicy::new(1, f64::NAN).is_err());
+        assert_eq!(
+            RateLimitPolicy::new(0, 1.0),
+            Err(RateLimitPolicyError::ZeroCapacity)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spoofed_user_header_shares_the_peer_bucket() {
+        let app = app();
+
+        for attempt in 0..3 {
+            let mut req = request("POST", "/api/checkout", ALICE);
+            req.headers_mut()
+                .insert("x-user-id", format!("user-{attempt}").parse().unwrap());
+            let status = app.clone().oneshot(req).await.unwrap().status();
+            let expected = if attempt < 2 {
+                StatusCode::OK
+            } else {
+                StatusCode::TOO_MANY_REQUESTS
+            };
+            assert_eq!(status, expected);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_prune_judges_each_bucket_by_its_own_policy() {
+        let store = InMemoryRateLimitStore::new();
+        let slow = RateLimitPolicy::new(1, 1.0 / 3600.0).unwrap();
+        let fast = RateLimitPolicy::new(1, 1.0).unwrap();
+
+        assert_eq!(
+            store.acquire("slow", slow).await,
+            RateLimitDecision::Allowed
+        );
+        for i in 0..MAX_IDLE_BUCKETS {
+            store.acquire(&format!("fast-{i}"), fast).await;
+        }
+        tokio::time::advance(Duration::from_secs(2)).await;
+
+        // The fast buckets are full again and go; the drained slow one must survive.
+        store.acquire("trigger", fast).await;
+        {
+            let buckets = store.buckets.lock().unwrap();
+            assert_eq!(buckets.by_key.len(), 2);
+            assert!(buckets.by_key.contains_key("slow"));
+        }
+        assert!(matches!(
+            store.acquire("slow", slow).await,
+            RateLimitDecision::Limited { .. }
+        ));
+    }
+}
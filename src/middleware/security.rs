@@ -0,0 +1,215 @@
+use axum::{
+    Router,
+    extract::DefaultBodyLimit,
+    http::{HeaderName, HeaderValue, Method, header},
+};
+use tower_http::{
+    cors::{AllowOrigin, CorsLayer},
+    limit::RequestBodyLimitLayer,
+    set_header::SetResponseHeaderLayer,
+};
+
+use crate::{
+    api::cart::CART_TOKEN_HEADER,
+    config::{AppConfig, CorsConfig},
+};
+
+/// Wraps `router` with the CORS policy, security headers and request body limit from `config`.
+///
+/// Extractors are held to `max_body_bytes`; routes accepting uploads raise that with their
+/// own `DefaultBodyLimit`, up to the hard cap enforced here for every request.
+pub fn apply_security_layers(router: Router, config: &AppConfig) -> Router {
+    let hard_limit = config
+        .max_body_bytes
+        .max(config.max_upload_request_bytes());
+    let mut router = router
+        .layer(DefaultBodyLimit::max(config.max_body_bytes))
+        .layer(RequestBodyLimitLayer::new(hard_limit))
+        .layer(SetResponseHeaderLayer::if_not_present(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        ))
+        .layer(SetResponseHeaderLayer::if_not_present(
+            header::X_FRAME_OPTIONS,
+            HeaderValue::from_static("DENY"),
+        ))
+        .layer(SetResponseHeaderLayer::if_not_present(
+            header::CONTENT_SECURITY_POLICY,
+            HeaderValue::from_static("frame-ancestors 'none'"),
+        ))
+        .layer(SetResponseHeaderLayer::if_not_present(
+            header::REFERRER_POLICY,
+            HeaderValue::from_static("no-referrer"),
+        ));
+
+    if !config.hsts_max_age.is_zero() {
+        let hsts = format!(
+            "max-age={}; includeSubDomains",
+            config.hsts_max_age.as_secs()
+        );
+        router = router.layer(SetResponseHeaderLayer::if_not_present(
+            header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_str(&hsts).expect("HSTS header is ASCII"),
+        ));
+    }
+
+    // CORS goes outermost so preflight requests are answered before any other layer.
+    router.layer(cors_layer(&config.cors))
+}
+
+fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let any_origin = config.allowed_origins.iter().any(|origin| origin == "*");
+
+    let origins = if any_origin {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(config.allowed_origins.iter().filter_map(|origin| {
+            HeaderValue::from_str(origin)
+                .inspect_err(|_| eprintln!("⚠️ Ignoring invalid CORS origin: {origin:?}"))
+                .ok()
+        }))
+    };
+
+    let methods: Vec<Method> = config
+        .allowed_methods
+        .iter()
+        .filter_map(|method| {
+            method
+                .to_uppercase()
+                .parse()
+                .inspect_err(|_| eprintln!("⚠️ Ignoring invalid CORS method: {method:?}"))
+                .ok()
+        })
+        .collect();
+
+    // Browsers reject credentialed responses with a wildcard origin, and tower-http
+    // refuses to build such a layer, so credentials are only honoured for explicit origins.
+    let allow_credentials = if any_origin && config.allow_credentials {
+        eprintln!("⚠️ CORS credentials are disabled because CORS_ALLOWED_ORIGINS is `*`");
+        false
+    } else {
+        config.allow_credentials
+    };
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(allowed_headers())
+        .expose_headers([header::ETAG, header::RETRY_AFTER])
+        .allow_credentials(allow_credentials)
+        .max_age(config.max_age)
+}
+
+fn allowed_headers() -> Vec<HeaderName> {
+    vec![
+        header::ACCEPT,
+        header::ACCEPT_LANGUAGE,
+        header::AUTHORIZATION,
+        header::CONTENT_TYPE,
+        header::IF_MATCH,
+        header::IF_NONE_MATCH,
+        HeaderName::from_static(CART_TOKEN_HEADER),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+        routing::{get, post},
+    };
+    use tower::ServiceExt;
+
+    use super::apply_security_layers;
+    use crate::config::AppConfig;
+
+    fn app(config: &AppConfig) -> Router {
+        let router = Router::new()
+            .route("/ping", get(|| async { "pong" }))
+            .route("/echo", post(|body: String| async move { body }));
+        apply_security_layers(router, config)
+    }
+
+    #[tokio::test]
+    async fn test_preflight_from_allowed_origin() {
+        let response = app(&AppConfig::default())
+            .oneshot(
+                Request::builder()
+                    .method("OPTIONS")
+                    .uri("/ping")
+                    .header(header::ORIGIN, "http://localhost:5173")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:5173"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disallowed_origin_gets_no_cors_headers() {
+        let response = app(&AppConfig::default())
+            .oneshot(
+                Request::builder()
+                    .uri("/ping")
+                    .header(header::ORIGIN, "https://evil.example")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(
+            !response
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_security_headers_present() {
+        let response = app(&AppConfig::default())
+            .oneshot(Request::builder().uri("/ping").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let headers = response.headers();
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+        assert!(
+            headers[header::STRICT_TRANSPORT_SECURITY]
+                .to_str()
+                .unwrap()
+                .starts_with("max-age=31536000")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_rejected() {
+        let config = AppConfig {
+            max_body_bytes: 16,
+            ..Default::default()
+        };
+
+        let response = app(&config)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/echo")
+                    .body(Body::from("x".repeat(64)))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
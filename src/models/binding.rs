@@ -1,22 +1,66 @@
-use crate::{api::model::ProductError, services::cart_services::CartError};
+use crate::{
+    api::model::ProductError,
+    models::locale::Translated,
+    services::{
+        cart_services::CartError, certification_service::CertificationError,
+        promotion_service::PromotionError,
+    },
+};
 
 impl From<ProductError> for CartError {
     fn from(err: ProductError) -> CartError {
         match err {
             ProductError::LockError => CartError::LockError,
-            ProductError::ProductNotFound => {
-                CartError::GenericError("Product not found".to_string())
-            }
+            ProductError::ProductNotFound => CartError::ProductNotFound,
+            ProductError::VariantRequired => CartError::VariantRequired,
+            ProductError::VariantNotFound(sku) => CartError::VariantNotFound(sku),
+            ProductError::InvalidQuery(_)
+            | ProductError::InvalidCursor
+            | ProductError::InvalidProduct(_) => CartError::GenericError(Translated::of(&err)),
         }
     }
 }
 
-impl Into<ProductError> for CartError {
-    fn into(self) -> ProductError {
-        match self {
+impl From<CartError> for ProductError {
+    fn from(err: CartError) -> ProductError {
+        match err {
             CartError::LockError => ProductError::LockError,
             CartError::CartNotFound => ProductError::ProductNotFound,
-            CartError::GenericError(_) => ProductError::ProductNotFound,
+            CartError::GenericError(_)
+            | CartError::ProductNotFound
+            | CartError::ItemNotInCart => ProductError::ProductNotFound,
+            CartError::VariantRequired => ProductError::VariantRequired,
+            CartError::VariantNotFound(sku) => ProductError::VariantNotFound(sku),
+            CartError::InvalidCartToken
+            | CartError::InvalidQuantity
+            | CartError::TooManyItems
+            | CartError::TooManyUnits
+            | CartError::PreconditionRequired
+            | CartError::VersionMismatch
+            | CartError::OutOfStock { .. } => ProductError::InvalidQuery(Translated::of(&err)),
+        }
+    }
+}
+impl From<PromotionError> for CartError {
+    fn from(err: PromotionError) -> CartError {
+        match err {
+            PromotionError::LockError => CartError::LockError,
+            PromotionError::Cart(err) => err,
+            PromotionError::UnknownCode
+            | PromotionError::NotActive
+            | PromotionError::UsageLimitReached
+            | PromotionError::CodeTaken(_)
+            | PromotionError::InvalidPromotion(_) => CartError::GenericError(Translated::of(&err)),
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<ProductError> for CertificationError {
+    fn from(err: ProductError) -> CertificationError {
+        match err {
+            ProductError::LockError => CertificationError::LockError,
+            ProductError::ProductNotFound => CertificationError::ProductNotFound,
+            err => CertificationError::InvalidRequest(Translated::of(&err)),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+// src/models/cart_metrics.rs
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Cart expiry and abandoned-cart recovery counters since the server started.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CartMetrics {
+    pub active_carts: usize,
+    /// Carts deleted after going untouched for the configured TTL.
+    pub expired_carts: u64,
+    /// Abandoned-cart reminders sent.
+    pub reminders_sent: u64,
+    /// Reminded users who went on to check out.
+    pub recovered_carts: u64,
+    /// `recovered_carts / reminders_sent`, or 0 before any reminder.
+    pub recovery_rate: f64,
+}
@@ -0,0 +1,86 @@
+// src/models/cart_summary.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The cart with current prices and totals, as shown before checkout.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CartSummary {
+    pub lines: Vec<CartLine>,
+    /// Sum of the line totals.
+    pub subtotal: f64,
+    /// Reductions applied to the subtotal, from automatic promotions and the coupon.
+    pub discounts: Vec<Discount>,
+    pub discount_total: f64,
+    pub delivery: DeliveryEstimate,
+    /// Subtotal minus discounts plus the delivery fee.
+    pub grand_total: f64,
+    /// Problems to resolve before checking out.
+    pub warnings: Vec<CartWarning>,
+    /// When the cart last changed; absent until something is added. Carts left
+    /// untouched for too long are deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Coupon code applied to the cart. It only yields a discount while the cart meets
+    /// the promotion's conditions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coupon_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CartLine {
+    pub product_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_sku: Option<String>,
+    /// In the response language; empty when the product is no longer published.
+    pub name: String,
+    pub quantity: u32,
+    /// Current price, in FCFA; `0` when the product is no longer published.
+    pub unit_price: f64,
+    pub line_total: f64,
+}
+
+/// A reduction from a promotion.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Discount {
+    pub promotion_id: String,
+    /// The coupon code it was applied with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// What the reduction is for, e.g. the promotion's name.
+    pub label: String,
+    pub amount: f64,
+}
+
+/// Courier fee and lead time. Each vendor ships separately, so the fee is per vendor.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeliveryEstimate {
+    pub fee: f64,
+    pub min_days: u32,
+    pub max_days: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CartWarningKind {
+    /// Fewer units are available than the cart holds (possibly none).
+    OutOfStock,
+    /// The product was withdrawn from the catalog; it can't be checked out.
+    Unpublished,
+    /// The unit price differs from the one shown when the item was added.
+    PriceChanged,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CartWarning {
+    pub kind: CartWarningKind,
+    pub product_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_sku: Option<String>,
+    /// Units left, for `out_of_stock`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available: Option<u32>,
+    /// Price when the item was added, for `price_changed`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_price: Option<f64>,
+}
@@ -0,0 +1,67 @@
+// src/models/cart_sync.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::cart_summary::CartSummary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CartOperationKind {
+    /// Adds `quantity` units (default 1) to the line.
+    Add,
+    /// Sets the line's quantity; 0 removes it.
+    Update,
+    Remove,
+}
+
+/// A cart change queued by the client while offline.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CartOperation {
+    /// Client-chosen identifier, echoed in the outcome.
+    pub op_id: String,
+    pub kind: CartOperationKind,
+    pub product_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_sku: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u32>,
+    /// When the change was made on the device.
+    pub made_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CartSyncRequest {
+    /// In the order they were made.
+    pub operations: Vec<CartOperation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    Applied,
+    /// The line changed after the operation was made, so the later change stands.
+    Superseded,
+    /// The cart refused the change, e.g. an unknown product or a quantity over the limits.
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OperationOutcome {
+    pub op_id: String,
+    pub status: OperationStatus,
+    /// Why the operation was rejected, in the response language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CartSyncResponse {
+    /// The cart once the operations are applied.
+    pub cart: CartSummary,
+    /// One per operation, in the order sent.
+    pub outcomes: Vec<OperationOutcome>,
+    /// `op_id`s of the operations left out of the cart, superseded or rejected, in the
+    /// order sent.
+    pub dropped: Vec<String>,
+}
@@ -0,0 +1,40 @@
+// src/models/category.rs
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A node of the category tree, e.g. Food → Spices → Pepper.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Category {
+    /// Stable identifier used in URLs and in `Product::category`, e.g. `pepper`.
+    pub slug: String,
+    /// Slug of the parent category; `None` for top-level categories.
+    #[serde(default)]
+    pub parent: Option<String>,
+    pub name_fr: String,
+    pub name_en: String,
+    /// Attributes products in this category (and its descendants) may or must describe,
+    /// in addition to those inherited from the ancestors.
+    #[serde(default)]
+    pub attributes: Vec<AttributeSchema>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AttributeSchema {
+    /// Key in `Product::attributes`, e.g. `net_weight_g`.
+    pub name: String,
+    pub kind: AttributeKind,
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values for `choice` attributes.
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeKind {
+    Text,
+    Number,
+    Boolean,
+    Choice,
+}
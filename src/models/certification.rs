@@ -0,0 +1,64 @@
+// src/models/certification.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Where a review stands. Only `Approved` certifications that haven't expired make a
+/// product "Made in Cameroon" certified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificationStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+    Revoked,
+}
+
+/// What a vendor provides to prove a product is made in Cameroon.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CertificationEvidence {
+    /// How and from what the product is made, e.g. "Hand-carved from local iroko wood".
+    pub origin: String,
+    /// Town or address of the workshop.
+    pub workshop_location: String,
+    /// URLs of supporting documents or photos (workshop, business registration...).
+    #[serde(default)]
+    pub documents: Vec<String>,
+}
+
+/// One entry of a certification's audit trail.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CertificationEvent {
+    pub at: DateTime<Utc>,
+    /// Who acted: the vendor, an admin, or `system` for expiry.
+    pub actor: String,
+    pub status: CertificationStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Certification {
+    pub certification_id: String,
+    pub product_id: String,
+    pub evidence: CertificationEvidence,
+    pub status: CertificationStatus,
+    pub submitted_at: DateTime<Utc>,
+    /// Set on approval.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Reason given for the latest rejection or revocation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision_reason: Option<String>,
+    /// Every status change, oldest first.
+    pub history: Vec<CertificationEvent>,
+}
+
+impl Certification {
+    /// Whether this certification currently makes its product certified.
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        self.status == CertificationStatus::Approved
+            && self.expires_at.is_some_and(|expires_at| now < expires_at)
+    }
+}
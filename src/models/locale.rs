@@ -0,0 +1,136 @@
+// src/models/locale.rs
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Languages the marketplace is served in: Cameroon's two official languages.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// Default when the client expresses no supported preference.
+    #[default]
+    En,
+    Fr,
+}
+
+impl Locale {
+    /// Matches a BCP 47 language tag by its primary subtag: `fr-CM` is French.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next()?;
+        if primary.eq_ignore_ascii_case("fr") {
+            Some(Locale::Fr)
+        } else if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else {
+            None
+        }
+    }
+
+    /// The supported language the client prefers in an `Accept-Language` header, going
+    /// by quality values (`fr;q=0.9, en;q=0.8`) and then by order.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut best: Option<(Locale, f32)> = None;
+        for range in header.split(',') {
+            let mut parts = range.split(';');
+            let Some(locale) = parts.next().and_then(Locale::from_tag) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+        best.map(|(locale, _)| locale)
+    }
+
+    /// Picks the text for this language.
+    pub fn pick<'a>(self, fr: &'a str, en: &'a str) -> &'a str {
+        match self {
+            Locale::Fr => fr,
+            Locale::En => en,
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::Fr => "fr",
+            Locale::En => "en",
+        }
+    }
+}
+
+/// Errors and other messages that can be shown to users in either language.
+pub trait Localize {
+    fn localized(&self, locale: Locale) -> String;
+}
+
+/// A message written in both languages, such as the reason a request was refused. Shown
+/// in English by `Display`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translated {
+    pub en: String,
+    pub fr: String,
+}
+
+impl Translated {
+    pub fn new(en: impl Into<String>, fr: impl Into<String>) -> Self {
+        Translated {
+            en: en.into(),
+            fr: fr.into(),
+        }
+    }
+
+    /// Both translations of `message`, e.g. an error reported as the detail of another.
+    pub fn of(message: &impl Localize) -> Self {
+        Translated::new(message.localized(Locale::En), message.localized(Locale::Fr))
+    }
+}
+
+impl fmt::Display for Translated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.en)
+    }
+}
+
+impl Localize for Translated {
+    fn localized(&self, locale: Locale) -> String {
+        locale.pick(&self.fr, &self.en).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Locale;
+
+    #[test]
+    fn test_accept_language_negotiation() {
+        assert_eq!(Locale::from_accept_language("fr-CM"), Some(Locale::Fr));
+        assert_eq!(
+            Locale::from_accept_language("de-DE, en;q=0.5, fr;q=0.8"),
+            Some(Locale::Fr)
+        );
+        assert_eq!(Locale::from_accept_language("en-GB, fr"), Some(Locale::En));
+        assert_eq!(
+            Locale::from_accept_language("fr;q=0, en;q=0.1"),
+            Some(Locale::En)
+        );
+        assert_eq!(Locale::from_accept_language("de, *"), None);
+    }
+}
@@ -1,2 +1,13 @@
 pub mod order;
-pub mod binding;
\ No newline at end of file
+pub mod category;
+pub mod locale;
+pub mod certification;
+pub mod moderation;
+pub mod binding;
+pub mod review;
+pub mod wishlist;
+pub mod notification;
+pub mod cart_summary;
+pub mod cart_metrics;
+pub mod cart_sync;
+pub mod promotion;
\ No newline at end of file
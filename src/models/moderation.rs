@@ -0,0 +1,53 @@
+// src/models/moderation.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::model::Product;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingAction {
+    Create,
+    Update,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationStatus {
+    /// Flagged by the listing checks and waiting for an admin.
+    Pending,
+    /// Published, automatically or by an admin.
+    Approved,
+    Rejected,
+}
+
+/// A vendor's product submission and what was decided about it.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModerationEntry {
+    pub moderation_id: String,
+    pub action: ListingAction,
+    /// The listing as submitted; published as-is on approval.
+    pub product: Product,
+    /// Why the listing checks flagged it; empty when it passed them.
+    pub flags: Vec<String>,
+    pub status: ModerationStatus,
+    pub submitted_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<DateTime<Utc>>,
+    /// `system` for automatic approvals, otherwise the reviewing admin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision_reason: Option<String>,
+}
+
+/// Brands and phrases that send a listing to manual review.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct Blocklist {
+    /// Foreign brand names, matched in the name, description, tags and brand field,
+    /// tolerating small misspellings of longer names.
+    pub brands: Vec<String>,
+    /// Phrases typical of imported or counterfeit goods, e.g. "replica" or "made in china".
+    pub patterns: Vec<String>,
+}
@@ -0,0 +1,38 @@
+// src/models/notification.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// A wishlisted item got cheaper.
+    PriceDrop,
+    /// A wishlisted item that had sold out can be bought again.
+    BackInStock,
+}
+
+/// A message for a user, shown in the app's notification inbox.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Notification {
+    pub notification_id: String,
+    pub kind: NotificationKind,
+    pub product_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_sku: Option<String>,
+    pub product_name: String,
+    /// Unit price before the change, for price drops.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_price: Option<f64>,
+    pub price: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A text message queued for the SMS gateway.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SmsMessage {
+    pub message_id: String,
+    pub user_id: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
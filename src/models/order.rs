@@ -1,18 +1,43 @@
 // src/models/order.rs
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::models::cart_summary::Discount;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum OrderStatus {
     PendingPayment,
     Paid,
     Failed,
+    /// Handed to the courier.
+    Shipped,
+    /// Received by the buyer, who may now review its products.
+    Delivered,
+}
+
+/// A purchased product (and variant, if it has any) with the price paid at checkout.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderLine {
+    pub product_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_sku: Option<String>,
+    pub name: String,
+    pub unit_price: f64,
+    pub quantity: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Order {
     pub order_id: String,
     pub user_id: String,
-    pub items: Vec<String>, 
+    pub items: Vec<OrderLine>,
+    /// Promotions applied at checkout.
+    #[serde(default)]
+    pub discounts: Vec<Discount>,
+    /// Courier fee charged for the order, as estimated in the cart summary.
+    #[serde(default)]
+    pub delivery_fee: f64,
+    /// Amount to pay: the lines less the discounts, plus the delivery fee.
     pub total_amount: f64,
     pub status: OrderStatus,
 }
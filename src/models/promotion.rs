@@ -0,0 +1,65 @@
+// src/models/promotion.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::model::Product;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscountKind {
+    /// `value` percent off the eligible items.
+    Percentage,
+    /// `value` FCFA off the eligible items.
+    FixedAmount,
+}
+
+/// A discount run by a vendor on their products, or by the platform on any.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Promotion {
+    pub promotion_id: String,
+    /// Coupon code buyers apply to their cart, in upper case. Promotions without one
+    /// apply to every eligible cart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Shown next to the discount, e.g. `Fête de la Jeunesse -10%`.
+    pub label: String,
+    pub kind: DiscountKind,
+    pub value: f64,
+    /// Vendor whose products it covers; platform-wide when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor_name: Option<String>,
+    /// Least the eligible items must come to, in FCFA.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_spend: Option<f64>,
+    /// How many orders of each user may use it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_user_limit: Option<u32>,
+    pub starts_at: DateTime<Utc>,
+    /// Exclusive.
+    pub ends_at: DateTime<Utc>,
+    /// Category slugs it is limited to, subcategories included. An item is eligible if
+    /// it matches `categories` or `product_ids`; when both are empty, every item is.
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub product_ids: Vec<String>,
+}
+
+impl Promotion {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+
+    /// Whether `product` is among the items the promotion takes money off.
+    pub fn applies_to(&self, product: &Product) -> bool {
+        let vendor = self
+            .vendor_name
+            .as_ref()
+            .is_none_or(|vendor| *vendor == product.vendor_name);
+        let targeted = (self.categories.is_empty() && self.product_ids.is_empty())
+            || self.categories.contains(&product.category_slug)
+            || self.product_ids.contains(&product.id);
+        vendor && targeted
+    }
+}
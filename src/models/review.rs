@@ -0,0 +1,41 @@
+// src/models/review.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::model::ProductImage;
+
+/// A verified buyer's opinion of a product.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Review {
+    pub review_id: String,
+    pub product_id: String,
+    pub user_id: String,
+    /// The delivered order that entitled the user to review the product.
+    pub order_id: String,
+    /// 1 to 5 stars.
+    pub rating: u8,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub photos: Vec<ProductImage>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor_reply: Option<VendorReply>,
+}
+
+/// The artisan's public answer to a review.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VendorReply {
+    pub vendor_name: String,
+    pub text: String,
+    pub replied_at: DateTime<Utc>,
+}
+
+/// Average and number of ratings of a product.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RatingSummary {
+    /// `None` until the product has been reviewed.
+    pub average_rating: Option<f64>,
+    pub rating_count: u32,
+}
@@ -0,0 +1,26 @@
+// src/models/wishlist.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A product a buyer saved to come back to later.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WishlistItem {
+    pub product_id: String,
+    /// SKU of the chosen variant; absent for products sold without variants.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_sku: Option<String>,
+    pub added_at: DateTime<Utc>,
+    /// Unit price when last seen; a lower price triggers a price-drop notification.
+    pub seen_price: f64,
+    /// Whether the item was available when last seen; becoming available again triggers a
+    /// back-in-stock notification.
+    pub seen_in_stock: bool,
+}
+
+impl WishlistItem {
+    /// Whether both items refer to the same product and variant.
+    pub fn is_same(&self, product_id: &str, variant_sku: Option<&str>) -> bool {
+        self.product_id == product_id && self.variant_sku.as_deref() == variant_sku
+    }
+}
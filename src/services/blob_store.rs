@@ -0,0 +1,153 @@
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BlobError {
+    #[error("Invalid blob key: {0}")]
+    InvalidKey(String),
+    #[error("Blob storage failure: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Storage for uploaded files, addressed by slash-separated keys such as
+/// `products/2/<image id>/480.jpg`. The local filesystem implementation is enough for a
+/// single instance; an object store (S3, GCS...) can implement this trait later.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), BlobError>;
+    /// Returns `None` when nothing is stored under `key`.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, BlobError>;
+    async fn delete(&self, key: &str) -> Result<(), BlobError>;
+}
+
+/// Stores blobs as files under a root directory.
+#[derive(Debug, Clone)]
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalBlobStore { root: root.into() }
+    }
+
+    /// Maps `key` to a path under the root, rejecting keys that could escape it.
+    fn path_for(&self, key: &str) -> Result<PathBuf, BlobError> {
+        let relative = Path::new(key);
+        let is_plain = !key.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !is_plain {
+            return Err(BlobError::InvalidKey(key.to_string()));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), BlobError> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, bytes).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, BlobError> {
+        match tokio::fs::read(self.path_for(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BlobError> {
+        match tokio::fs::remove_file(self.path_for(key)?).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Keeps blobs in memory, for tests and other throwaway instances.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBlobStore {
+    blobs: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+}
+
+impl MemoryBlobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn blobs(&self) -> Result<MutexGuard<'_, HashMap<String, Vec<u8>>>, BlobError> {
+        self.blobs
+            .lock()
+            .map_err(|_| std::io::Error::other("blob storage lock poisoned").into())
+    }
+}
+
+#[async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), BlobError> {
+        self.blobs()?.insert(key.to_string(), bytes);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, BlobError> {
+        Ok(self.blobs()?.get(key).cloned())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BlobError> {
+        self.blobs()?.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlobStore, LocalBlobStore, MemoryBlobStore};
+
+    #[tokio::test]
+    async fn test_local_store_round_trip_and_rejects_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalBlobStore::new(dir.path());
+
+        store
+            .put("products/2/a/original.jpg", vec![1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("products/2/a/original.jpg").await.unwrap(),
+            Some(vec![1, 2, 3])
+        );
+
+        store.delete("products/2/a/original.jpg").await.unwrap();
+        assert_eq!(store.get("products/2/a/original.jpg").await.unwrap(), None);
+
+        assert!(store.get("../etc/passwd").await.is_err());
+        assert!(store.put("/tmp/x", vec![]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_round_trip() {
+        let store = MemoryBlobStore::new();
+        store.put("products/2/a/480.jpg", vec![1, 2]).await.unwrap();
+        assert_eq!(
+            store.get("products/2/a/480.jpg").await.unwrap(),
+            Some(vec![1, 2])
+        );
+        store.delete("products/2/a/480.jpg").await.unwrap();
+        assert_eq!(store.get("products/2/a/480.jpg").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    api::{cart::CartItem, model::Product},
+    models::{
+        cart_summary::{
+            CartLine, CartSummary, CartWarning, CartWarningKind, DeliveryEstimate, Discount,
+        },
+        promotion::{DiscountKind, Promotion},
+    },
+};
+
+/// Courier fee per vendor in the cart, in FCFA.
+pub const DELIVERY_FEE_PER_VENDOR: f64 = 1500.0;
+/// Subtotal from which delivery is free.
+pub const FREE_DELIVERY_THRESHOLD: f64 = 50000.0;
+/// Lead time for vendors all in one region; orders spanning regions take longer.
+const DELIVERY_DAYS: (u32, u32) = (2, 5);
+const EXTRA_DAYS_ACROSS_REGIONS: u32 = 2;
+
+/// Prices `items` against the current catalog. `products` holds the published products
+/// of the cart, already localized; items missing from it are reported as unpublished and
+/// left out of the totals. `promotions` are those the cart may get, in the order they
+/// apply.
+pub fn price_cart(
+    items: Vec<CartItem>,
+    products: &HashMap<String, Product>,
+    promotions: &[Promotion],
+) -> CartSummary {
+    let mut lines = Vec::with_capacity(items.len());
+    let mut priced_lines = Vec::with_capacity(items.len());
+    let mut warnings = Vec::new();
+    let mut vendors = BTreeSet::new();
+    let mut regions = BTreeSet::new();
+    for item in items {
+        let warning = |kind, available, previous_price| CartWarning {
+            kind,
+            product_id: item.product_id.clone(),
+            variant_sku: item.variant_sku.clone(),
+            available,
+            previous_price,
+        };
+        // A variant withdrawn from a product can't be bought either.
+        let priced = products.get(&item.product_id).and_then(|product| {
+            let sku = item.variant_sku.as_deref();
+            Some((
+                product,
+                product.unit_price(sku).ok()?,
+                product.available_stock(sku).ok()?,
+            ))
+        });
+        let Some((product, unit_price, available)) = priced else {
+            warnings.push(warning(CartWarningKind::Unpublished, None, None));
+            lines.push(CartLine {
+                product_id: item.product_id,
+                variant_sku: item.variant_sku,
+                name: String::new(),
+                quantity: item.quantity,
+                unit_price: 0.0,
+                line_total: 0.0,
+            });
+            continue;
+        };
+
+        if let Some(available) = available
+            && available < item.quantity
+        {
+            warnings.push(warning(CartWarningKind::OutOfStock, Some(available), None));
+        }
+        if let Some(added_price) = item.added_unit_price
+            && added_price != unit_price
+        {
+            warnings.push(warning(
+                CartWarningKind::PriceChanged,
+                None,
+                Some(added_price),
+            ));
+        }
+        vendors.insert(product.vendor_name.clone());
+        regions.insert(product.region.clone());
+        priced_lines.push((product, unit_price * f64::from(item.quantity)));
+        lines.push(CartLine {
+            name: product.name.clone(),
+            quantity: item.quantity,
+            unit_price,
+            line_total: unit_price * f64::from(item.quantity),
+            product_id: item.product_id,
+            variant_sku: item.variant_sku,
+        });
+    }
+
+    let subtotal: f64 = lines.iter().map(|line| line.line_total).sum();
+    let fee = if subtotal >= FREE_DELIVERY_THRESHOLD {
+        0.0
+    } else {
+        DELIVERY_FEE_PER_VENDOR * vendors.len() as f64
+    };
+    let extra_days = if regions.len() > 1 {
+        EXTRA_DAYS_ACROSS_REGIONS
+    } else {
+        0
+    };
+    let delivery = DeliveryEstimate {
+        fee,
+        min_days: DELIVERY_DAYS.0 + extra_days,
+        max_days: DELIVERY_DAYS.1 + extra_days,
+    };
+    let discounts = discounts(&priced_lines, promotions);
+    let discount_total = discounts.iter().map(|discount| discount.amount).sum();
+    CartSummary {
+        lines,
+        subtotal,
+        discounts,
+        discount_total,
+        grand_total: subtotal - discount_total + delivery.fee,
+        delivery,
+        warnings,
+        updated_at: None,
+        coupon_code: None,
+    }
+}
+
+/// Discounts from `promotions` on priced lines (product and line total), in the order
+/// given. Each takes its share off the lines it covers once they reach its minimum
+/// spend, rounded to the franc; together they never exceed the lines' total.
+pub fn discounts(lines: &[(&Product, f64)], promotions: &[Promotion]) -> Vec<Discount> {
+    let mut remaining: f64 = lines.iter().map(|(_, total)| total).sum();
+    let mut discounts = Vec::new();
+    for promotion in promotions {
+        let eligible: f64 = lines
+            .iter()
+            .filter(|(product, _)| promotion.applies_to(product))
+            .map(|(_, total)| total)
+            .sum();
+        if eligible <= 0.0 || promotion.min_spend.is_some_and(|min| eligible < min) {
+            continue;
+        }
+        let amount = match promotion.kind {
+            DiscountKind::Percentage => (eligible * promotion.value / 100.0).round(),
+            DiscountKind::FixedAmount => promotion.value.min(eligible),
+        }
+        .min(remaining);
+        if amount <= 0.0 {
+            continue;
+        }
+        remaining -= amount;
+        discounts.push(Discount {
+            promotion_id: promotion.promotion_id.clone(),
+            code: promotion.code.clone(),
+            label: promotion.label.clone(),
+            amount,
+        });
+    }
+    discounts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{TimeDelta, Utc};
+
+    use super::{DELIVERY_FEE_PER_VENDOR, price_cart};
+    use crate::{
+        api::{cart::CartItem, model::mock_products},
+        models::{
+            cart_summary::CartWarningKind,
+            promotion::{DiscountKind, Promotion},
+        },
+    };
+
+    fn item(product_id: &str, quantity: u32, added_unit_price: Option<f64>) -> CartItem {
+        CartItem {
+            product_id: product_id.to_string(),
+            variant_sku: None,
+            quantity,
+            added_unit_price,
+        }
+    }
+
+    #[test]
+    fn test_totals_delivery_and_warnings() {
+        let products: HashMap<_, _> = mock_products()
+            .into_iter()
+            .map(|p| (p.id.clone(), p))
+            .collect();
+        let summary = price_cart(
+            vec![
+                // 4 stools in stock, added at an older price.
+                item("2", 5, Some(14000.0)),
+                item("3", 2, Some(5000.0)),
+                item("99", 1, Some(2000.0)),
+            ],
+            &products,
+            &[],
+        );
+
+        assert_eq!(summary.lines.len(), 3);
+        assert_eq!(summary.lines[0].line_total, 75000.0);
+        assert_eq!(summary.lines[2].line_total, 0.0);
+        assert_eq!(summary.subtotal, 85000.0);
+        // Free delivery over the threshold; vendors in two regions lengthen the lead time.
+        assert_eq!(summary.delivery.fee, 0.0);
+        assert_eq!(summary.delivery.max_days, 7);
+        assert_eq!(summary.grand_total, 85000.0);
+
+        let kinds: Vec<_> = summary
+            .warnings
+            .iter()
+            .map(|w| (w.kind, w.product_id.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (CartWarningKind::OutOfStock, "2"),
+                (CartWarningKind::PriceChanged, "2"),
+                (CartWarningKind::Unpublished, "99"),
+            ]
+        );
+        assert_eq!(summary.warnings[0].available, Some(4));
+        assert_eq!(summary.warnings[1].previous_price, Some(14000.0));
+
+        let small = price_cart(vec![item("3", 1, None)], &products, &[]);
+        assert_eq!(small.delivery.fee, DELIVERY_FEE_PER_VENDOR);
+        assert_eq!(small.grand_total, 5000.0 + DELIVERY_FEE_PER_VENDOR);
+        assert!(small.warnings.is_empty());
+    }
+
+    fn promotion(id: &str, kind: DiscountKind, value: f64, vendor: Option<&str>) -> Promotion {
+        Promotion {
+            promotion_id: id.to_string(),
+            code: None,
+            label: id.to_string(),
+            kind,
+            value,
+            vendor_name: vendor.map(String::from),
+            min_spend: None,
+            per_user_limit: None,
+            starts_at: Utc::now(),
+            ends_at: Utc::now() + TimeDelta::days(1),
+            categories: Vec::new(),
+            product_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_promotions_discount_the_items_they_cover() {
+        let products: HashMap<_, _> = mock_products()
+            .into_iter()
+            .map(|p| (p.id.clone(), p))
+            .collect();
+        let items = vec![item("2", 2, None), item("3", 1, None)];
+
+        let stools = promotion("stools", DiscountKind::Percentage, 10.0, Some("Atelier Fotso"));
+        let clothing = Promotion {
+            categories: vec!["clothing".to_string()],
+            ..promotion("clothing", DiscountKind::FixedAmount, 8000.0, None)
+        };
+        let big_spenders = Promotion {
+            min_spend: Some(50000.0),
+            ..promotion("big-spenders", DiscountKind::FixedAmount, 5000.0, None)
+        };
+        let summary = price_cart(items, &products, &[stools, clothing, big_spenders]);
+
+        // 10% of the 30000 of stools; the clothing voucher is capped at the 5000 shirt;
+        // the subtotal of 35000 is short of the minimum spend.
+        let amounts: Vec<_> = summary
+            .discounts
+            .iter()
+            .map(|d| (d.promotion_id.as_str(), d.amount))
+            .collect();
+        assert_eq!(amounts, [("stools", 3000.0), ("clothing", 5000.0)]);
+        assert_eq!(summary.discount_total, 8000.0);
+        assert_eq!(summary.grand_total, 35000.0 - 8000.0 + DELIVERY_FEE_PER_VENDOR * 2.0);
+
+        let everything = promotion("all", DiscountKind::Percentage, 100.0, None);
+        let free = promotion("free", DiscountKind::FixedAmount, 1000.0, None);
+        let summary = price_cart(vec![item("3", 1, None)], &products, &[everything, free]);
+        assert_eq!(summary.discount_total, 5000.0);
+        assert_eq!(summary.discounts.len(), 1);
+    }
+}
@@ -3,7 +3,19 @@ use std::sync::{Arc, Mutex};
 
 use axum::response::IntoResponse;
 
+use chrono::{DateTime, Duration, Utc};
+
 use crate::api::cart::CartItem;
+use crate::middleware::locale::localized_error;
+use crate::models::cart_metrics::CartMetrics;
+use crate::models::cart_sync::{CartOperationKind, OperationStatus};
+use crate::models::locale::{Locale, Localize, Translated};
+
+/// Units of a single cart line, and the most a cart can hold overall.
+pub const MIN_ITEM_QUANTITY: u32 = 1;
+pub const MAX_ITEM_QUANTITY: u32 = 99;
+pub const MAX_CART_LINES: usize = 50;
+pub const MAX_CART_UNITS: u32 = 300;
 
 /// Represents possible errors from CartService.
 #[derive(Debug, thiserror::Error)]
@@ -13,67 +25,579 @@ pub enum CartError {
     #[error("Cart not found for user")]
     CartNotFound,
     #[error("Item not found in cart: {0}")]
-    GenericError(String),
+    GenericError(Translated),
+    #[error("Invalid guest cart token")]
+    InvalidCartToken,
+    #[error("Product not found")]
+    ProductNotFound,
+    #[error("This item is not in the cart")]
+    ItemNotInCart,
+    #[error("This product comes in several variants; choose one")]
+    VariantRequired,
+    #[error("Unknown product variant: {0}")]
+    VariantNotFound(String),
+    #[error("Quantity must be between {MIN_ITEM_QUANTITY} and {MAX_ITEM_QUANTITY}")]
+    InvalidQuantity,
+    #[error("A cart holds at most {MAX_CART_LINES} different items")]
+    TooManyItems,
+    #[error("A cart holds at most {MAX_CART_UNITS} units in total")]
+    TooManyUnits,
+    #[error("Send the cart's current ETag in If-Match")]
+    PreconditionRequired,
+    #[error("The cart changed since it was last read")]
+    VersionMismatch,
+    #[error("Only {available} units of product {product_id} are left; update the cart first")]
+    OutOfStock { product_id: String, available: u32 },
 }
 impl IntoResponse for CartError {
     fn into_response(self) -> axum::response::Response {
         let status = match self {
             CartError::LockError => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            CartError::CartNotFound => axum::http::StatusCode::NOT_FOUND,
-            CartError::GenericError(_) => axum::http::StatusCode::BAD_REQUEST,
+            CartError::CartNotFound | CartError::ProductNotFound | CartError::ItemNotInCart => {
+                axum::http::StatusCode::NOT_FOUND
+            }
+            CartError::GenericError(_)
+            | CartError::InvalidCartToken
+            | CartError::VariantRequired
+            | CartError::VariantNotFound(_)
+            | CartError::InvalidQuantity
+            | CartError::TooManyItems
+            | CartError::TooManyUnits => axum::http::StatusCode::BAD_REQUEST,
+            CartError::PreconditionRequired => axum::http::StatusCode::PRECONDITION_REQUIRED,
+            CartError::VersionMismatch => axum::http::StatusCode::PRECONDITION_FAILED,
+            CartError::OutOfStock { .. } => axum::http::StatusCode::CONFLICT,
         };
-        (status, self.to_string()).into_response()
+        localized_error(status, &self)
+    }
+}
+impl Localize for CartError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, CartError::LockError) => {
+                "Impossible d'accéder au stockage des paniers".to_string()
+            }
+            (Locale::Fr, CartError::CartNotFound) => {
+                "Panier introuvable pour cet utilisateur".to_string()
+            }
+            (Locale::Fr, CartError::GenericError(detail)) => {
+                format!(
+                    "Article introuvable dans le panier : {}",
+                    detail.localized(locale)
+                )
+            }
+            (Locale::Fr, CartError::InvalidCartToken) => {
+                "Jeton de panier invité invalide".to_string()
+            }
+            (Locale::Fr, CartError::ProductNotFound) => "Produit introuvable".to_string(),
+            (Locale::Fr, CartError::ItemNotInCart) => {
+                "Cet article n'est pas dans le panier".to_string()
+            }
+            (Locale::Fr, CartError::VariantRequired) => {
+                "Ce produit existe en plusieurs variantes ; choisissez-en une".to_string()
+            }
+            (Locale::Fr, CartError::VariantNotFound(sku)) => {
+                format!("Variante de produit inconnue : {sku}")
+            }
+            (Locale::Fr, CartError::InvalidQuantity) => format!(
+                "La quantité doit être comprise entre {MIN_ITEM_QUANTITY} et {MAX_ITEM_QUANTITY}"
+            ),
+            (Locale::Fr, CartError::TooManyItems) => {
+                format!("Un panier contient au plus {MAX_CART_LINES} articles différents")
+            }
+            (Locale::Fr, CartError::TooManyUnits) => {
+                format!("Un panier contient au plus {MAX_CART_UNITS} unités au total")
+            }
+            (Locale::Fr, CartError::PreconditionRequired) => {
+                "Envoyez l'ETag actuel du panier dans If-Match".to_string()
+            }
+            (Locale::Fr, CartError::VersionMismatch) => {
+                "Le panier a changé depuis sa dernière lecture".to_string()
+            }
+            (Locale::Fr, CartError::OutOfStock { product_id, available }) => format!(
+                "Il ne reste que {available} unités du produit {product_id} ; \
+                 mettez d'abord le panier à jour"
+            ),
+        }
+    }
+}
+
+/// Checks the quantity of one line and the total of the cart once it holds `quantity`
+/// units of the line at `index` (or of a new line when `index` is `None`).
+fn check_limits(cart: &[CartItem], index: Option<usize>, quantity: u32) -> Result<(), CartError> {
+    if !(MIN_ITEM_QUANTITY..=MAX_ITEM_QUANTITY).contains(&quantity) {
+        return Err(CartError::InvalidQuantity);
+    }
+    if index.is_none() && cart.len() >= MAX_CART_LINES {
+        return Err(CartError::TooManyItems);
     }
+    let others: u32 = cart
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != index)
+        .map(|(_, item)| item.quantity)
+        .sum();
+    if others + quantity > MAX_CART_UNITS {
+        return Err(CartError::TooManyUnits);
+    }
+    Ok(())
+}
+
+/// Prefix of the storage keys of guest carts, which are never sent reminders.
+pub const GUEST_CART_PREFIX: &str = "guest:";
+
+/// A change made to the cart while offline, checked against the catalog by the caller.
+#[derive(Debug, Clone)]
+pub struct OfflineOperation {
+    pub kind: CartOperationKind,
+    /// The line and, for `add` and `update`, the quantity.
+    pub item: CartItem,
+    pub made_at: DateTime<Utc>,
 }
+
+/// A cart as read at one version.
+#[derive(Debug, Clone, Default)]
+pub struct CartSnapshot {
+    pub items: Vec<CartItem>,
+    /// Changes with every write; `0` when there is no cart yet.
+    pub version: u64,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub coupon: Option<String>,
+}
+
+/// A cart with the bookkeeping used for versioning, expiry and abandoned-cart reminders.
+#[derive(Debug, Clone)]
+struct StoredCart {
+    items: Vec<CartItem>,
+    version: u64,
+    updated_at: DateTime<Utc>,
+    /// When the owner was last reminded of the cart; cleared at checkout.
+    reminded_at: Option<DateTime<Utc>>,
+    /// When the owner last checked the cart out. Until the cart changes again it isn't
+    /// abandoned, whatever its age.
+    checked_out_at: Option<DateTime<Utc>>,
+    coupon: Option<String>,
+    /// When each line (product and variant) last changed, kept after the line is
+    /// removed, to resolve conflicts with changes made offline.
+    line_changes: HashMap<(String, Option<String>), DateTime<Utc>>,
+}
+
+impl StoredCart {
+    fn new() -> Self {
+        StoredCart {
+            items: Vec::new(),
+            version: 0,
+            updated_at: Utc::now(),
+            reminded_at: None,
+            checked_out_at: None,
+            coupon: None,
+            line_changes: HashMap::new(),
+        }
+    }
+
+    fn position(&self, product_id: &str, variant_sku: Option<&str>) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|i| i.product_id == product_id && i.variant_sku.as_deref() == variant_sku)
+    }
+
+    fn record_change(&mut self, product_id: &str, variant_sku: Option<&str>, at: DateTime<Utc>) {
+        let key = (product_id.to_string(), variant_sku.map(String::from));
+        self.line_changes.insert(key, at);
+    }
+
+    fn last_change(&self, product_id: &str, variant_sku: Option<&str>) -> Option<DateTime<Utc>> {
+        let key = (product_id.to_string(), variant_sku.map(String::from));
+        self.line_changes.get(&key).copied()
+    }
+
+    /// Adds `item`, incrementing the quantity of its line if already there.
+    fn add(&mut self, item: CartItem, at: DateTime<Utc>) -> Result<(), CartError> {
+        if item.quantity == 0 {
+            return Err(CartError::InvalidQuantity);
+        }
+        match self.position(&item.product_id, item.variant_sku.as_deref()) {
+            Some(index) => {
+                let quantity = self.items[index]
+                    .quantity
+                    .checked_add(item.quantity)
+                    .ok_or(CartError::InvalidQuantity)?;
+                check_limits(&self.items, Some(index), quantity)?;
+                self.items[index].quantity = quantity;
+            }
+            None => {
+                check_limits(&self.items, None, item.quantity)?;
+                self.items.push(item.clone());
+            }
+        }
+        self.record_change(&item.product_id, item.variant_sku.as_deref(), at);
+        Ok(())
+    }
+
+    /// Sets the quantity of `item`'s line; 0 removes it.
+    fn set_quantity(&mut self, item: &CartItem, at: DateTime<Utc>) -> Result<(), CartError> {
+        let index = self
+            .position(&item.product_id, item.variant_sku.as_deref())
+            .ok_or(CartError::ItemNotInCart)?;
+        if item.quantity == 0 {
+            self.items.remove(index);
+        } else {
+            check_limits(&self.items, Some(index), item.quantity)?;
+            self.items[index].quantity = item.quantity;
+        }
+        self.record_change(&item.product_id, item.variant_sku.as_deref(), at);
+        Ok(())
+    }
+
+    fn replay(
+        &mut self,
+        operation: OfflineOperation,
+        now: DateTime<Utc>,
+    ) -> Result<OperationStatus, CartError> {
+        let made_at = operation.made_at.min(now);
+        let item = operation.item;
+        let changed_since = self
+            .last_change(&item.product_id, item.variant_sku.as_deref())
+            .is_some_and(|changed| changed > made_at);
+        if changed_since {
+            return Ok(OperationStatus::Superseded);
+        }
+        match operation.kind {
+            CartOperationKind::Add => self.add(item, made_at)?,
+            CartOperationKind::Update => self.set_quantity(&item, made_at)?,
+            CartOperationKind::Remove => {
+                self.remove(&item.product_id, item.variant_sku.as_deref(), made_at)?
+            }
+        }
+        Ok(OperationStatus::Applied)
+    }
+
+    fn remove(
+        &mut self,
+        product_id: &str,
+        variant_sku: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> Result<(), CartError> {
+        let index = self
+            .position(product_id, variant_sku)
+            .ok_or(CartError::ItemNotInCart)?;
+        self.items.remove(index);
+        self.record_change(product_id, variant_sku, at);
+        Ok(())
+    }
+
+    fn check_version(&self, expected: Option<u64>) -> Result<(), CartError> {
+        match expected {
+            Some(version) if version != self.version => Err(CartError::VersionMismatch),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CartStore {
+    carts: HashMap<String, StoredCart>,
+    /// Last version handed out. Versions are unique across carts, so a cart deleted and
+    /// started again never matches an ETag read before.
+    last_version: u64,
+    expired: u64,
+    reminders_sent: u64,
+    recovered: u64,
+}
+
+impl CartStore {
+    /// Records a write to the cart under `key`, returning its new version.
+    fn touch(&mut self, key: &str) -> u64 {
+        self.last_version += 1;
+        let version = self.last_version;
+        if let Some(cart) = self.carts.get_mut(key) {
+            cart.version = version;
+            cart.updated_at = Utc::now();
+        }
+        version
+    }
+}
+
 #[derive(Clone)]
 pub struct CartService {
-    carts: Arc<Mutex<HashMap<String, Vec<CartItem>>>>,
+    store: Arc<Mutex<CartStore>>,
 }
 
 impl CartService {
     pub fn new() -> Self {
         CartService {
-            carts: Arc::new(Mutex::new(HashMap::new())),
+            store: Arc::new(Mutex::new(CartStore::default())),
         }
     }
 
-    /// Adds an item to the user's cart. If the item exists, increments the quantity.
-    pub fn add_item(&self, user_id: String, item: CartItem) -> Result<(), CartError> {
-        let mut carts = self.carts.lock().map_err(|_| CartError::LockError)?;
-        let cart = carts.entry(user_id).or_insert_with(Vec::new);
+    /// Adds an item to the user's cart. If the same product variant is already there,
+    /// increments its quantity. The caller checks that the product exists. With
+    /// `expected_version`, fails with `VersionMismatch` if the cart changed since (`0`
+    /// expects no cart yet). Returns the new version of the cart.
+    pub fn add_item(
+        &self,
+        user_id: String,
+        item: CartItem,
+        expected_version: Option<u64>,
+    ) -> Result<u64, CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        if !store.carts.contains_key(&user_id)
+            && expected_version.is_some_and(|version| version != 0)
+        {
+            return Err(CartError::VersionMismatch);
+        }
+        let cart = store.carts.entry(user_id.clone()).or_insert_with(StoredCart::new);
+        cart.check_version(expected_version)?;
+        cart.add(item, Utc::now())?;
+        Ok(store.touch(&user_id))
+    }
 
-        if let Some(existing) = cart.iter_mut().find(|i| i.product_id == item.product_id) {
-            existing.quantity += item.quantity;
-        } else {
-            cart.push(item);
+    /// Sets an item's quantity in the user's cart; a quantity of 0 removes the item.
+    /// With `expected_version`, fails with `VersionMismatch` if the cart changed since.
+    /// Returns the new version of the cart.
+    pub fn update_item(
+        &self,
+        user_id: String,
+        item: CartItem,
+        expected_version: Option<u64>,
+    ) -> Result<u64, CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        let cart = store.carts.get_mut(&user_id);
+        let cart = match cart {
+            Some(cart) => cart,
+            None if expected_version.is_some_and(|version| version != 0) => {
+                return Err(CartError::VersionMismatch);
+            }
+            None => return Err(CartError::ItemNotInCart),
+        };
+        cart.check_version(expected_version)?;
+        cart.set_quantity(&item, Utc::now())?;
+        Ok(store.touch(&user_id))
+    }
+
+    /// Removes an item (the given variant of a product) from the user's cart, checking
+    /// `expected_version` like `update_item`. Returns the new version of the cart.
+    pub fn remove_item(
+        &self,
+        user_id: String,
+        product_id: String,
+        variant_sku: Option<String>,
+        expected_version: Option<u64>,
+    ) -> Result<u64, CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        let cart = store.carts.get_mut(&user_id);
+        let cart = match cart {
+            Some(cart) => cart,
+            None if expected_version.is_some_and(|version| version != 0) => {
+                return Err(CartError::VersionMismatch);
+            }
+            None => return Err(CartError::ItemNotInCart),
+        };
+        cart.check_version(expected_version)?;
+        cart.remove(&product_id, variant_sku.as_deref(), Utc::now())?;
+        Ok(store.touch(&user_id))
+    }
+
+    /// Retrieves the user's cart.
+    pub fn get_cart(&self, user_id: String) -> Result<Vec<CartItem>, CartError> {
+        let store = self.store.lock().map_err(|_| CartError::LockError)?;
+        Ok(store
+            .carts
+            .get(&user_id)
+            .map(|cart| cart.items.clone())
+            .unwrap_or_default())
+    }
+
+    /// Retrieves the user's cart with its version, read together.
+    pub fn snapshot(&self, user_id: &str) -> Result<CartSnapshot, CartError> {
+        let store = self.store.lock().map_err(|_| CartError::LockError)?;
+        Ok(store
+            .carts
+            .get(user_id)
+            .map(|cart| CartSnapshot {
+                items: cart.items.clone(),
+                version: cart.version,
+                updated_at: Some(cart.updated_at),
+                coupon: cart.coupon.clone(),
+            })
+            .unwrap_or_default())
+    }
+
+    /// Applies a coupon code to the user's cart, replacing any other, or with `None`
+    /// takes it off. Checking the code is up to the caller. Returns the new version.
+    pub fn set_coupon(&self, user_id: &str, code: Option<String>) -> Result<u64, CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        let cart = store
+            .carts
+            .entry(user_id.to_string())
+            .or_insert_with(StoredCart::new);
+        cart.coupon = code;
+        Ok(store.touch(user_id))
+    }
+
+    /// Moves the lines of the cart stored under `from` into the one under `into` and
+    /// deletes the former; its coupon carries over unless `into` has one. Quantities of
+    /// lines in both carts are summed, then capped at `stock_limit` (`None` for untracked
+    /// stock) and the cart limits without going below what `into` already held; lines
+    /// with nothing left in stock or no room left are dropped. Returns the merged cart.
+    pub fn merge_carts(
+        &self,
+        from: &str,
+        into: String,
+        stock_limit: impl Fn(&CartItem) -> Option<u32>,
+    ) -> Result<Vec<CartItem>, CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        let (merged, coupon) = store
+            .carts
+            .remove(from)
+            .map(|cart| (cart.items, cart.coupon))
+            .unwrap_or_default();
+        let cart = store.carts.entry(into.clone()).or_insert_with(StoredCart::new);
+        if cart.coupon.is_none() {
+            cart.coupon = coupon;
+        }
+        let now = Utc::now();
+        for item in merged {
+            cart.record_change(&item.product_id, item.variant_sku.as_deref(), now);
+            let limit = stock_limit(&item).map_or(MAX_ITEM_QUANTITY, |stock| {
+                stock.min(MAX_ITEM_QUANTITY)
+            });
+            let total: u32 = cart.items.iter().map(|i| i.quantity).sum();
+            let room = MAX_CART_UNITS.saturating_sub(total);
+            if let Some(existing) = cart.items.iter_mut().find(|i| i.is_same_line(&item)) {
+                let wanted = item.quantity.min(room) + existing.quantity;
+                existing.quantity = wanted.min(limit.max(existing.quantity));
+            } else if cart.items.len() < MAX_CART_LINES {
+                let quantity = item.quantity.min(limit).min(room);
+                if quantity > 0 {
+                    cart.items.push(CartItem { quantity, ..item });
+                }
+            }
         }
+        let items = cart.items.clone();
+        store.touch(&into);
+        Ok(items)
+    }
 
-        Ok(())
+    /// Replays changes made offline on the user's cart as a single write. Changes are
+    /// applied oldest first (ties in the order given), times in the future counting as
+    /// `now`. A change older than the last change to its line, made here or on another
+    /// device, is superseded and skipped; one the cart refuses is rejected without
+    /// affecting the others. The changes are replayed on a copy of the cart, stored only
+    /// if at least one was applied, so a sync that changes nothing doesn't create a cart.
+    /// Returns each change's outcome in the order given.
+    pub fn apply_offline(
+        &self,
+        user_id: &str,
+        operations: Vec<Result<OfflineOperation, CartError>>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Result<OperationStatus, CartError>>, CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        let mut cart = store
+            .carts
+            .get(user_id)
+            .cloned()
+            .unwrap_or_else(StoredCart::new);
+
+        let mut operations: Vec<_> = operations.into_iter().enumerate().collect();
+        operations.sort_by_key(|(_, op)| op.as_ref().ok().map(|op| op.made_at.min(now)));
+        let mut outcomes: Vec<_> = operations
+            .into_iter()
+            .map(|(index, op)| (index, op.and_then(|op| cart.replay(op, now))))
+            .collect();
+        outcomes.sort_by_key(|(index, _)| *index);
+
+        if outcomes
+            .iter()
+            .any(|(_, outcome)| matches!(outcome, Ok(OperationStatus::Applied)))
+        {
+            store.carts.insert(user_id.to_string(), cart);
+            store.touch(user_id);
+        }
+        Ok(outcomes.into_iter().map(|(_, outcome)| outcome).collect())
     }
 
-    /// Updates an item's quantity in the user's cart.
-    pub fn update_item(&self, user_id: String, item: CartItem) -> Result<(), CartError> {
-        let mut carts = self.carts.lock().map_err(|_| CartError::LockError)?;
-        if let Some(cart) = carts.get_mut(&user_id) {
-            if let Some(existing) = cart.iter_mut().find(|i| i.product_id == item.product_id) {
-                existing.quantity = item.quantity;
+    /// Deletes carts left untouched for `ttl`. Returns how many were deleted.
+    pub fn expire_carts(&self, now: DateTime<Utc>, ttl: Duration) -> Result<usize, CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        let before = store.carts.len();
+        store.carts.retain(|_, cart| now - cart.updated_at < ttl);
+        let expired = before - store.carts.len();
+        store.expired += expired as u64;
+        Ok(expired)
+    }
+
+    /// Signed-in users' non-empty carts left untouched for `after`, with their items,
+    /// each returned once per abandonment: a cart is only returned again once it has
+    /// changed since. Carts checked out since their last change aren't abandoned.
+    pub fn take_abandoned(
+        &self,
+        now: DateTime<Utc>,
+        after: Duration,
+    ) -> Result<Vec<(String, Vec<CartItem>)>, CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        let mut abandoned = Vec::new();
+        for (user_id, cart) in store.carts.iter_mut() {
+            let handled_since_change = |at: Option<DateTime<Utc>>| {
+                at.is_some_and(|at| at >= cart.updated_at)
+            };
+            let due = !user_id.starts_with(GUEST_CART_PREFIX)
+                && !cart.items.is_empty()
+                && now - cart.updated_at >= after
+                && !handled_since_change(cart.reminded_at)
+                && !handled_since_change(cart.checked_out_at);
+            if due {
+                cart.reminded_at = Some(now);
+                abandoned.push((user_id.clone(), cart.items.clone()));
             }
         }
+        Ok(abandoned)
+    }
+
+    /// Counts reminders actually sent for carts returned by `take_abandoned`.
+    pub fn record_reminders_sent(&self, count: u64) -> Result<(), CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        store.reminders_sent += count;
         Ok(())
     }
 
-    /// Removes an item from the user's cart.
-    pub fn remove_item(&self, user_id: String, product_id: String) -> Result<(), CartError> {
-        let mut carts = self.carts.lock().map_err(|_| CartError::LockError)?;
-        if let Some(cart) = carts.get_mut(&user_id) {
-            cart.retain(|i| i.product_id != product_id);
+    /// Records that the user checked out, which counts as a recovery if they had been
+    /// reminded of their cart. The cart isn't reminded of again until it changes.
+    pub fn record_checkout(&self, user_id: &str) -> Result<(), CartError> {
+        let mut store = self.store.lock().map_err(|_| CartError::LockError)?;
+        if let Some(cart) = store.carts.get_mut(user_id) {
+            cart.checked_out_at = Some(Utc::now());
+            if cart.reminded_at.take().is_some() {
+                store.recovered += 1;
+            }
         }
         Ok(())
     }
 
-    /// Retrieves the user's cart.
-    pub fn get_cart(&self, user_id: String) -> Result<Vec<CartItem>, CartError> {
-        let carts = self.carts.lock().map_err(|_| CartError::LockError)?;
-        Ok(carts.get(&user_id).cloned().unwrap_or_else(Vec::new))
+    pub fn metrics(&self) -> Result<CartMetrics, CartError> {
+        let store = self.store.lock().map_err(|_| CartError::LockError)?;
+        let recovery_rate = if store.reminders_sent == 0 {
+            0.0
+        } else {
+            store.recovered as f64 / store.reminders_sent as f64
+        };
+        Ok(CartMetrics {
+            active_carts: store.carts.len(),
+            expired_carts: store.expired,
+            reminders_sent: store.reminders_sent,
+            recovered_carts: store.recovered,
+            recovery_rate,
+        })
+    }
+
+    /// Checks that the cart storage can still be locked (used by readiness probes).
+    pub fn ping(&self) -> Result<(), CartError> {
+        self.store.lock().map(|_| ()).map_err(|_| CartError::LockError)
+    }
+}
+
+impl Default for CartService {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,145 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    api::cart::CartItem, models::locale::Locale, services::cart_services::CartError,
+    state::AppState,
+};
+
+/// What one pass of the sweeper did.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    pub expired: usize,
+    pub reminded: usize,
+}
+
+/// Deletes expired carts, then texts signed-in users whose cart has been idle for
+/// `abandoned_cart_after`. A reminder that can't be queued is logged and not retried.
+pub fn sweep(state: &AppState, now: DateTime<Utc>) -> Result<SweepReport, CartError> {
+    let config = &state.config;
+    let expired = state
+        .cart_service
+        .expire_carts(now, to_delta(config.cart_ttl))?;
+    let abandoned = state
+        .cart_service
+        .take_abandoned(now, to_delta(config.abandoned_cart_after))?;
+
+    let mut reminded = 0;
+    for (user_id, items) in abandoned {
+        let locale = state
+            .preference_service
+            .language(&user_id)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        match state
+            .notification_service
+            .send_sms(&user_id, reminder_text(locale, &items), now)
+        {
+            Ok(_) => reminded += 1,
+            Err(err) => eprintln!("⚠️ Failed to send cart reminder to {user_id}: {err}"),
+        }
+    }
+    state.cart_service.record_reminders_sent(reminded as u64)?;
+    Ok(SweepReport { expired, reminded })
+}
+
+/// Sweeps carts every `cart_sweep_interval` until `token` is cancelled.
+pub async fn run_cart_sweeper(token: CancellationToken, state: AppState) {
+    let mut interval = tokio::time::interval(state.config.cart_sweep_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = interval.tick() => {
+                if let Err(err) = sweep(&state, Utc::now()) {
+                    eprintln!("⚠️ Cart sweep failed: {err}");
+                }
+            }
+        }
+    }
+}
+
+fn reminder_text(locale: Locale, items: &[CartItem]) -> String {
+    let units: u32 = items.iter().map(|item| item.quantity).sum();
+    match locale {
+        Locale::Fr => {
+            format!("Votre panier vous attend : {units} article(s) à finaliser sur le marché.")
+        }
+        Locale::En => format!("Your cart is waiting: {units} item(s) left to check out."),
+    }
+}
+
+/// Configured durations fit in a `TimeDelta` unless absurdly large, which means never.
+fn to_delta(duration: std::time::Duration) -> TimeDelta {
+    TimeDelta::from_std(duration).unwrap_or(TimeDelta::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+
+    use super::{SweepReport, sweep};
+    use crate::{api::cart::CartItem, models::locale::Locale, state::AppState};
+
+    fn item(product_id: &str) -> CartItem {
+        CartItem {
+            product_id: product_id.to_string(),
+            variant_sku: None,
+            quantity: 2,
+            added_unit_price: None,
+        }
+    }
+
+    #[test]
+    fn test_sweep_expires_and_reminds_once() {
+        let state = AppState::default();
+        let carts = &state.cart_service;
+        carts.add_item("user123".to_string(), item("1"), None).unwrap();
+        carts.add_item("guest:abc".to_string(), item("2"), None).unwrap();
+        state
+            .preference_service
+            .set_language("user123", Some(Locale::Fr))
+            .unwrap();
+
+        // Nothing is due yet.
+        let now = Utc::now();
+        assert_eq!(sweep(&state, now).unwrap(), SweepReport::default());
+
+        // Idle for two days: only the signed-in user is reminded, and only once.
+        let later = now + TimeDelta::hours(48);
+        let report = sweep(&state, later).unwrap();
+        assert_eq!(
+            report,
+            SweepReport {
+                expired: 0,
+                reminded: 1
+            }
+        );
+        assert_eq!(sweep(&state, later).unwrap().reminded, 0);
+        let outbox = state.notification_service.sms_outbox().unwrap();
+        assert_eq!(outbox.len(), 1);
+        assert_eq!(outbox[0].user_id, "user123");
+        assert!(outbox[0].text.starts_with("Votre panier"));
+
+        carts.record_checkout("user123").unwrap();
+        let metrics = carts.metrics().unwrap();
+        assert_eq!((metrics.reminders_sent, metrics.recovered_carts), (1, 1));
+        assert_eq!(metrics.recovery_rate, 1.0);
+
+        // A checked-out cart isn't abandoned, however long it then sits idle.
+        let report = sweep(&state, now + TimeDelta::days(3)).unwrap();
+        assert_eq!(report.reminded, 0);
+        assert_eq!(state.notification_service.sms_outbox().unwrap().len(), 1);
+        carts.record_checkout("user123").unwrap();
+        let metrics = carts.metrics().unwrap();
+        assert_eq!((metrics.reminders_sent, metrics.recovered_carts), (1, 1));
+
+        // Past the TTL both carts go.
+        let report = sweep(&state, now + TimeDelta::days(31)).unwrap();
+        assert_eq!(report.expired, 2);
+        assert!(carts.get_cart("user123".to_string()).unwrap().is_empty());
+        assert_eq!(carts.metrics().unwrap().expired_carts, 2);
+    }
+}
@@ -0,0 +1,524 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    middleware::locale::localized_error,
+    models::{
+        category::{AttributeKind, AttributeSchema, Category},
+        locale::{Locale, Localize, Translated},
+    },
+    services::search::fold,
+};
+
+/// Categories nested deeper than this are refused; it also bounds ancestor walks.
+const MAX_DEPTH: usize = 6;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CategoryError {
+    #[error("Failed to lock the category storage")]
+    LockError,
+    #[error("Category not found")]
+    NotFound,
+    #[error("A category with slug {0} already exists")]
+    AlreadyExists(String),
+    #[error("Category {0} still has subcategories or products")]
+    InUse(String),
+    #[error("Invalid category: {0}")]
+    InvalidCategory(Translated),
+}
+
+impl IntoResponse for CategoryError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            CategoryError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+            CategoryError::NotFound => StatusCode::NOT_FOUND,
+            CategoryError::AlreadyExists(_) | CategoryError::InUse(_) => StatusCode::CONFLICT,
+            CategoryError::InvalidCategory(_) => StatusCode::BAD_REQUEST,
+        };
+        localized_error(status, &self)
+    }
+}
+
+impl Localize for CategoryError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, CategoryError::LockError) => {
+                "Impossible d'accéder au stockage des catégories".to_string()
+            }
+            (Locale::Fr, CategoryError::NotFound) => "Catégorie introuvable".to_string(),
+            (Locale::Fr, CategoryError::AlreadyExists(slug)) => {
+                format!("Une catégorie de slug {slug} existe déjà")
+            }
+            (Locale::Fr, CategoryError::InUse(slug)) => {
+                format!("La catégorie {slug} a encore des sous-catégories ou des produits")
+            }
+            (Locale::Fr, CategoryError::InvalidCategory(detail)) => {
+                format!("Catégorie invalide : {}", detail.localized(locale))
+            }
+        }
+    }
+}
+
+/// The admin-managed category tree products are filed under.
+#[derive(Clone)]
+pub struct CategoryService {
+    categories: Arc<Mutex<BTreeMap<String, Category>>>,
+}
+
+fn category(
+    slug: &str,
+    parent: Option<&str>,
+    name_fr: &str,
+    name_en: &str,
+    attributes: Vec<AttributeSchema>,
+) -> Category {
+    Category {
+        slug: slug.to_string(),
+        parent: parent.map(String::from),
+        name_fr: name_fr.to_string(),
+        name_en: name_en.to_string(),
+        attributes,
+    }
+}
+
+fn attribute(name: &str, kind: AttributeKind, required: bool, options: &[&str]) -> AttributeSchema {
+    AttributeSchema {
+        name: name.to_string(),
+        kind,
+        required,
+        options: options.iter().map(|o| o.to_string()).collect(),
+    }
+}
+
+impl CategoryService {
+    pub fn new() -> Self {
+        let seed = [
+            category("clothing", None, "Vêtements", "Clothing", Vec::new()),
+            category("shoes", Some("clothing"), "Chaussures", "Shoes", Vec::new()),
+            category("crafts", None, "Artisanat", "Crafts", Vec::new()),
+            category(
+                "furniture",
+                None,
+                "Meubles",
+                "Furniture",
+                vec![attribute("wood", AttributeKind::Text, false, &[])],
+            ),
+            category("food", None, "Alimentation", "Food", Vec::new()),
+            category(
+                "spices",
+                Some("food"),
+                "Épices",
+                "Spices",
+                vec![attribute("net_weight_g", AttributeKind::Number, true, &[])],
+            ),
+            category(
+                "pepper",
+                Some("spices"),
+                "Poivre",
+                "Pepper",
+                vec![
+                    attribute(
+                        "color",
+                        AttributeKind::Choice,
+                        false,
+                        &["black", "white", "red"],
+                    ),
+                    attribute("penja_pgi", AttributeKind::Boolean, false, &[]),
+                ],
+            ),
+        ];
+        CategoryService {
+            categories: Arc::new(Mutex::new(
+                seed.into_iter().map(|c| (c.slug.clone(), c)).collect(),
+            )),
+        }
+    }
+
+    /// All categories, each parent before its children, siblings by slug.
+    pub fn list(&self) -> Result<Vec<Category>, CategoryError> {
+        let categories = self.lock()?;
+        let mut ordered = Vec::with_capacity(categories.len());
+        let mut pending: Vec<&Category> = categories
+            .values()
+            .filter(|c| c.parent.is_none())
+            .rev()
+            .collect();
+        while let Some(category) = pending.pop() {
+            ordered.push(category.clone());
+            pending.extend(
+                categories
+                    .values()
+                    .filter(|c| c.parent.as_deref() == Some(category.slug.as_str()))
+                    .rev(),
+            );
+        }
+        Ok(ordered)
+    }
+
+    pub fn get(&self, slug: &str) -> Result<Category, CategoryError> {
+        self.lock()?
+            .get(slug)
+            .cloned()
+            .ok_or(CategoryError::NotFound)
+    }
+
+    /// Finds a category by slug or by French or English name, ignoring case and accents.
+    pub fn resolve(&self, key: &str) -> Result<Category, CategoryError> {
+        let categories = self.lock()?;
+        let key = fold(key.trim());
+        categories
+            .values()
+            .find(|c| {
+                [&c.slug, &c.name_fr, &c.name_en]
+                    .iter()
+                    .any(|k| fold(k) == key)
+            })
+            .cloned()
+            .ok_or(CategoryError::NotFound)
+    }
+
+    /// Slugs of the category and all of its descendants.
+    pub fn subtree(&self, slug: &str) -> Result<HashSet<String>, CategoryError> {
+        let categories = self.lock()?;
+        if !categories.contains_key(slug) {
+            return Err(CategoryError::NotFound);
+        }
+        let mut slugs = HashSet::from([slug.to_string()]);
+        let mut frontier = vec![slug.to_string()];
+        while let Some(parent) = frontier.pop() {
+            for child in categories
+                .values()
+                .filter(|c| c.parent.as_deref() == Some(parent.as_str()))
+            {
+                if slugs.insert(child.slug.clone()) {
+                    frontier.push(child.slug.clone());
+                }
+            }
+        }
+        Ok(slugs)
+    }
+
+    /// The attributes products in `slug` are described with, the root's first.
+    pub fn attribute_schema(&self, slug: &str) -> Result<Vec<AttributeSchema>, CategoryError> {
+        let categories = self.lock()?;
+        let mut chain = ancestry(&categories, slug).ok_or(CategoryError::NotFound)?;
+        chain.reverse();
+        Ok(chain
+            .into_iter()
+            .flat_map(|c| c.attributes.iter().cloned())
+            .collect())
+    }
+
+    /// Checks product attributes against the category's schema: required ones present,
+    /// no unknown keys, and values of the declared kind.
+    pub fn validate_attributes(
+        &self,
+        slug: &str,
+        attributes: &BTreeMap<String, String>,
+    ) -> Result<(), CategoryError> {
+        let schema = self.attribute_schema(slug)?;
+        let invalid =
+            |en: String, fr: String| Err(CategoryError::InvalidCategory(Translated::new(en, fr)));
+
+        for name in attributes.keys() {
+            if !schema.iter().any(|a| &a.name == name) {
+                return invalid(
+                    format!("attribute {name} is not defined for category {slug}"),
+                    format!("l'attribut {name} n'existe pas pour la catégorie {slug}"),
+                );
+            }
+        }
+        for attribute in &schema {
+            let Some(value) = attributes.get(&attribute.name) else {
+                if attribute.required {
+                    return invalid(
+                        format!("attribute {} is required", attribute.name),
+                        format!("l'attribut {} est obligatoire", attribute.name),
+                    );
+                }
+                continue;
+            };
+            let valid = match attribute.kind {
+                AttributeKind::Text => !value.trim().is_empty(),
+                AttributeKind::Number => value.trim().parse::<f64>().is_ok_and(f64::is_finite),
+                AttributeKind::Boolean => matches!(value.as_str(), "true" | "false"),
+                AttributeKind::Choice => attribute.options.contains(value),
+            };
+            if !valid {
+                return invalid(
+                    format!(
+                        "{value:?} is not a valid value for attribute {}",
+                        attribute.name
+                    ),
+                    format!(
+                        "{value:?} n'est pas une valeur valide pour l'attribut {}",
+                        attribute.name
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn create(&self, category: Category) -> Result<Category, CategoryError> {
+        let mut categories = self.lock()?;
+        if categories.contains_key(&category.slug) {
+            return Err(CategoryError::AlreadyExists(category.slug));
+        }
+        check(&categories, &category)?;
+        categories.insert(category.slug.clone(), category.clone());
+        Ok(category)
+    }
+
+    /// Replaces a category's names, parent and attributes; the slug can't change.
+    pub fn update(&self, category: Category) -> Result<Category, CategoryError> {
+        let mut categories = self.lock()?;
+        if !categories.contains_key(&category.slug) {
+            return Err(CategoryError::NotFound);
+        }
+        check(&categories, &category)?;
+        categories.insert(category.slug.clone(), category.clone());
+        Ok(category)
+    }
+
+    /// Deletes a leaf category. Whether products still use it is up to the caller.
+    pub fn delete(&self, slug: &str) -> Result<(), CategoryError> {
+        let mut categories = self.lock()?;
+        if !categories.contains_key(slug) {
+            return Err(CategoryError::NotFound);
+        }
+        if categories
+            .values()
+            .any(|c| c.parent.as_deref() == Some(slug))
+        {
+            return Err(CategoryError::InUse(slug.to_string()));
+        }
+        categories.remove(slug);
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, BTreeMap<String, Category>>, CategoryError> {
+        self.categories.lock().map_err(|_| CategoryError::LockError)
+    }
+}
+
+impl Default for CategoryService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The category followed by its ancestors, or `None` if it or an ancestor is missing.
+fn ancestry<'a>(
+    categories: &'a BTreeMap<String, Category>,
+    slug: &str,
+) -> Option<Vec<&'a Category>> {
+    let mut chain = vec![categories.get(slug)?];
+    while let Some(parent) = chain.last()?.parent.as_deref() {
+        if chain.len() > MAX_DEPTH {
+            return None;
+        }
+        chain.push(categories.get(parent)?);
+    }
+    Some(chain)
+}
+
+/// Validates `category` as it would be stored alongside the other `categories`.
+fn check(
+    categories: &BTreeMap<String, Category>,
+    category: &Category,
+) -> Result<(), CategoryError> {
+    let invalid =
+        |en: String, fr: String| Err(CategoryError::InvalidCategory(Translated::new(en, fr)));
+
+    let slug_ok = !category.slug.is_empty()
+        && !category.slug.starts_with('-')
+        && !category.slug.ends_with('-')
+        && category
+            .slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !slug_ok {
+        return invalid(
+            "slugs use lowercase letters, digits and inner hyphens".to_string(),
+            "les slugs s'écrivent en minuscules, chiffres et tirets intérieurs".to_string(),
+        );
+    }
+    if category.name_fr.trim().is_empty() || category.name_en.trim().is_empty() {
+        return invalid(
+            "French and English names are required".to_string(),
+            "les noms en français et en anglais sont obligatoires".to_string(),
+        );
+    }
+
+    // Walk up from the new parent: it must exist, stay shallow and not be the category itself.
+    let mut inherited: Vec<&AttributeSchema> = Vec::new();
+    if let Some(ref parent) = category.parent {
+        let ancestors = ancestry(categories, parent).ok_or_else(|| {
+            CategoryError::InvalidCategory(Translated::new(
+                format!("unknown parent {parent}"),
+                format!("catégorie parente inconnue : {parent}"),
+            ))
+        })?;
+        if ancestors.iter().any(|c| c.slug == category.slug) {
+            return invalid(
+                "a category can't be its own ancestor".to_string(),
+                "une catégorie ne peut pas être son propre ancêtre".to_string(),
+            );
+        }
+        if ancestors.len() >= MAX_DEPTH {
+            return invalid(
+                format!("categories nest at most {MAX_DEPTH} levels deep"),
+                format!("les catégories s'imbriquent sur {MAX_DEPTH} niveaux au plus"),
+            );
+        }
+        inherited.extend(ancestors.iter().flat_map(|c| &c.attributes));
+    }
+
+    let mut names: HashSet<&str> = inherited.iter().map(|a| a.name.as_str()).collect();
+    for attribute in &category.attributes {
+        if attribute.name.trim().is_empty() {
+            return invalid(
+                "attribute names can't be blank".to_string(),
+                "les noms d'attribut ne peuvent pas être vides".to_string(),
+            );
+        }
+        if !names.insert(&attribute.name) {
+            return invalid(
+                format!("attribute {} is defined twice", attribute.name),
+                format!("l'attribut {} est défini deux fois", attribute.name),
+            );
+        }
+        if (attribute.kind == AttributeKind::Choice) == attribute.options.is_empty() {
+            return invalid(
+                format!(
+                    "attribute {} must list options if and only if it is a choice",
+                    attribute.name
+                ),
+                format!(
+                    "l'attribut {} doit lister des options si et seulement s'il est un choix",
+                    attribute.name
+                ),
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+
+    use super::{CategoryError, CategoryService};
+    use crate::models::{
+        category::Category,
+        locale::{Locale, Localize},
+    };
+
+    fn new_category(slug: &str, parent: Option<&str>) -> Category {
+        Category {
+            slug: slug.to_string(),
+            parent: parent.map(String::from),
+            name_fr: slug.to_string(),
+            name_en: slug.to_string(),
+            attributes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_tree_and_inherited_attributes() {
+        let service = CategoryService::new();
+        assert_eq!(
+            service.subtree("food").unwrap(),
+            HashSet::from(["food", "spices", "pepper"].map(String::from))
+        );
+        assert_eq!(service.resolve("Épices").unwrap().slug, "spices");
+        assert_eq!(service.resolve("SPICES").unwrap().slug, "spices");
+
+        let schema: Vec<String> = service
+            .attribute_schema("pepper")
+            .unwrap()
+            .into_iter()
+            .map(|a| a.name)
+            .collect();
+        assert_eq!(schema, ["net_weight_g", "color", "penja_pgi"]);
+
+        let attributes = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert!(
+            service
+                .validate_attributes(
+                    "pepper",
+                    &attributes(&[("net_weight_g", "250"), ("color", "white")])
+                )
+                .is_ok()
+        );
+        for invalid in [
+            attributes(&[]),
+            attributes(&[("net_weight_g", "a lot")]),
+            attributes(&[("net_weight_g", "250"), ("color", "blue")]),
+            attributes(&[("net_weight_g", "250"), ("origin", "Penja")]),
+        ] {
+            assert!(
+                service.validate_attributes("pepper", &invalid).is_err(),
+                "{invalid:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_admin_edits_keep_the_tree_consistent() {
+        let service = CategoryService::new();
+        service
+            .create(new_category("white-pepper", Some("pepper")))
+            .unwrap();
+        assert!(matches!(
+            service.create(new_category("white-pepper", None)),
+            Err(CategoryError::AlreadyExists(_))
+        ));
+        assert!(service.create(new_category("Bad Slug", None)).is_err());
+        // Refusals are explained in the client's language, details included.
+        let err = service
+            .create(new_category("orphan", Some("nowhere")))
+            .unwrap_err();
+        assert_eq!(
+            err.localized(Locale::Fr),
+            "Catégorie invalide : catégorie parente inconnue : nowhere"
+        );
+
+        // Moving `spices` under its own grandchild would create a cycle.
+        let mut spices = service.get("spices").unwrap();
+        spices.parent = Some("white-pepper".to_string());
+        assert!(service.update(spices).is_err());
+
+        assert!(matches!(
+            service.delete("pepper"),
+            Err(CategoryError::InUse(_))
+        ));
+        service.delete("white-pepper").unwrap();
+        service.delete("pepper").unwrap();
+
+        let slugs: Vec<String> = service
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|c| c.slug)
+            .collect();
+        assert_eq!(
+            slugs,
+            ["clothing", "shoes", "crafts", "food", "spices", "furniture"]
+        );
+    }
+}
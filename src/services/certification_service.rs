@@ -0,0 +1,466 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    middleware::locale::localized_error,
+    models::{
+        certification::{
+            Certification, CertificationEvent, CertificationEvidence, CertificationStatus,
+        },
+        locale::{Locale, Localize, Translated},
+    },
+};
+
+/// How long an approval lasts when the reviewer doesn't say otherwise.
+pub const DEFAULT_VALIDITY_DAYS: u32 = 365;
+
+/// Actor recorded in the audit trail when a certification lapses on its own.
+const SYSTEM_ACTOR: &str = "system";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CertificationError {
+    #[error("Failed to lock the certification storage")]
+    LockError,
+    #[error("Certification not found")]
+    NotFound,
+    #[error("Product not found")]
+    ProductNotFound,
+    #[error("A certification request for this product is already pending review")]
+    AlreadyPending,
+    #[error("Cannot {action} a certification that is {status:?}")]
+    InvalidTransition {
+        action: &'static str,
+        status: CertificationStatus,
+    },
+    #[error("Invalid certification request: {0}")]
+    InvalidRequest(Translated),
+}
+
+impl IntoResponse for CertificationError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            CertificationError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+            CertificationError::NotFound | CertificationError::ProductNotFound => {
+                StatusCode::NOT_FOUND
+            }
+            CertificationError::AlreadyPending | CertificationError::InvalidTransition { .. } => {
+                StatusCode::CONFLICT
+            }
+            CertificationError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+        };
+        localized_error(status, &self)
+    }
+}
+
+impl Localize for CertificationError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, CertificationError::LockError) => {
+                "Impossible d'accéder au stockage des certifications".to_string()
+            }
+            (Locale::Fr, CertificationError::NotFound) => "Certification introuvable".to_string(),
+            (Locale::Fr, CertificationError::ProductNotFound) => "Produit introuvable".to_string(),
+            (Locale::Fr, CertificationError::AlreadyPending) => {
+                "Une demande de certification pour ce produit attend déjà d'être examinée"
+                    .to_string()
+            }
+            (Locale::Fr, CertificationError::InvalidTransition { action, status }) => {
+                let action = match *action {
+                    "approve" => "approuver",
+                    "reject" => "rejeter",
+                    "revoke" => "révoquer",
+                    other => other,
+                };
+                let status = match status {
+                    CertificationStatus::Pending => "en attente",
+                    CertificationStatus::Approved => "approuvée",
+                    CertificationStatus::Rejected => "rejetée",
+                    CertificationStatus::Expired => "expirée",
+                    CertificationStatus::Revoked => "révoquée",
+                };
+                format!("Impossible de {action} une certification {status}")
+            }
+            (Locale::Fr, CertificationError::InvalidRequest(detail)) => {
+                format!(
+                    "Demande de certification invalide : {}",
+                    detail.localized(locale)
+                )
+            }
+        }
+    }
+}
+
+/// "Made in Cameroon" certification requests and their review.
+///
+/// Vendors submit evidence, admins approve (for a limited time), reject or later revoke;
+/// every step is appended to the certification's history. Methods take `now` so expiry can
+/// be tested without waiting.
+#[derive(Clone)]
+pub struct CertificationService {
+    certifications: Arc<Mutex<Vec<Certification>>>,
+}
+
+impl CertificationService {
+    /// Starts with the approved certification of the mock catalog's certified product.
+    pub fn new() -> Self {
+        let now = Utc::now();
+        let seeded = Certification {
+            certification_id: Uuid::new_v4().to_string(),
+            product_id: "2".to_string(),
+            evidence: CertificationEvidence {
+                origin: "Hand-carved from a single block of iroko wood".to_string(),
+                workshop_location: "Bafoussam".to_string(),
+                documents: Vec::new(),
+            },
+            status: CertificationStatus::Approved,
+            submitted_at: now,
+            expires_at: Some(now + Duration::days(DEFAULT_VALIDITY_DAYS.into())),
+            decision_reason: None,
+            history: vec![CertificationEvent {
+                at: now,
+                actor: SYSTEM_ACTOR.to_string(),
+                status: CertificationStatus::Approved,
+                reason: Some("Imported from the initial catalog".to_string()),
+            }],
+        };
+        CertificationService {
+            certifications: Arc::new(Mutex::new(vec![seeded])),
+        }
+    }
+
+    /// Records a vendor's evidence for review. A product can only have one pending request.
+    pub fn submit(
+        &self,
+        product_id: &str,
+        vendor: &str,
+        evidence: CertificationEvidence,
+        now: DateTime<Utc>,
+    ) -> Result<Certification, CertificationError> {
+        if evidence.origin.trim().is_empty() || evidence.workshop_location.trim().is_empty() {
+            return Err(CertificationError::InvalidRequest(Translated::new(
+                "origin and workshop_location are required",
+                "origin et workshop_location sont obligatoires",
+            )));
+        }
+
+        let mut certifications = self.lock()?;
+        if certifications
+            .iter()
+            .any(|c| c.product_id == product_id && c.status == CertificationStatus::Pending)
+        {
+            return Err(CertificationError::AlreadyPending);
+        }
+
+        let certification = Certification {
+            certification_id: Uuid::new_v4().to_string(),
+            product_id: product_id.to_string(),
+            evidence,
+            status: CertificationStatus::Pending,
+            submitted_at: now,
+            expires_at: None,
+            decision_reason: None,
+            history: vec![CertificationEvent {
+                at: now,
+                actor: vendor.to_string(),
+                status: CertificationStatus::Pending,
+                reason: None,
+            }],
+        };
+        certifications.push(certification.clone());
+        Ok(certification)
+    }
+
+    /// Approves a pending request for `valid_for_days`.
+    pub fn approve(
+        &self,
+        certification_id: &str,
+        admin: &str,
+        valid_for_days: u32,
+        now: DateTime<Utc>,
+    ) -> Result<Certification, CertificationError> {
+        if valid_for_days == 0 {
+            return Err(CertificationError::InvalidRequest(Translated::new(
+                "valid_for_days must be at least 1",
+                "valid_for_days doit valoir au moins 1",
+            )));
+        }
+        self.transition(certification_id, "approve", now, |certification| {
+            if certification.status != CertificationStatus::Pending {
+                return None;
+            }
+            certification.expires_at = Some(now + Duration::days(valid_for_days.into()));
+            certification.decision_reason = None;
+            Some((CertificationStatus::Approved, admin, None))
+        })
+    }
+
+    /// Rejects a pending request; the vendor sees `reason` and may submit again.
+    pub fn reject(
+        &self,
+        certification_id: &str,
+        admin: &str,
+        reason: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Certification, CertificationError> {
+        let reason = required_reason(reason)?;
+        self.transition(certification_id, "reject", now, |certification| {
+            (certification.status == CertificationStatus::Pending).then_some((
+                CertificationStatus::Rejected,
+                admin,
+                Some(reason),
+            ))
+        })
+    }
+
+    /// Withdraws an approved certification before it expires.
+    pub fn revoke(
+        &self,
+        certification_id: &str,
+        admin: &str,
+        reason: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Certification, CertificationError> {
+        let reason = required_reason(reason)?;
+        self.transition(certification_id, "revoke", now, |certification| {
+            (certification.status == CertificationStatus::Approved).then_some((
+                CertificationStatus::Revoked,
+                admin,
+                Some(reason),
+            ))
+        })
+    }
+
+    pub fn get(
+        &self,
+        certification_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Certification, CertificationError> {
+        let mut certifications = self.lock()?;
+        expire_lapsed(&mut certifications, now);
+        certifications
+            .iter()
+            .find(|c| c.certification_id == certification_id)
+            .cloned()
+            .ok_or(CertificationError::NotFound)
+    }
+
+    /// Certifications of a product, oldest first.
+    pub fn for_product(
+        &self,
+        product_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Certification>, CertificationError> {
+        let mut certifications = self.lock()?;
+        expire_lapsed(&mut certifications, now);
+        Ok(certifications
+            .iter()
+            .filter(|c| c.product_id == product_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Certifications in the given status (all of them for `None`), oldest first.
+    pub fn list(
+        &self,
+        status: Option<CertificationStatus>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Certification>, CertificationError> {
+        let mut certifications = self.lock()?;
+        expire_lapsed(&mut certifications, now);
+        Ok(certifications
+            .iter()
+            .filter(|c| status.is_none_or(|status| c.status == status))
+            .cloned()
+            .collect())
+    }
+
+    /// Ids of the products holding a valid certification at `now`.
+    pub fn certified_products(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<HashSet<String>, CertificationError> {
+        let certifications = self.lock()?;
+        Ok(certifications
+            .iter()
+            .filter(|c| c.is_valid_at(now))
+            .map(|c| c.product_id.clone())
+            .collect())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Vec<Certification>>, CertificationError> {
+        self.certifications
+            .lock()
+            .map_err(|_| CertificationError::LockError)
+    }
+
+    /// Applies a review decision. `decide` returns the new status, actor and reason, or
+    /// `None` when the current status doesn't allow `action`.
+    fn transition<'a>(
+        &self,
+        certification_id: &str,
+        action: &'static str,
+        now: DateTime<Utc>,
+        decide: impl FnOnce(
+            &mut Certification,
+        ) -> Option<(CertificationStatus, &'a str, Option<String>)>,
+    ) -> Result<Certification, CertificationError> {
+        let mut certifications = self.lock()?;
+        expire_lapsed(&mut certifications, now);
+        let certification = certifications
+            .iter_mut()
+            .find(|c| c.certification_id == certification_id)
+            .ok_or(CertificationError::NotFound)?;
+
+        let status = certification.status;
+        let (status, actor, reason) = decide(certification)
+            .ok_or(CertificationError::InvalidTransition { action, status })?;
+        if reason.is_some() {
+            certification.decision_reason = reason.clone();
+        }
+        record(certification, now, actor, status, reason);
+        Ok(certification.clone())
+    }
+}
+
+impl Default for CertificationService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn required_reason(reason: &str) -> Result<String, CertificationError> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(CertificationError::InvalidRequest(Translated::new(
+            "a reason is required",
+            "un motif est obligatoire",
+        )));
+    }
+    Ok(reason.to_string())
+}
+
+fn record(
+    certification: &mut Certification,
+    at: DateTime<Utc>,
+    actor: &str,
+    status: CertificationStatus,
+    reason: Option<String>,
+) {
+    certification.status = status;
+    certification.history.push(CertificationEvent {
+        at,
+        actor: actor.to_string(),
+        status,
+        reason,
+    });
+}
+
+/// Marks approvals past their expiry date as `Expired`, so the audit trail shows when
+/// they lapsed.
+fn expire_lapsed(certifications: &mut [Certification], now: DateTime<Utc>) {
+    for certification in certifications {
+        if certification.status == CertificationStatus::Approved && !certification.is_valid_at(now)
+        {
+            let expired_at = certification.expires_at.unwrap_or(now);
+            record(
+                certification,
+                expired_at,
+                SYSTEM_ACTOR,
+                CertificationStatus::Expired,
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::{CertificationError, CertificationService};
+    use crate::models::certification::{CertificationEvidence, CertificationStatus};
+
+    fn evidence() -> CertificationEvidence {
+        CertificationEvidence {
+            origin: "Woven from raffia harvested near Foumban".to_string(),
+            workshop_location: "Foumban".to_string(),
+            documents: vec!["https://example.cm/workshop.jpg".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_approval_expires_and_is_audited() {
+        let service = CertificationService::new();
+        let now = Utc::now();
+
+        let pending = service
+            .submit("3", "Yaoundé Prints", evidence(), now)
+            .unwrap();
+        assert!(matches!(
+            service.submit("3", "Yaoundé Prints", evidence(), now),
+            Err(CertificationError::AlreadyPending)
+        ));
+        assert!(!service.certified_products(now).unwrap().contains("3"));
+
+        service
+            .approve(&pending.certification_id, "admin", 30, now)
+            .unwrap();
+        assert!(service.certified_products(now).unwrap().contains("3"));
+
+        let later = now + Duration::days(31);
+        assert!(!service.certified_products(later).unwrap().contains("3"));
+        let expired = service.get(&pending.certification_id, later).unwrap();
+        assert_eq!(expired.status, CertificationStatus::Expired);
+        let statuses: Vec<CertificationStatus> =
+            expired.history.iter().map(|event| event.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                CertificationStatus::Pending,
+                CertificationStatus::Approved,
+                CertificationStatus::Expired
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reject_and_revoke_require_reason_and_valid_state() {
+        let service = CertificationService::new();
+        let now = Utc::now();
+        let pending = service
+            .submit("3", "Yaoundé Prints", evidence(), now)
+            .unwrap();
+
+        assert!(matches!(
+            service.revoke(&pending.certification_id, "admin", "fake", now),
+            Err(CertificationError::InvalidTransition { .. })
+        ));
+        assert!(matches!(
+            service.reject(&pending.certification_id, "admin", "  ", now),
+            Err(CertificationError::InvalidRequest(_))
+        ));
+
+        let rejected = service
+            .reject(&pending.certification_id, "admin", "Printed abroad", now)
+            .unwrap();
+        assert_eq!(rejected.status, CertificationStatus::Rejected);
+        assert_eq!(rejected.decision_reason.as_deref(), Some("Printed abroad"));
+
+        // The seeded certification of product 2 can be revoked.
+        let seeded = &service.for_product("2", now).unwrap()[0];
+        service
+            .revoke(&seeded.certification_id, "admin", "Workshop closed", now)
+            .unwrap();
+        assert!(service.certified_products(now).unwrap().is_empty());
+    }
+}
@@ -1,8 +1,17 @@
 use std::sync::{Arc, Mutex};
 
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
 use uuid::Uuid;
 
-use crate::models::order::{Order, OrderStatus};
+use crate::middleware::locale::localized_error;
+use crate::models::{
+    cart_summary::Discount,
+    locale::{Locale, Localize},
+    order::{Order, OrderLine, OrderStatus},
+};
 
 #[derive(Clone)]
 pub struct CheckoutService {
@@ -17,6 +26,38 @@ pub enum CheckoutError {
     OrderNotFound,
     #[error("Cannot cancel this order")]
     CannotCancelOrder,
+    #[error("The order can't move to this status")]
+    InvalidStatusChange,
+}
+
+impl Localize for CheckoutError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, CheckoutError::LockError) => {
+                "Impossible d'accéder au stockage des commandes".to_string()
+            }
+            (Locale::Fr, CheckoutError::OrderNotFound) => "Commande introuvable".to_string(),
+            (Locale::Fr, CheckoutError::CannotCancelOrder) => {
+                "Impossible d'annuler cette commande".to_string()
+            }
+            (Locale::Fr, CheckoutError::InvalidStatusChange) => {
+                "La commande ne peut pas passer à ce statut".to_string()
+            }
+        }
+    }
+}
+
+impl IntoResponse for CheckoutError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            CheckoutError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+            CheckoutError::OrderNotFound => StatusCode::NOT_FOUND,
+            CheckoutError::CannotCancelOrder => StatusCode::BAD_REQUEST,
+            CheckoutError::InvalidStatusChange => StatusCode::CONFLICT,
+        };
+        localized_error(status, &self)
+    }
 }
 
 impl CheckoutService {
@@ -64,13 +105,17 @@ impl CheckoutService {
     pub fn create_order(
         &self,
         user_id: String,
-        items: Vec<String>,
+        items: Vec<OrderLine>,
+        discounts: Vec<Discount>,
+        delivery_fee: f64,
         total_amount: f64,
     ) -> Result<Order, CheckoutError> {
         let order = Order {
             order_id: Uuid::new_v4().to_string(),
             user_id,
             items,
+            discounts,
+            delivery_fee,
             total_amount,
             status: OrderStatus::PendingPayment,
         };
@@ -93,6 +138,58 @@ impl CheckoutService {
 
         Ok(())
     }
+
+    /// Moves a paid order along delivery: to `Shipped`, then `Delivered` (or straight to
+    /// `Delivered` for hand-over at the market).
+    pub fn advance_fulfilment(
+        &self,
+        order_id: &str,
+        next: OrderStatus,
+    ) -> Result<Order, CheckoutError> {
+        let mut orders = self.orders.lock().map_err(|_| CheckoutError::LockError)?;
+        let order = orders
+            .iter_mut()
+            .find(|o| o.order_id == order_id)
+            .ok_or(CheckoutError::OrderNotFound)?;
+        let allowed = matches!(
+            (&order.status, &next),
+            (OrderStatus::Paid, OrderStatus::Shipped)
+                | (OrderStatus::Paid | OrderStatus::Shipped, OrderStatus::Delivered)
+        );
+        if !allowed {
+            return Err(CheckoutError::InvalidStatusChange);
+        }
+        order.status = next;
+        Ok(order.clone())
+    }
+
+    /// A delivered order of the user containing the product, if any.
+    pub fn delivered_order_with(
+        &self,
+        user_id: &str,
+        product_id: &str,
+    ) -> Result<Option<String>, CheckoutError> {
+        let orders = self.orders.lock().map_err(|_| CheckoutError::LockError)?;
+        Ok(orders
+            .iter()
+            .find(|order| {
+                order.user_id == user_id
+                    && matches!(order.status, OrderStatus::Delivered)
+                    && order.items.iter().any(|line| line.product_id == product_id)
+            })
+            .map(|order| order.order_id.clone()))
+    }
+
+    /// Checks that the order storage can still be locked (used by readiness probes).
+    pub fn ping(&self) -> Result<(), CheckoutError> {
+        self.orders.lock().map(|_| ()).map_err(|_| CheckoutError::LockError)
+    }
+}
+
+impl Default for CheckoutService {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -103,7 +200,12 @@ mod tests {
     use tower::ServiceExt;
 
     use crate::{
-        api::{cart::CartItem, checkout::checkout_routes, model::ProductService},
+        api::{
+            cart::CartItem,
+            checkout::checkout_routes,
+            model::{Product, ProductService, ProductVariant},
+        },
+        middleware::locale::LocalizedMessage,
         services::{cart_services::CartService, payment_service::PaymentService},
         state::AppState,
     };
@@ -121,6 +223,7 @@ mod tests {
             checkout_service,
             payment_service,
             product_service,
+            ..Default::default()
         };
 
         Router::new()
@@ -160,6 +263,7 @@ mod tests {
             checkout_service,
             payment_service,
             product_service,
+            ..Default::default()
         };
 
         // Build the app
@@ -173,8 +277,11 @@ mod tests {
                 "user123".to_string(),
                 CartItem {
                     product_id: "2".to_string(), // Bamileke Stool (price = 15000)
+                    variant_sku: None,
                     quantity: 2,
+                    added_unit_price: None,
                 },
+                None,
             )
             .unwrap();
 
@@ -221,4 +328,114 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_order_lines_use_variant_price() {
+        let product_service = ProductService::new();
+        product_service
+            .create_product(Product {
+                id: "10".to_string(),
+                name: "Toghu shirt".to_string(),
+                price: 12000.0,
+                category: "clothing".to_string(),
+                variants: vec![ProductVariant {
+                    sku: "TOGHU-XL".to_string(),
+                    price: 14000.0,
+                    stock: 2,
+                    ..ProductVariant::default()
+                }],
+                ..Product::default()
+            })
+            .await
+            .unwrap();
+        let app_state = AppState {
+            product_service,
+            ..Default::default()
+        };
+        let app = Router::new()
+            .merge(checkout_routes())
+            .layer(Extension(app_state.clone()));
+
+        app_state
+            .cart_service
+            .add_item(
+                "user123".to_string(),
+                CartItem {
+                    product_id: "10".to_string(),
+                    variant_sku: Some("TOGHU-XL".to_string()),
+                    quantity: 2,
+                    added_unit_price: None,
+                },
+                None,
+            )
+            .unwrap();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/checkout")
+                    .header("content-type", "application/json")
+                    .body(Body::from(json!({ "payment_method": "MTN" }).to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let orders = app_state.checkout_service.get_user_orders("user123").unwrap();
+        // Two shirts plus the courier fee, as in the cart summary.
+        assert_eq!(orders[0].delivery_fee, 1500.0);
+        assert_eq!(orders[0].total_amount, 29500.0);
+        assert_eq!(orders[0].items[0].variant_sku.as_deref(), Some("TOGHU-XL"));
+        assert_eq!(orders[0].items[0].name, "Toghu shirt");
+    }
+
+    #[tokio::test]
+    async fn test_checkout_beyond_stock_should_fail() {
+        let app_state = AppState::default();
+        let app = Router::new()
+            .merge(checkout_routes())
+            .layer(Extension(app_state.clone()));
+
+        // Only 4 Bamileke stools are in stock.
+        app_state
+            .cart_service
+            .add_item(
+                "user123".to_string(),
+                CartItem {
+                    product_id: "2".to_string(),
+                    variant_sku: None,
+                    quantity: 5,
+                    added_unit_price: None,
+                },
+                None,
+            )
+            .unwrap();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/checkout")
+                    .header("content-type", "application/json")
+                    .body(Body::from(json!({ "payment_method": "MTN" }).to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let message = response.extensions().get::<LocalizedMessage>().unwrap();
+        assert_eq!(
+            message.en,
+            "Only 4 units of product 2 are left; update the cart first"
+        );
+        assert!(
+            app_state
+                .checkout_service
+                .get_user_orders("user123")
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,577 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::model::{Product, ProductError},
+    models::locale::Translated,
+    services::search::fold,
+};
+
+/// Radius used for "near this town" searches when none is given.
+pub const DEFAULT_NEAR_RADIUS_KM: f64 = 50.0;
+pub const MAX_NEAR_RADIUS_KM: f64 = 500.0;
+
+const EARTH_RADIUS_KM: f64 = 6_371.0;
+
+/// (name in French, name in English, latitude, longitude)
+type TownData = (&'static str, &'static str, f64, f64);
+/// (name in French, name in English, towns; the first one is the department's seat)
+type DepartmentData = (&'static str, &'static str, &'static [TownData]);
+/// (ISO 3166-2 code, name in French, name in English, departments)
+type RegionData = (
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static [DepartmentData],
+);
+
+/// Cameroon's 10 regions and 58 departments, with each department's seat and other
+/// towns vendors commonly work from.
+const CAMEROON: &[RegionData] = &[
+    (
+        "CM-AD",
+        "Adamaoua",
+        "Adamawa",
+        &[
+            ("Djérem", "Djerem", &[("Tibati", "Tibati", 6.47, 12.63)]),
+            (
+                "Faro-et-Déo",
+                "Faro and Deo",
+                &[("Tignère", "Tignere", 7.37, 12.65)],
+            ),
+            (
+                "Mayo-Banyo",
+                "Mayo-Banyo",
+                &[("Banyo", "Banyo", 6.75, 11.82)],
+            ),
+            ("Mbéré", "Mbere", &[("Meiganga", "Meiganga", 6.52, 14.29)]),
+            ("Vina", "Vina", &[("Ngaoundéré", "Ngaoundere", 7.32, 13.58)]),
+        ],
+    ),
+    (
+        "CM-CE",
+        "Centre",
+        "Centre",
+        &[
+            (
+                "Haute-Sanaga",
+                "Upper Sanaga",
+                &[("Nanga-Eboko", "Nanga-Eboko", 4.68, 12.37)],
+            ),
+            (
+                "Lekié",
+                "Lekie",
+                &[
+                    ("Monatélé", "Monatele", 4.26, 11.20),
+                    ("Obala", "Obala", 4.17, 11.53),
+                ],
+            ),
+            (
+                "Mbam-et-Inoubou",
+                "Mbam and Inoubou",
+                &[("Bafia", "Bafia", 4.75, 11.23)],
+            ),
+            (
+                "Mbam-et-Kim",
+                "Mbam and Kim",
+                &[("Ntui", "Ntui", 4.45, 11.63)],
+            ),
+            (
+                "Méfou-et-Afamba",
+                "Mefou and Afamba",
+                &[("Mfou", "Mfou", 3.72, 11.64)],
+            ),
+            (
+                "Méfou-et-Akono",
+                "Mefou and Akono",
+                &[("Ngoumou", "Ngoumou", 3.59, 11.30)],
+            ),
+            ("Mfoundi", "Mfoundi", &[("Yaoundé", "Yaounde", 3.87, 11.52)]),
+            (
+                "Nyong-et-Kéllé",
+                "Nyong and Kelle",
+                &[("Éséka", "Eseka", 3.65, 10.77)],
+            ),
+            (
+                "Nyong-et-Mfoumou",
+                "Nyong and Mfoumou",
+                &[("Akonolinga", "Akonolinga", 3.77, 12.25)],
+            ),
+            (
+                "Nyong-et-So'o",
+                "Nyong and So'o",
+                &[("Mbalmayo", "Mbalmayo", 3.52, 11.50)],
+            ),
+        ],
+    ),
+    (
+        "CM-ES",
+        "Est",
+        "East",
+        &[
+            (
+                "Boumba-et-Ngoko",
+                "Boumba and Ngoko",
+                &[("Yokadouma", "Yokadouma", 3.51, 15.05)],
+            ),
+            (
+                "Haut-Nyong",
+                "Upper Nyong",
+                &[("Abong-Mbang", "Abong-Mbang", 3.98, 13.18)],
+            ),
+            ("Kadey", "Kadey", &[("Batouri", "Batouri", 4.43, 14.36)]),
+            (
+                "Lom-et-Djérem",
+                "Lom and Djerem",
+                &[("Bertoua", "Bertoua", 4.58, 13.68)],
+            ),
+        ],
+    ),
+    (
+        "CM-EN",
+        "Extrême-Nord",
+        "Far North",
+        &[
+            ("Diamaré", "Diamare", &[("Maroua", "Maroua", 10.59, 14.32)]),
+            (
+                "Logone-et-Chari",
+                "Logone and Chari",
+                &[("Kousséri", "Kousseri", 12.08, 15.03)],
+            ),
+            (
+                "Mayo-Danay",
+                "Mayo-Danay",
+                &[("Yagoua", "Yagoua", 10.34, 15.23)],
+            ),
+            (
+                "Mayo-Kani",
+                "Mayo-Kani",
+                &[("Kaélé", "Kaele", 10.11, 14.45)],
+            ),
+            ("Mayo-Sava", "Mayo-Sava", &[("Mora", "Mora", 11.05, 14.14)]),
+            (
+                "Mayo-Tsanaga",
+                "Mayo-Tsanaga",
+                &[("Mokolo", "Mokolo", 10.74, 13.80)],
+            ),
+        ],
+    ),
+    (
+        "CM-LT",
+        "Littoral",
+        "Littoral",
+        &[
+            (
+                "Moungo",
+                "Moungo",
+                &[
+                    ("Nkongsamba", "Nkongsamba", 4.95, 9.94),
+                    ("Loum", "Loum", 4.72, 9.74),
+                ],
+            ),
+            ("Nkam", "Nkam", &[("Yabassi", "Yabassi", 4.46, 9.96)]),
+            (
+                "Sanaga-Maritime",
+                "Sanaga-Maritime",
+                &[("Édéa", "Edea", 3.80, 10.13)],
+            ),
+            ("Wouri", "Wouri", &[("Douala", "Douala", 4.05, 9.70)]),
+        ],
+    ),
+    (
+        "CM-NO",
+        "Nord",
+        "North",
+        &[
+            ("Bénoué", "Benue", &[("Garoua", "Garoua", 9.30, 13.40)]),
+            ("Faro", "Faro", &[("Poli", "Poli", 8.48, 13.24)]),
+            (
+                "Mayo-Louti",
+                "Mayo-Louti",
+                &[("Guider", "Guider", 9.93, 13.95)],
+            ),
+            (
+                "Mayo-Rey",
+                "Mayo-Rey",
+                &[("Tcholliré", "Tchollire", 8.40, 14.17)],
+            ),
+        ],
+    ),
+    (
+        "CM-NW",
+        "Nord-Ouest",
+        "North-West",
+        &[
+            ("Boyo", "Boyo", &[("Fundong", "Fundong", 6.29, 10.27)]),
+            ("Bui", "Bui", &[("Kumbo", "Kumbo", 6.20, 10.68)]),
+            (
+                "Donga-Mantung",
+                "Donga-Mantung",
+                &[("Nkambé", "Nkambe", 6.60, 10.67)],
+            ),
+            ("Menchum", "Menchum", &[("Wum", "Wum", 6.38, 10.07)]),
+            ("Mezam", "Mezam", &[("Bamenda", "Bamenda", 5.96, 10.15)]),
+            ("Momo", "Momo", &[("Mbengwi", "Mbengwi", 6.02, 10.00)]),
+            (
+                "Ngo-Ketunjia",
+                "Ngo-Ketunjia",
+                &[("Ndop", "Ndop", 6.00, 10.42)],
+            ),
+        ],
+    ),
+    (
+        "CM-OU",
+        "Ouest",
+        "West",
+        &[
+            (
+                "Bamboutos",
+                "Bamboutos",
+                &[("Mbouda", "Mbouda", 5.63, 10.25)],
+            ),
+            (
+                "Haut-Nkam",
+                "Upper Nkam",
+                &[("Bafang", "Bafang", 5.16, 10.18)],
+            ),
+            (
+                "Hauts-Plateaux",
+                "Upper Plateau",
+                &[("Baham", "Baham", 5.33, 10.39)],
+            ),
+            (
+                "Koung-Khi",
+                "Koung-Khi",
+                &[("Bandjoun", "Bandjoun", 5.35, 10.41)],
+            ),
+            ("Menoua", "Menoua", &[("Dschang", "Dschang", 5.44, 10.05)]),
+            ("Mifi", "Mifi", &[("Bafoussam", "Bafoussam", 5.48, 10.42)]),
+            ("Ndé", "Nde", &[("Bangangté", "Bangangte", 5.14, 10.52)]),
+            (
+                "Noun",
+                "Noun",
+                &[
+                    ("Foumban", "Foumban", 5.73, 10.90),
+                    ("Foumbot", "Foumbot", 5.51, 10.63),
+                ],
+            ),
+        ],
+    ),
+    (
+        "CM-SU",
+        "Sud",
+        "South",
+        &[
+            (
+                "Dja-et-Lobo",
+                "Dja and Lobo",
+                &[("Sangmélima", "Sangmelima", 2.93, 11.98)],
+            ),
+            ("Mvila", "Mvila", &[("Ebolowa", "Ebolowa", 2.90, 11.15)]),
+            ("Océan", "Ocean", &[("Kribi", "Kribi", 2.94, 9.91)]),
+            (
+                "Vallée-du-Ntem",
+                "Ntem Valley",
+                &[("Ambam", "Ambam", 2.38, 11.28)],
+            ),
+        ],
+    ),
+    (
+        "CM-SW",
+        "Sud-Ouest",
+        "South-West",
+        &[
+            (
+                "Fako",
+                "Fako",
+                &[
+                    ("Limbé", "Limbe", 4.02, 9.21),
+                    ("Buea", "Buea", 4.16, 9.24),
+                    ("Tiko", "Tiko", 4.08, 9.36),
+                ],
+            ),
+            (
+                "Koupé-Manengouba",
+                "Kupe-Muanenguba",
+                &[("Bangem", "Bangem", 5.08, 9.77)],
+            ),
+            ("Lebialem", "Lebialem", &[("Menji", "Menji", 5.53, 9.97)]),
+            ("Manyu", "Manyu", &[("Mamfe", "Mamfe", 5.75, 9.31)]),
+            ("Meme", "Meme", &[("Kumba", "Kumba", 4.64, 9.45)]),
+            ("Ndian", "Ndian", &[("Mundemba", "Mundemba", 4.95, 8.88)]),
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Region {
+    /// ISO 3166-2 code, e.g. `CM-OU`.
+    pub code: String,
+    pub name_fr: String,
+    pub name_en: String,
+    pub departments: Vec<Department>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Department {
+    /// Region code plus the department name, e.g. `CM-OU-MIFI`.
+    pub code: String,
+    pub name_fr: String,
+    pub name_en: String,
+    pub towns: Vec<Town>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Town {
+    /// Region code plus the town name, e.g. `CM-OU-BAFOUSSAM`.
+    pub code: String,
+    pub name_fr: String,
+    pub name_en: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Where a town sits in the hierarchy.
+#[derive(Debug, Clone, Copy)]
+pub struct Locality<'a> {
+    pub region: &'a Region,
+    pub department: &'a Department,
+    pub town: &'a Town,
+}
+
+impl Town {
+    /// Great-circle distance in kilometres.
+    pub fn distance_km(&self, other: &Town) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+/// The canonical region → department → town hierarchy, indexed by code and by folded
+/// French and English names.
+pub struct Geography {
+    regions: Vec<Region>,
+    region_keys: HashMap<String, usize>,
+    department_keys: HashMap<String, (usize, usize)>,
+    town_keys: HashMap<String, (usize, usize, usize)>,
+}
+
+/// The hierarchy for Cameroon, built on first use.
+pub fn cameroon() -> &'static Geography {
+    static GEOGRAPHY: OnceLock<Geography> = OnceLock::new();
+    GEOGRAPHY.get_or_init(|| Geography::build(CAMEROON))
+}
+
+/// `CM-OU` + "Haut-Nkam" -> `CM-OU-HAUT-NKAM`.
+fn child_code(parent: &str, name: &str) -> String {
+    let slug: Vec<String> = fold(name)
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_uppercase)
+        .collect();
+    format!("{parent}-{}", slug.join("-"))
+}
+
+impl Geography {
+    fn build(data: &[RegionData]) -> Self {
+        let mut geography = Geography {
+            regions: Vec::with_capacity(data.len()),
+            region_keys: HashMap::new(),
+            department_keys: HashMap::new(),
+            town_keys: HashMap::new(),
+        };
+
+        for (r, (code, name_fr, name_en, departments)) in data.iter().enumerate() {
+            let mut region = Region {
+                code: code.to_string(),
+                name_fr: name_fr.to_string(),
+                name_en: name_en.to_string(),
+                departments: Vec::with_capacity(departments.len()),
+            };
+            for key in [*code, name_fr, name_en] {
+                geography.region_keys.entry(fold(key)).or_insert(r);
+            }
+
+            for (d, (name_fr, name_en, towns)) in departments.iter().enumerate() {
+                let mut department = Department {
+                    code: child_code(code, name_fr),
+                    name_fr: name_fr.to_string(),
+                    name_en: name_en.to_string(),
+                    towns: Vec::with_capacity(towns.len()),
+                };
+                for key in [department.code.as_str(), name_fr, name_en] {
+                    geography.department_keys.entry(fold(key)).or_insert((r, d));
+                }
+
+                for (t, (name_fr, name_en, latitude, longitude)) in towns.iter().enumerate() {
+                    let town = Town {
+                        code: child_code(code, name_fr),
+                        name_fr: name_fr.to_string(),
+                        name_en: name_en.to_string(),
+                        latitude: *latitude,
+                        longitude: *longitude,
+                    };
+                    for key in [town.code.as_str(), name_fr, name_en] {
+                        geography.town_keys.entry(fold(key)).or_insert((r, d, t));
+                    }
+                    department.towns.push(town);
+                }
+                region.departments.push(department);
+            }
+            geography.regions.push(region);
+        }
+        geography
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Looks a region up by code or by French or English name, ignoring case and accents.
+    pub fn region(&self, key: &str) -> Option<&Region> {
+        self.region_keys
+            .get(&fold(key.trim()))
+            .map(|&r| &self.regions[r])
+    }
+
+    pub fn department(&self, key: &str) -> Option<(&Region, &Department)> {
+        self.department_keys.get(&fold(key.trim())).map(|&(r, d)| {
+            let region = &self.regions[r];
+            (region, &region.departments[d])
+        })
+    }
+
+    pub fn town(&self, key: &str) -> Option<Locality<'_>> {
+        self.town_keys.get(&fold(key.trim())).map(|&(r, d, t)| {
+            let region = &self.regions[r];
+            let department = &region.departments[d];
+            Locality {
+                region,
+                department,
+                town: &department.towns[t],
+            }
+        })
+    }
+
+    /// The town a product is sold from, if it names a known one.
+    pub fn locate(&self, product: &Product) -> Option<Locality<'_>> {
+        product.town.as_deref().and_then(|town| self.town(town))
+    }
+
+    /// Validates a vendor's location and rewrites it in canonical form: `town` becomes a
+    /// town code and `region` the French name of its region, which must agree with the
+    /// region given, if any.
+    pub fn normalize_location(&self, product: &mut Product) -> Result<(), ProductError> {
+        let region = match product.region.trim() {
+            "" => None,
+            region => Some(self.region(region).ok_or_else(|| {
+                ProductError::InvalidProduct(Translated::new(
+                    format!("unknown region {region:?}"),
+                    format!("région inconnue {region:?}"),
+                ))
+            })?),
+        };
+
+        match product.town.as_deref() {
+            Some(town) => {
+                let locality = self.town(town).ok_or_else(|| {
+                    ProductError::InvalidProduct(Translated::new(
+                        format!("unknown town {town:?}"),
+                        format!("ville inconnue {town:?}"),
+                    ))
+                })?;
+                if region.is_some_and(|region| region.code != locality.region.code) {
+                    return Err(ProductError::InvalidProduct(Translated::new(
+                        format!(
+                            "{} is not in region {}",
+                            locality.town.name_fr, product.region
+                        ),
+                        format!(
+                            "{} n'est pas dans la région {}",
+                            locality.town.name_fr, product.region
+                        ),
+                    )));
+                }
+                product.town = Some(locality.town.code.clone());
+                product.region = locality.region.name_fr.clone();
+            }
+            None => {
+                let region = region.ok_or_else(|| {
+                    ProductError::InvalidProduct(Translated::new(
+                        "a region or town is required",
+                        "une région ou une ville est obligatoire",
+                    ))
+                })?;
+                product.region = region.name_fr.clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cameroon;
+    use crate::api::model::Product;
+
+    #[test]
+    fn test_hierarchy_is_complete_and_bilingual() {
+        let geography = cameroon();
+        assert_eq!(geography.regions().len(), 10);
+        let departments: usize = geography
+            .regions()
+            .iter()
+            .map(|region| region.departments.len())
+            .sum();
+        assert_eq!(departments, 58);
+
+        assert_eq!(geography.region("west").unwrap().code, "CM-OU");
+        assert_eq!(
+            geography.region("extreme-nord").unwrap().name_en,
+            "Far North"
+        );
+        let (region, department) = geography.department("upper nkam").unwrap();
+        assert_eq!(
+            (region.code.as_str(), department.code.as_str()),
+            ("CM-OU", "CM-OU-HAUT-NKAM")
+        );
+
+        let limbe = geography.town("Limbe").unwrap();
+        assert_eq!(limbe.town.code, "CM-SW-LIMBE");
+        assert_eq!(limbe.department.name_fr, "Fako");
+        let buea = geography.town("CM-SW-BUEA").unwrap();
+        assert!(limbe.town.distance_km(buea.town) < 20.0);
+    }
+
+    #[test]
+    fn test_normalize_location() {
+        let geography = cameroon();
+
+        let mut product = Product {
+            region: "west".to_string(),
+            town: Some("foumban".to_string()),
+            ..Product::default()
+        };
+        geography.normalize_location(&mut product).unwrap();
+        assert_eq!(product.region, "Ouest");
+        assert_eq!(product.town.as_deref(), Some("CM-OU-FOUMBAN"));
+
+        let mut mismatched = Product {
+            region: "Littoral".to_string(),
+            town: Some("Foumban".to_string()),
+            ..Product::default()
+        };
+        assert!(geography.normalize_location(&mut mismatched).is_err());
+
+        let mut unknown = Product {
+            region: "Atlantis".to_string(),
+            ..Product::default()
+        };
+        assert!(geography.normalize_location(&mut unknown).is_err());
+    }
+}
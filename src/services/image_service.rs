@@ -0,0 +1,366 @@
+use std::{io::Cursor, sync::Arc};
+
+use axum::{
+    extract::multipart::MultipartError,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use image::{
+    DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits,
+    codecs::{jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::model::{ImageFormatKind, ProductError, ProductImage, Thumbnail},
+    config::AppConfig,
+    middleware::locale::localized_error,
+    models::locale::{Locale, Localize, Translated},
+    services::blob_store::{BlobError, BlobStore, LocalBlobStore, MemoryBlobStore},
+};
+
+/// Widths (px) of the generated thumbnails, smallest first. Product cards on 3G use the
+/// smallest one; images are never upscaled. Thumbnails are always JPEG: the only WebP
+/// encoder available is lossless, which makes photos several times larger.
+pub const THUMBNAIL_WIDTHS: [u32; 3] = [160, 480, 960];
+
+/// Uploads larger than this in either dimension are rejected before being decoded.
+const MAX_DIMENSION: u32 = 8_000;
+
+const JPEG_QUALITY: u8 = 80;
+
+/// Public URL prefix that `GET /api/images/{*key}` serves blobs under.
+pub const IMAGE_URL_PREFIX: &str = "/api/images/";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImageError {
+    #[error("Image is larger than the {0} byte limit")]
+    TooLarge(usize),
+    #[error("Unsupported image type; upload a JPEG, PNG or WebP file")]
+    UnsupportedType,
+    #[error("Invalid image: {0}")]
+    InvalidImage(Translated),
+    #[error("Image not found")]
+    NotFound,
+    #[error(transparent)]
+    Storage(#[from] BlobError),
+    #[error(transparent)]
+    Product(#[from] ProductError),
+}
+
+impl IntoResponse for ImageError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ImageError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ImageError::UnsupportedType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ImageError::InvalidImage(_) => StatusCode::BAD_REQUEST,
+            ImageError::NotFound => StatusCode::NOT_FOUND,
+            ImageError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ImageError::Product(err) => return err.into_response(),
+        };
+        localized_error(status, &self)
+    }
+}
+
+impl From<MultipartError> for ImageError {
+    fn from(err: MultipartError) -> ImageError {
+        ImageError::InvalidImage(Translated::new(
+            err.body_text(),
+            "le formulaire d'envoi est mal formé",
+        ))
+    }
+}
+
+impl Localize for ImageError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (_, ImageError::Product(err)) => err.localized(locale),
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, ImageError::TooLarge(limit)) => {
+                format!("L'image dépasse la limite de {limit} octets")
+            }
+            (Locale::Fr, ImageError::UnsupportedType) => {
+                "Type d'image non pris en charge ; envoyez un fichier JPEG, PNG ou WebP".to_string()
+            }
+            (Locale::Fr, ImageError::InvalidImage(detail)) => {
+                format!("Image invalide : {}", detail.localized(locale))
+            }
+            (Locale::Fr, ImageError::NotFound) => "Image introuvable".to_string(),
+            (Locale::Fr, ImageError::Storage(BlobError::InvalidKey(key))) => {
+                format!("Clé de fichier invalide : {key}")
+            }
+            (Locale::Fr, ImageError::Storage(BlobError::Io(_))) => {
+                "Échec du stockage des fichiers".to_string()
+            }
+        }
+    }
+}
+
+/// Validates uploaded product photos, strips their metadata and stores them together with
+/// their thumbnails.
+#[derive(Clone)]
+pub struct ImageService {
+    store: Arc<dyn BlobStore>,
+    max_bytes: usize,
+}
+
+/// An encoded file ready to be stored.
+struct Rendition {
+    key: String,
+    bytes: Vec<u8>,
+}
+
+impl ImageService {
+    pub fn new(store: Arc<dyn BlobStore>, max_bytes: usize) -> Self {
+        ImageService { store, max_bytes }
+    }
+
+    /// Stores images on disk under `image_storage_dir`.
+    pub fn from_config(config: &AppConfig) -> Self {
+        ImageService::new(
+            Arc::new(LocalBlobStore::new(config.image_storage_dir.clone())),
+            config.max_image_bytes,
+        )
+    }
+
+    /// Processes an uploaded photo of `product_id` and stores the cleaned original plus one
+    /// JPEG thumbnail per `THUMBNAIL_WIDTHS` entry.
+    ///
+    /// Only the pixels are kept: EXIF (camera, GPS position of the workshop...) and other
+    /// metadata are dropped by re-encoding, after applying the EXIF orientation so phone
+    /// photos aren't displayed sideways.
+    pub async fn upload(
+        &self,
+        product_id: &str,
+        bytes: Vec<u8>,
+        alt: Option<String>,
+    ) -> Result<ProductImage, ImageError> {
+        self.store_image(&format!("products/{product_id}"), bytes, alt)
+            .await
+    }
+
+    /// Same processing as `upload`, for a buyer's photo attached to a review.
+    pub async fn upload_review_photo(
+        &self,
+        product_id: &str,
+        bytes: Vec<u8>,
+    ) -> Result<ProductImage, ImageError> {
+        self.store_image(&format!("reviews/{product_id}"), bytes, None)
+            .await
+    }
+
+    async fn store_image(
+        &self,
+        folder: &str,
+        bytes: Vec<u8>,
+        alt: Option<String>,
+    ) -> Result<ProductImage, ImageError> {
+        if bytes.len() > self.max_bytes {
+            return Err(ImageError::TooLarge(self.max_bytes));
+        }
+
+        let image_id = Uuid::new_v4().to_string();
+        let prefix = format!("{folder}/{image_id}");
+        let (mut image, renditions) = tokio::task::spawn_blocking(move || process(&prefix, &bytes))
+            .await
+            .map_err(|err| {
+                ImageError::InvalidImage(Translated::new(
+                    err.to_string(),
+                    "le traitement de l'image a échoué",
+                ))
+            })??;
+
+        for rendition in renditions {
+            self.store.put(&rendition.key, rendition.bytes).await?;
+        }
+        image.id = image_id;
+        image.alt = alt.filter(|alt| !alt.trim().is_empty());
+        Ok(image)
+    }
+
+    /// Reads a stored file back, with its content type.
+    pub async fn fetch(&self, key: &str) -> Result<(Vec<u8>, &'static str), ImageError> {
+        let content_type = ImageFormatKind::from_key(key)
+            .ok_or(ImageError::NotFound)?
+            .content_type();
+        let bytes = self.store.get(key).await?.ok_or(ImageError::NotFound)?;
+        Ok((bytes, content_type))
+    }
+}
+
+/// Keeps images in memory with the default size limit, so building one (e.g. in a default
+/// `AppState`) neither reads the environment nor touches the disk.
+impl Default for ImageService {
+    fn default() -> Self {
+        ImageService::new(
+            Arc::new(MemoryBlobStore::new()),
+            AppConfig::default().max_image_bytes,
+        )
+    }
+}
+
+/// Decodes, cleans and resizes an upload. CPU-bound, so it runs on the blocking pool.
+fn process(prefix: &str, bytes: &[u8]) -> Result<(ProductImage, Vec<Rendition>), ImageError> {
+    // Trust the file's magic bytes, not the client-supplied content type or file name.
+    let format = image::guess_format(bytes).map_err(|_| ImageError::UnsupportedType)?;
+    let original_kind = match format {
+        ImageFormat::Jpeg => ImageFormatKind::Jpeg,
+        ImageFormat::Png => ImageFormatKind::Png,
+        ImageFormat::WebP => ImageFormatKind::Webp,
+        _ => return Err(ImageError::UnsupportedType),
+    };
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+    let invalid = |err: image::ImageError| {
+        ImageError::InvalidImage(Translated::new(
+            err.to_string(),
+            "le fichier est illisible ou corrompu",
+        ))
+    };
+    let mut decoder = reader.into_decoder().map_err(invalid)?;
+    let orientation = decoder.orientation().map_err(invalid)?;
+    let mut decoded = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+    decoded.apply_orientation(orientation);
+
+    let original_key = format!("{prefix}/original.{}", original_kind.extension());
+    let mut renditions = vec![Rendition {
+        bytes: encode(&decoded, original_kind)?,
+        key: original_key.clone(),
+    }];
+    let mut image = ProductImage {
+        url: format!("{IMAGE_URL_PREFIX}{original_key}"),
+        width: decoded.width(),
+        height: decoded.height(),
+        ..ProductImage::default()
+    };
+
+    let mut widths: Vec<u32> = THUMBNAIL_WIDTHS
+        .iter()
+        .map(|width| (*width).min(decoded.width()))
+        .collect();
+    widths.dedup();
+    for width in widths {
+        let thumbnail = decoded.resize(width, MAX_DIMENSION, FilterType::Triangle);
+        let kind = ImageFormatKind::Jpeg;
+        let key = format!("{prefix}/{width}.{}", kind.extension());
+        renditions.push(Rendition {
+            bytes: encode(&thumbnail, kind)?,
+            key: key.clone(),
+        });
+        image.thumbnails.push(Thumbnail {
+            width: thumbnail.width(),
+            height: thumbnail.height(),
+            format: kind,
+            url: format!("{IMAGE_URL_PREFIX}{key}"),
+        });
+    }
+
+    Ok((image, renditions))
+}
+
+fn encode(image: &DynamicImage, kind: ImageFormatKind) -> Result<Vec<u8>, ImageError> {
+    let mut bytes = Vec::new();
+    let result = match kind {
+        // JPEG has no alpha channel.
+        ImageFormatKind::Jpeg => image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)),
+        ImageFormatKind::Png => image
+            .to_rgba8()
+            .write_with_encoder(PngEncoder::new(&mut bytes)),
+        ImageFormatKind::Webp => image
+            .to_rgba8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+    };
+    result.map_err(|err| {
+        ImageError::InvalidImage(Translated::new(
+            err.to_string(),
+            "l'image n'a pas pu être réencodée",
+        ))
+    })?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, sync::Arc};
+
+    use image::{DynamicImage, ImageFormat, RgbImage};
+
+    use super::{ImageError, ImageService, THUMBNAIL_WIDTHS};
+    use crate::services::blob_store::LocalBlobStore;
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Jpeg)
+            .unwrap();
+        bytes
+    }
+
+    /// Inserts an APP1 EXIF segment right after the JPEG start-of-image marker.
+    fn with_exif(mut jpeg: Vec<u8>) -> Vec<u8> {
+        let payload = b"Exif\0\0GPS 3.8480N 11.5021E";
+        let length = (payload.len() + 2) as u16;
+        let mut segment = vec![0xFF, 0xE1];
+        segment.extend_from_slice(&length.to_be_bytes());
+        segment.extend_from_slice(payload);
+        jpeg.splice(2..2, segment);
+        jpeg
+    }
+
+    #[tokio::test]
+    async fn test_upload_strips_exif_and_generates_thumbnails() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = ImageService::new(Arc::new(LocalBlobStore::new(dir.path())), 1 << 20);
+
+        let image = service
+            .upload(
+                "2",
+                with_exif(jpeg(1200, 600)),
+                Some("Side view".to_string()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!((image.width, image.height), (1200, 600));
+        assert_eq!(image.thumbnails.len(), THUMBNAIL_WIDTHS.len());
+        assert_eq!(
+            (image.thumbnails[0].width, image.thumbnails[0].height),
+            (160, 80)
+        );
+
+        let key = image.url.trim_start_matches(super::IMAGE_URL_PREFIX);
+        let (original, content_type) = service.fetch(key).await.unwrap();
+        assert_eq!(content_type, "image/jpeg");
+        assert!(!original.windows(4).any(|w| w == b"Exif"));
+
+        let thumbnail = image.thumbnails[0]
+            .url
+            .trim_start_matches(super::IMAGE_URL_PREFIX);
+        let (_, content_type) = service.fetch(thumbnail).await.unwrap();
+        assert_eq!(content_type, "image/jpeg");
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_wrong_type_and_oversized_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = ImageService::new(Arc::new(LocalBlobStore::new(dir.path())), 1024);
+
+        assert!(matches!(
+            service
+                .upload("2", b"GIF89a not really".to_vec(), None)
+                .await,
+            Err(ImageError::UnsupportedType)
+        ));
+        assert!(matches!(
+            service.upload("2", vec![0xFF; 2048], None).await,
+            Err(ImageError::TooLarge(1024))
+        ));
+    }
+}
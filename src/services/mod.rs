@@ -1,3 +1,17 @@
+pub mod blob_store;
+pub mod cart_pricing;
 pub mod cart_services;
+pub mod cart_sweeper;
+pub mod category_service;
+pub mod certification_service;
 pub mod checkout_service;
-pub mod payment_service;
\ No newline at end of file
+pub mod geography;
+pub mod image_service;
+pub mod moderation_service;
+pub mod notification_service;
+pub mod payment_service;
+pub mod preference_service;
+pub mod promotion_service;
+pub mod review_service;
+pub mod search;
+pub mod wishlist_service;
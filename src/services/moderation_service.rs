@@ -0,0 +1,461 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    api::model::{Product, ProductError},
+    middleware::locale::localized_error,
+    models::{
+        locale::{Locale, Localize, Translated},
+        moderation::{Blocklist, ListingAction, ModerationEntry, ModerationStatus},
+    },
+    services::search::{fold, max_typos, tokenize, within_typos},
+};
+
+/// Decision maker recorded for listings that pass the checks.
+const SYSTEM_ACTOR: &str = "system";
+
+/// Single-word brands shorter than this must match exactly: "Nike" is one typo away from
+/// "nice" and "Mike".
+const MIN_FUZZY_BRAND_LEN: usize = 5;
+
+const DEFAULT_BRANDS: &[&str] = &[
+    "Adidas",
+    "Gucci",
+    "H&M",
+    "Louis Vuitton",
+    "Nike",
+    "Puma",
+    "Samsung",
+    "Shein",
+    "Zara",
+];
+
+const DEFAULT_PATTERNS: &[&str] = &[
+    "copie conforme",
+    "first copy",
+    "importé",
+    "imported",
+    "made in china",
+    "original quality",
+    "replica",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModerationError {
+    #[error("Failed to lock the moderation storage")]
+    LockError,
+    #[error("Moderation entry not found")]
+    NotFound,
+    #[error("This listing has already been decided")]
+    AlreadyDecided,
+    #[error("Invalid moderation request: {0}")]
+    InvalidRequest(Translated),
+    #[error(transparent)]
+    Product(#[from] ProductError),
+}
+
+impl IntoResponse for ModerationError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ModerationError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+            ModerationError::NotFound => StatusCode::NOT_FOUND,
+            ModerationError::AlreadyDecided => StatusCode::CONFLICT,
+            ModerationError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            ModerationError::Product(err) => return err.into_response(),
+        };
+        localized_error(status, &self)
+    }
+}
+
+impl Localize for ModerationError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (_, ModerationError::Product(err)) => err.localized(locale),
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, ModerationError::LockError) => {
+                "Impossible d'accéder au stockage de la modération".to_string()
+            }
+            (Locale::Fr, ModerationError::NotFound) => {
+                "Entrée de modération introuvable".to_string()
+            }
+            (Locale::Fr, ModerationError::AlreadyDecided) => {
+                "Cette annonce a déjà fait l'objet d'une décision".to_string()
+            }
+            (Locale::Fr, ModerationError::InvalidRequest(detail)) => {
+                format!(
+                    "Demande de modération invalide : {}",
+                    detail.localized(locale)
+                )
+            }
+        }
+    }
+}
+
+/// Listing-validation pipeline keeping foreign brands off the marketplace.
+///
+/// Every vendor create/update is screened against an admin-managed blocklist. Clean
+/// listings are approved on the spot; flagged ones wait in the moderation queue until an
+/// admin publishes or rejects them. Both outcomes are kept with their reason.
+#[derive(Clone)]
+pub struct ModerationService {
+    blocklist: Arc<Mutex<Blocklist>>,
+    entries: Arc<Mutex<Vec<ModerationEntry>>>,
+}
+
+impl ModerationService {
+    pub fn new() -> Self {
+        ModerationService {
+            blocklist: Arc::new(Mutex::new(Blocklist {
+                brands: DEFAULT_BRANDS.iter().map(|b| b.to_string()).collect(),
+                patterns: DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            })),
+            entries: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Reasons `product` needs a human look; empty if it may be published directly.
+    pub fn screen(&self, product: &Product) -> Result<Vec<String>, ModerationError> {
+        let blocklist = self
+            .blocklist
+            .lock()
+            .map_err(|_| ModerationError::LockError)?;
+
+        let mut fields = vec![
+            ("brand".to_string(), product.brand.clone().unwrap_or_default()),
+            ("name".to_string(), product.name.clone()),
+            ("description".to_string(), product.description.clone()),
+            ("tags".to_string(), product.tags.join(" ")),
+            ("vendor_name".to_string(), product.vendor_name.clone()),
+            ("materials".to_string(), product.materials.join(" ")),
+        ];
+        for (locale, text) in &product.translations {
+            let tag = locale.tag();
+            fields.push((format!("name ({tag})"), text.name.clone()));
+            fields.push((format!("description ({tag})"), text.description.clone()));
+        }
+        let mut flags = Vec::new();
+        for (field, text) in &fields {
+            let words = tokenize(text);
+            for brand in &blocklist.brands {
+                if mentions(&words, brand, true) {
+                    flags.push(format!("{field} mentions the brand \"{brand}\""));
+                }
+            }
+            for pattern in &blocklist.patterns {
+                if mentions(&words, pattern, false) {
+                    flags.push(format!("{field} contains \"{pattern}\""));
+                }
+            }
+        }
+        Ok(flags)
+    }
+
+    /// Records a submission: approved immediately when `flags` is empty, pending otherwise.
+    pub fn record(
+        &self,
+        action: ListingAction,
+        product: Product,
+        flags: Vec<String>,
+        now: DateTime<Utc>,
+    ) -> Result<ModerationEntry, ModerationError> {
+        let passed = flags.is_empty();
+        let entry = ModerationEntry {
+            moderation_id: Uuid::new_v4().to_string(),
+            action,
+            product,
+            flags,
+            status: if passed {
+                ModerationStatus::Approved
+            } else {
+                ModerationStatus::Pending
+            },
+            submitted_at: now,
+            decided_at: passed.then_some(now),
+            decided_by: passed.then(|| SYSTEM_ACTOR.to_string()),
+            decision_reason: passed
+                .then(|| "No blocklisted brand or suspicious pattern found".to_string()),
+        };
+        self.entries
+            .lock()
+            .map_err(|_| ModerationError::LockError)?
+            .push(entry.clone());
+        Ok(entry)
+    }
+
+    /// Entries in the given status (all of them for `None`), oldest first.
+    pub fn list(
+        &self,
+        status: Option<ModerationStatus>,
+    ) -> Result<Vec<ModerationEntry>, ModerationError> {
+        let entries = self
+            .entries
+            .lock()
+            .map_err(|_| ModerationError::LockError)?;
+        Ok(entries
+            .iter()
+            .filter(|entry| status.is_none_or(|status| entry.status == status))
+            .cloned()
+            .collect())
+    }
+
+    /// Marks a pending entry as decided. Publishing an approved listing is up to the caller.
+    pub fn decide(
+        &self,
+        moderation_id: &str,
+        status: ModerationStatus,
+        reviewer: &str,
+        reason: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ModerationEntry, ModerationError> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(ModerationError::InvalidRequest(Translated::new(
+                "a reason is required",
+                "un motif est obligatoire",
+            )));
+        }
+
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| ModerationError::LockError)?;
+        let entry = entries
+            .iter_mut()
+            .find(|entry| entry.moderation_id == moderation_id)
+            .ok_or(ModerationError::NotFound)?;
+        if entry.status != ModerationStatus::Pending {
+            return Err(ModerationError::AlreadyDecided);
+        }
+
+        entry.status = status;
+        entry.decided_at = Some(now);
+        entry.decided_by = Some(reviewer.to_string());
+        entry.decision_reason = Some(reason.to_string());
+        Ok(entry.clone())
+    }
+
+    /// Puts an approved entry back in the queue, e.g. when publishing it failed.
+    pub fn reopen(&self, moderation_id: &str) -> Result<(), ModerationError> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| ModerationError::LockError)?;
+        let entry = entries
+            .iter_mut()
+            .find(|entry| entry.moderation_id == moderation_id)
+            .ok_or(ModerationError::NotFound)?;
+        entry.status = ModerationStatus::Pending;
+        entry.decided_at = None;
+        entry.decided_by = None;
+        entry.decision_reason = None;
+        Ok(())
+    }
+
+    pub fn blocklist(&self) -> Result<Blocklist, ModerationError> {
+        Ok(self
+            .blocklist
+            .lock()
+            .map_err(|_| ModerationError::LockError)?
+            .clone())
+    }
+
+    /// Replaces the blocklist; blank and duplicate entries are dropped.
+    pub fn set_blocklist(&self, blocklist: Blocklist) -> Result<Blocklist, ModerationError> {
+        let cleaned = Blocklist {
+            brands: dedup_terms(blocklist.brands),
+            patterns: dedup_terms(blocklist.patterns),
+        };
+        *self
+            .blocklist
+            .lock()
+            .map_err(|_| ModerationError::LockError)? = cleaned.clone();
+        Ok(cleaned)
+    }
+}
+
+impl Default for ModerationService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `term` appears in `words` as a run of whole words. With `fuzzy`, longer words
+/// may be misspelled ("Addidas"), and multi-word terms also match when written as one
+/// word ("LouisVuitton").
+fn mentions(words: &[String], term: &str, fuzzy: bool) -> bool {
+    let term_words = tokenize(term);
+    if term_words.is_empty() {
+        return false;
+    }
+
+    let word_matches = |word: &str, term_word: &str| {
+        word == term_word
+            || (fuzzy
+                && term_word.chars().count() >= MIN_FUZZY_BRAND_LEN
+                && within_typos(word, term_word, max_typos(term_word)))
+    };
+    let phrase = words.windows(term_words.len()).any(|window| {
+        window
+            .iter()
+            .zip(&term_words)
+            .all(|(word, term_word)| word_matches(word, term_word))
+    });
+
+    phrase
+        || (fuzzy && term_words.len() > 1 && {
+            let joined = term_words.concat();
+            words.iter().any(|word| word_matches(word, &joined))
+        })
+}
+
+fn dedup_terms(terms: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    terms
+        .into_iter()
+        .map(|term| term.trim().to_string())
+        .filter(|term| !term.is_empty() && seen.insert(fold(term)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::ModerationService;
+    use crate::{
+        api::model::{Product, ProductText},
+        models::{
+            locale::Locale,
+            moderation::{Blocklist, ListingAction, ModerationStatus},
+        },
+    };
+
+    fn product(name: &str, description: &str, brand: Option<&str>) -> Product {
+        Product {
+            id: "10".to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            brand: brand.map(String::from),
+            ..Product::default()
+        }
+    }
+
+    #[test]
+    fn test_screen_flags_brands_and_patterns() {
+        let service = ModerationService::new();
+
+        let clean = product("Sac en raphia", "Tressé à Foumban", Some("Atelier Njoya"));
+        assert!(service.screen(&clean).unwrap().is_empty());
+
+        let flags = service
+            .screen(&product("Baskets Addidas", "Original quality", None))
+            .unwrap();
+        assert_eq!(
+            flags,
+            [
+                "name mentions the brand \"Adidas\"",
+                "description contains \"original quality\""
+            ]
+        );
+
+        assert_eq!(
+            service
+                .screen(&product("Sac", "", Some("LouisVuitton")))
+                .unwrap()
+                .len(),
+            1
+        );
+        // The vendor name and materials are screened too.
+        let mut vendor = product("Sac", "", None);
+        vendor.vendor_name = "Nike Store Douala".to_string();
+        vendor.materials = vec!["Tissu Zara".to_string()];
+        assert_eq!(service.screen(&vendor).unwrap().len(), 2);
+        // So are translations.
+        let mut translated = product("Sac en raphia", "Tressé à Foumban", None);
+        translated.translations.insert(
+            Locale::Fr,
+            ProductText {
+                name: "Sac façon Gucci".to_string(),
+                description: String::new(),
+            },
+        );
+        translated.materials = vec!["Raphia".to_string()];
+        assert_eq!(
+            service.screen(&translated).unwrap(),
+            ["name (fr) mentions the brand \"Gucci\""]
+        );
+
+        // Short brands need an exact match.
+        assert!(
+            service
+                .screen(&product("Nice wooden mask", "", None))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_flagged_listing_waits_for_decision() {
+        let service = ModerationService::new();
+        service
+            .set_blocklist(Blocklist {
+                brands: vec!["Zara".to_string(), " zara ".to_string(), String::new()],
+                patterns: Vec::new(),
+            })
+            .unwrap();
+        assert_eq!(service.blocklist().unwrap().brands, ["Zara"]);
+
+        let listing = product("Robe Zara", "", None);
+        let flags = service.screen(&listing).unwrap();
+        let entry = service
+            .record(ListingAction::Create, listing, flags, Utc::now())
+            .unwrap();
+        assert_eq!(entry.status, ModerationStatus::Pending);
+        assert_eq!(
+            service.list(Some(ModerationStatus::Pending)).unwrap().len(),
+            1
+        );
+
+        assert!(
+            service
+                .decide(
+                    &entry.moderation_id,
+                    ModerationStatus::Rejected,
+                    "admin",
+                    "",
+                    Utc::now()
+                )
+                .is_err()
+        );
+        let decided = service
+            .decide(
+                &entry.moderation_id,
+                ModerationStatus::Rejected,
+                "admin",
+                "Resold Zara dresses",
+                Utc::now(),
+            )
+            .unwrap();
+        assert_eq!(
+            decided.decision_reason.as_deref(),
+            Some("Resold Zara dresses")
+        );
+        assert!(
+            service
+                .decide(
+                    &entry.moderation_id,
+                    ModerationStatus::Approved,
+                    "admin",
+                    "ok",
+                    Utc::now()
+                )
+                .is_err()
+        );
+    }
+}
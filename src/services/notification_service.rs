@@ -0,0 +1,123 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    middleware::locale::localized_error,
+    models::{
+        locale::{Locale, Localize},
+        notification::{Notification, SmsMessage},
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotificationError {
+    #[error("Failed to lock the notification storage")]
+    LockError,
+}
+
+impl IntoResponse for NotificationError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            NotificationError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        localized_error(status, &self)
+    }
+}
+
+impl Localize for NotificationError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, NotificationError::LockError) => {
+                "Impossible d'accéder au stockage des notifications".to_string()
+            }
+        }
+    }
+}
+
+/// Text messages kept for the SMS gateway. Until it drains the outbox, the oldest ones
+/// are dropped past this many so the queue can't grow without bound.
+const MAX_QUEUED_SMS: usize = 1_000;
+
+/// Per-user inbox of notifications, newest last, and the outbox of text messages.
+#[derive(Clone, Default)]
+pub struct NotificationService {
+    inboxes: Arc<Mutex<HashMap<String, Vec<Notification>>>>,
+    sms_outbox: Arc<Mutex<VecDeque<SmsMessage>>>,
+}
+
+impl NotificationService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn notify(
+        &self,
+        user_id: &str,
+        notification: Notification,
+    ) -> Result<(), NotificationError> {
+        let mut inboxes = self
+            .inboxes
+            .lock()
+            .map_err(|_| NotificationError::LockError)?;
+        inboxes
+            .entry(user_id.to_string())
+            .or_default()
+            .push(notification);
+        Ok(())
+    }
+
+    /// The user's notifications, newest first.
+    pub fn for_user(&self, user_id: &str) -> Result<Vec<Notification>, NotificationError> {
+        let inboxes = self
+            .inboxes
+            .lock()
+            .map_err(|_| NotificationError::LockError)?;
+        let mut notifications = inboxes.get(user_id).cloned().unwrap_or_default();
+        notifications.reverse();
+        Ok(notifications)
+    }
+
+    /// Queues a text message to the user's phone number for the SMS gateway.
+    pub fn send_sms(
+        &self,
+        user_id: &str,
+        text: String,
+        now: DateTime<Utc>,
+    ) -> Result<SmsMessage, NotificationError> {
+        let message = SmsMessage {
+            message_id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            text,
+            created_at: now,
+        };
+        let mut outbox = self
+            .sms_outbox
+            .lock()
+            .map_err(|_| NotificationError::LockError)?;
+        if outbox.len() >= MAX_QUEUED_SMS {
+            outbox.pop_front();
+        }
+        outbox.push_back(message.clone());
+        Ok(message)
+    }
+
+    /// Text messages queued so far (at most the latest `MAX_QUEUED_SMS`), oldest first.
+    pub fn sms_outbox(&self) -> Result<Vec<SmsMessage>, NotificationError> {
+        let outbox = self
+            .sms_outbox
+            .lock()
+            .map_err(|_| NotificationError::LockError)?;
+        Ok(outbox.iter().cloned().collect())
+    }
+}
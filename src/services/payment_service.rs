@@ -1,12 +1,34 @@
 // src/services/payment_service.rs
+use std::time::Duration;
+
+use tokio::{net::TcpStream, time::timeout};
+
 use crate::models::order::Order;
 
+/// Represents possible errors from PaymentService.
+#[derive(Debug, thiserror::Error)]
+pub enum PaymentError {
+    #[error("Payment provider unreachable: {0}")]
+    ProviderUnreachable(String),
+}
+
 #[derive(Clone)]
-pub struct PaymentService;
+pub struct PaymentService {
+    /// `host:port` of the mobile money gateway. `None` means payments are simulated in-process.
+    provider_addr: Option<String>,
+}
 
 impl PaymentService {
     pub fn new() -> Self {
-        PaymentService
+        PaymentService {
+            provider_addr: None,
+        }
+    }
+
+    pub fn with_provider(provider_addr: impl Into<String>) -> Self {
+        PaymentService {
+            provider_addr: Some(provider_addr.into()),
+        }
     }
 
     pub fn initiate_payment(&self, order: &Order) {
@@ -16,4 +38,23 @@ impl PaymentService {
         );
         // In real life, call MTN/Orange API here
     }
+
+    /// Checks that the payment provider accepts TCP connections within `deadline`.
+    pub async fn check_reachability(&self, deadline: Duration) -> Result<(), PaymentError> {
+        let Some(addr) = &self.provider_addr else {
+            return Ok(());
+        };
+
+        match timeout(deadline, TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(err)) => Err(PaymentError::ProviderUnreachable(format!("{addr}: {err}"))),
+            Err(_) => Err(PaymentError::ProviderUnreachable(format!("{addr}: timed out"))),
+        }
+    }
+}
+
+impl Default for PaymentService {
+    fn default() -> Self {
+        Self::new()
+    }
 }
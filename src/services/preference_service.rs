@@ -0,0 +1,62 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::models::locale::Locale;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PreferenceError {
+    #[error("Failed to lock the preference storage")]
+    LockError,
+}
+
+impl IntoResponse for PreferenceError {
+    fn into_response(self) -> Response {
+        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
+}
+
+/// Per-user settings; currently only the language, which takes precedence over the
+/// browser's `Accept-Language`.
+#[derive(Clone, Default)]
+pub struct PreferenceService {
+    languages: Arc<Mutex<HashMap<String, Locale>>>,
+}
+
+impl PreferenceService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn language(&self, user_id: &str) -> Result<Option<Locale>, PreferenceError> {
+        Ok(self
+            .languages
+            .lock()
+            .map_err(|_| PreferenceError::LockError)?
+            .get(user_id)
+            .copied())
+    }
+
+    /// Stores the user's language; `None` goes back to following `Accept-Language`.
+    pub fn set_language(
+        &self,
+        user_id: &str,
+        language: Option<Locale>,
+    ) -> Result<(), PreferenceError> {
+        let mut languages = self
+            .languages
+            .lock()
+            .map_err(|_| PreferenceError::LockError)?;
+        match language {
+            Some(language) => languages.insert(user_id.to_string(), language),
+            None => languages.remove(user_id),
+        };
+        Ok(())
+    }
+}
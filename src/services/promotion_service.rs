@@ -0,0 +1,258 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    middleware::locale::localized_error,
+    models::{
+        locale::{Locale, Localize, Translated},
+        promotion::{DiscountKind, Promotion},
+    },
+    services::cart_services::CartError,
+};
+
+pub const MIN_CODE_CHARS: usize = 3;
+pub const MAX_CODE_CHARS: usize = 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PromotionError {
+    #[error("Failed to lock the promotion storage")]
+    LockError,
+    #[error("Unknown coupon code")]
+    UnknownCode,
+    #[error("This coupon is not valid at the moment")]
+    NotActive,
+    #[error("You have already used this coupon as many times as allowed")]
+    UsageLimitReached,
+    #[error("The coupon code {0} is already taken")]
+    CodeTaken(String),
+    #[error("Invalid promotion: {0}")]
+    InvalidPromotion(Translated),
+    #[error(transparent)]
+    Cart(#[from] CartError),
+}
+
+impl IntoResponse for PromotionError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            PromotionError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+            PromotionError::UnknownCode => StatusCode::NOT_FOUND,
+            PromotionError::NotActive | PromotionError::InvalidPromotion(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            PromotionError::UsageLimitReached | PromotionError::CodeTaken(_) => {
+                StatusCode::CONFLICT
+            }
+            PromotionError::Cart(err) => return err.into_response(),
+        };
+        localized_error(status, &self)
+    }
+}
+
+impl Localize for PromotionError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, PromotionError::LockError) => {
+                "Impossible d'accéder au stockage des promotions".to_string()
+            }
+            (Locale::Fr, PromotionError::UnknownCode) => "Code promo inconnu".to_string(),
+            (Locale::Fr, PromotionError::NotActive) => {
+                "Ce code promo n'est pas valable en ce moment".to_string()
+            }
+            (Locale::Fr, PromotionError::UsageLimitReached) => {
+                "Vous avez déjà utilisé ce code promo autant de fois que permis".to_string()
+            }
+            (Locale::Fr, PromotionError::CodeTaken(code)) => {
+                format!("Le code promo {code} est déjà pris")
+            }
+            (Locale::Fr, PromotionError::InvalidPromotion(reason)) => {
+                format!("Promotion invalide : {}", reason.localized(locale))
+            }
+            (Locale::Fr, PromotionError::Cart(err)) => err.localized(locale),
+        }
+    }
+}
+
+#[derive(Default)]
+struct PromotionStore {
+    /// In creation order, which is also the order discounts are applied in.
+    promotions: Vec<Promotion>,
+    /// Orders placed with each promotion, by promotion and user.
+    redemptions: HashMap<(String, String), u32>,
+}
+
+impl PromotionStore {
+    fn used_up(&self, promotion: &Promotion, user_id: &str) -> bool {
+        promotion.per_user_limit.is_some_and(|limit| {
+            let key = (promotion.promotion_id.clone(), user_id.to_string());
+            self.redemptions.get(&key).copied().unwrap_or(0) >= limit
+        })
+    }
+}
+
+/// Coupons and automatic promotions, and how often each user has redeemed them.
+#[derive(Clone, Default)]
+pub struct PromotionService {
+    store: Arc<Mutex<PromotionStore>>,
+}
+
+impl PromotionService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, PromotionStore>, PromotionError> {
+        self.store.lock().map_err(|_| PromotionError::LockError)
+    }
+
+    /// Checks and stores a promotion, upper-casing its code. The caller resolves the
+    /// targeted categories and products.
+    pub fn create(&self, mut promotion: Promotion) -> Result<Promotion, PromotionError> {
+        let invalid =
+            |en: &str, fr: &str| Err(PromotionError::InvalidPromotion(Translated::new(en, fr)));
+        if promotion.label.trim().is_empty() {
+            return invalid("label must not be empty", "label ne doit pas être vide");
+        }
+        if !promotion.value.is_finite() || promotion.value <= 0.0 {
+            return invalid("value must be positive", "value doit être positive");
+        }
+        if promotion.kind == DiscountKind::Percentage && promotion.value > 100.0 {
+            return invalid(
+                "a percentage can't exceed 100",
+                "un pourcentage ne peut pas dépasser 100",
+            );
+        }
+        if promotion.ends_at <= promotion.starts_at {
+            return invalid(
+                "ends_at must be after starts_at",
+                "ends_at doit être postérieur à starts_at",
+            );
+        }
+        if promotion
+            .min_spend
+            .is_some_and(|min| !min.is_finite() || min < 0.0)
+        {
+            return invalid(
+                "min_spend must not be negative",
+                "min_spend ne doit pas être négatif",
+            );
+        }
+        if promotion.per_user_limit == Some(0) {
+            return invalid(
+                "per_user_limit must be at least 1",
+                "per_user_limit doit valoir au moins 1",
+            );
+        }
+        if let Some(code) = &mut promotion.code {
+            let valid = (MIN_CODE_CHARS..=MAX_CODE_CHARS).contains(&code.len())
+                && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if !valid {
+                return Err(PromotionError::InvalidPromotion(Translated::new(
+                    format!(
+                        "code must be {MIN_CODE_CHARS} to {MAX_CODE_CHARS} letters, digits or dashes"
+                    ),
+                    format!(
+                        "code doit compter de {MIN_CODE_CHARS} à {MAX_CODE_CHARS} lettres, chiffres ou tirets"
+                    ),
+                )));
+            }
+            code.make_ascii_uppercase();
+        }
+
+        let mut store = self.lock()?;
+        if let Some(code) = &promotion.code
+            && store
+                .promotions
+                .iter()
+                .any(|p| p.code.as_ref() == Some(code))
+        {
+            return Err(PromotionError::CodeTaken(code.clone()));
+        }
+        store.promotions.push(promotion.clone());
+        Ok(promotion)
+    }
+
+    /// All promotions, in creation order.
+    pub fn list(&self) -> Result<Vec<Promotion>, PromotionError> {
+        Ok(self.lock()?.promotions.clone())
+    }
+
+    /// The promotion behind a coupon code, if the user can apply it now.
+    pub fn coupon(
+        &self,
+        code: &str,
+        user_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Promotion, PromotionError> {
+        let store = self.lock()?;
+        let promotion = store
+            .promotions
+            .iter()
+            .find(|p| {
+                p.code
+                    .as_deref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(code.trim()))
+            })
+            .ok_or(PromotionError::UnknownCode)?;
+        if !promotion.is_active(now) {
+            return Err(PromotionError::NotActive);
+        }
+        if store.used_up(promotion, user_id) {
+            return Err(PromotionError::UsageLimitReached);
+        }
+        Ok(promotion.clone())
+    }
+
+    /// Promotions the user's cart gets now: the automatic ones and the one behind
+    /// `coupon`, leaving out those the user has used up. In the order they apply.
+    pub fn applicable(
+        &self,
+        user_id: &str,
+        coupon: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Promotion>, PromotionError> {
+        let store = self.lock()?;
+        Ok(store
+            .promotions
+            .iter()
+            .filter(|p| p.is_active(now) && !store.used_up(p, user_id))
+            .filter(|p| match (&p.code, coupon) {
+                (None, _) => true,
+                (Some(code), Some(coupon)) => code.eq_ignore_ascii_case(coupon),
+                (Some(_), None) => false,
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Counts an order of the user against the promotions it used. Fails, counting
+    /// nothing, if the user has used any of them up in the meantime.
+    pub fn redeem(&self, user_id: &str, promotion_ids: &[String]) -> Result<(), PromotionError> {
+        let mut store = self.lock()?;
+        for promotion_id in promotion_ids {
+            if let Some(promotion) = store
+                .promotions
+                .iter()
+                .find(|p| p.promotion_id == *promotion_id)
+                && store.used_up(promotion, user_id)
+            {
+                return Err(PromotionError::UsageLimitReached);
+            }
+        }
+        for promotion_id in promotion_ids {
+            *store
+                .redemptions
+                .entry((promotion_id.clone(), user_id.to_string()))
+                .or_default() += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    api::model::{ProductError, ProductImage},
+    middleware::locale::localized_error,
+    models::{
+        locale::{Locale, Localize, Translated},
+        review::{RatingSummary, Review, VendorReply},
+    },
+    services::{checkout_service::CheckoutError, image_service::ImageError},
+};
+
+pub const MAX_REVIEW_PHOTOS: usize = 4;
+pub const MAX_REVIEW_TEXT_CHARS: usize = 2000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReviewError {
+    #[error("Failed to lock the review storage")]
+    LockError,
+    #[error("Review not found")]
+    NotFound,
+    #[error("Only buyers with a delivered order of this product can review it")]
+    NotVerifiedBuyer,
+    #[error("You have already reviewed this product")]
+    AlreadyReviewed,
+    #[error("Only the author of a review can add photos to it")]
+    NotAuthor,
+    #[error("Invalid review: {0}")]
+    InvalidReview(Translated),
+    #[error(transparent)]
+    Product(#[from] ProductError),
+    #[error(transparent)]
+    Order(#[from] CheckoutError),
+    #[error(transparent)]
+    Image(#[from] ImageError),
+}
+
+impl IntoResponse for ReviewError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ReviewError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+            ReviewError::NotFound => StatusCode::NOT_FOUND,
+            ReviewError::NotVerifiedBuyer | ReviewError::NotAuthor => StatusCode::FORBIDDEN,
+            ReviewError::AlreadyReviewed => StatusCode::CONFLICT,
+            ReviewError::InvalidReview(_) => StatusCode::BAD_REQUEST,
+            ReviewError::Product(err) => return err.into_response(),
+            ReviewError::Order(err) => return err.into_response(),
+            ReviewError::Image(err) => return err.into_response(),
+        };
+        localized_error(status, &self)
+    }
+}
+
+impl Localize for ReviewError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (_, ReviewError::Product(err)) => err.localized(locale),
+            (_, ReviewError::Order(err)) => err.localized(locale),
+            (_, ReviewError::Image(err)) => err.localized(locale),
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, ReviewError::LockError) => {
+                "Impossible d'accéder au stockage des avis".to_string()
+            }
+            (Locale::Fr, ReviewError::NotFound) => "Avis introuvable".to_string(),
+            (Locale::Fr, ReviewError::NotVerifiedBuyer) => {
+                "Seuls les acheteurs dont une commande de ce produit a été livrée peuvent le noter"
+                    .to_string()
+            }
+            (Locale::Fr, ReviewError::AlreadyReviewed) => {
+                "Vous avez déjà donné votre avis sur ce produit".to_string()
+            }
+            (Locale::Fr, ReviewError::NotAuthor) => {
+                "Seul l'auteur d'un avis peut y ajouter des photos".to_string()
+            }
+            (Locale::Fr, ReviewError::InvalidReview(detail)) => {
+                format!("Avis invalide : {}", detail.localized(locale))
+            }
+        }
+    }
+}
+
+/// Reviews from verified buyers and the per-product rating aggregates derived from them.
+#[derive(Clone, Default)]
+pub struct ReviewService {
+    reviews: Arc<Mutex<Vec<Review>>>,
+}
+
+impl ReviewService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a review. Checking that `order_id` is a delivered order of the user
+    /// containing the product is up to the caller.
+    pub fn submit(
+        &self,
+        product_id: &str,
+        user_id: &str,
+        order_id: &str,
+        rating: u8,
+        text: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Review, ReviewError> {
+        if !(1..=5).contains(&rating) {
+            return Err(ReviewError::InvalidReview(Translated::new(
+                "the rating must be between 1 and 5 stars",
+                "la note doit être comprise entre 1 et 5 étoiles",
+            )));
+        }
+        let text = text.trim();
+        if text.chars().count() > MAX_REVIEW_TEXT_CHARS {
+            return Err(ReviewError::InvalidReview(Translated::new(
+                format!("reviews are limited to {MAX_REVIEW_TEXT_CHARS} characters"),
+                format!("un avis compte au plus {MAX_REVIEW_TEXT_CHARS} caractères"),
+            )));
+        }
+
+        let mut reviews = self.reviews.lock().map_err(|_| ReviewError::LockError)?;
+        if reviews
+            .iter()
+            .any(|r| r.product_id == product_id && r.user_id == user_id)
+        {
+            return Err(ReviewError::AlreadyReviewed);
+        }
+        let review = Review {
+            review_id: Uuid::new_v4().to_string(),
+            product_id: product_id.to_string(),
+            user_id: user_id.to_string(),
+            order_id: order_id.to_string(),
+            rating,
+            text: text.to_string(),
+            photos: Vec::new(),
+            created_at: now,
+            vendor_reply: None,
+        };
+        reviews.push(review.clone());
+        Ok(review)
+    }
+
+    pub fn get(&self, review_id: &str) -> Result<Review, ReviewError> {
+        let reviews = self.reviews.lock().map_err(|_| ReviewError::LockError)?;
+        reviews
+            .iter()
+            .find(|r| r.review_id == review_id)
+            .cloned()
+            .ok_or(ReviewError::NotFound)
+    }
+
+    /// Reviews of a product, newest first.
+    pub fn for_product(&self, product_id: &str) -> Result<Vec<Review>, ReviewError> {
+        let reviews = self.reviews.lock().map_err(|_| ReviewError::LockError)?;
+        let mut found: Vec<Review> = reviews
+            .iter()
+            .filter(|r| r.product_id == product_id)
+            .cloned()
+            .collect();
+        found.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+        Ok(found)
+    }
+
+    pub fn add_photo(
+        &self,
+        review_id: &str,
+        user_id: &str,
+        photo: ProductImage,
+    ) -> Result<Review, ReviewError> {
+        let mut reviews = self.reviews.lock().map_err(|_| ReviewError::LockError)?;
+        let review = reviews
+            .iter_mut()
+            .find(|r| r.review_id == review_id)
+            .ok_or(ReviewError::NotFound)?;
+        if review.user_id != user_id {
+            return Err(ReviewError::NotAuthor);
+        }
+        if review.photos.len() >= MAX_REVIEW_PHOTOS {
+            return Err(ReviewError::InvalidReview(Translated::new(
+                format!("a review has at most {MAX_REVIEW_PHOTOS} photos"),
+                format!("un avis compte au plus {MAX_REVIEW_PHOTOS} photos"),
+            )));
+        }
+        review.photos.push(photo);
+        Ok(review.clone())
+    }
+
+    /// Sets (or replaces) the vendor's answer to a review.
+    pub fn reply(
+        &self,
+        review_id: &str,
+        vendor_name: &str,
+        text: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Review, ReviewError> {
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_REVIEW_TEXT_CHARS {
+            return Err(ReviewError::InvalidReview(Translated::new(
+                format!("replies must have 1 to {MAX_REVIEW_TEXT_CHARS} characters"),
+                format!("une réponse compte de 1 à {MAX_REVIEW_TEXT_CHARS} caractères"),
+            )));
+        }
+        let mut reviews = self.reviews.lock().map_err(|_| ReviewError::LockError)?;
+        let review = reviews
+            .iter_mut()
+            .find(|r| r.review_id == review_id)
+            .ok_or(ReviewError::NotFound)?;
+        review.vendor_reply = Some(VendorReply {
+            vendor_name: vendor_name.to_string(),
+            text: text.to_string(),
+            replied_at: now,
+        });
+        Ok(review.clone())
+    }
+
+    /// Rating aggregates of every reviewed product.
+    pub fn summaries(&self) -> Result<HashMap<String, RatingSummary>, ReviewError> {
+        let reviews = self.reviews.lock().map_err(|_| ReviewError::LockError)?;
+        let mut totals: HashMap<String, (u32, u32)> = HashMap::new();
+        for review in reviews.iter() {
+            let (sum, count) = totals.entry(review.product_id.clone()).or_default();
+            *sum += u32::from(review.rating);
+            *count += 1;
+        }
+        Ok(totals
+            .into_iter()
+            .map(|(product_id, (sum, count))| {
+                // One decimal, as displayed next to the stars.
+                let average = (f64::from(sum) / f64::from(count) * 10.0).round() / 10.0;
+                let summary = RatingSummary {
+                    average_rating: Some(average),
+                    rating_count: count,
+                };
+                (product_id, summary)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{ReviewError, ReviewService};
+
+    #[test]
+    fn test_ratings_are_aggregated_per_product() {
+        let service = ReviewService::new();
+        let now = Utc::now();
+        service
+            .submit("2", "ama", "o1", 5, "Très beau", now)
+            .unwrap();
+        service.submit("2", "bello", "o2", 4, "", now).unwrap();
+        service
+            .submit("3", "ama", "o1", 2, "Taille petit", now)
+            .unwrap();
+
+        assert!(matches!(
+            service.submit("2", "ama", "o3", 1, "", now),
+            Err(ReviewError::AlreadyReviewed)
+        ));
+        assert!(matches!(
+            service.submit("2", "chi", "o4", 6, "", now),
+            Err(ReviewError::InvalidReview(_))
+        ));
+
+        let summaries = service.summaries().unwrap();
+        assert_eq!(summaries["2"].average_rating, Some(4.5));
+        assert_eq!(summaries["2"].rating_count, 2);
+        assert_eq!(summaries["3"].rating_count, 1);
+    }
+}
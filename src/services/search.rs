@@ -0,0 +1,332 @@
+use std::collections::{HashMap, HashSet};
+
+use rust_stemmers::{Algorithm, Stemmer};
+use strsim::osa_distance;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+use crate::api::model::Product;
+
+/// Relative importance of each indexed field when scoring a match.
+const NAME_WEIGHT: f64 = 3.0;
+const TAGS_WEIGHT: f64 = 2.0;
+const CATEGORY_WEIGHT: f64 = 2.0;
+const VENDOR_WEIGHT: f64 = 1.5;
+const DESCRIPTION_WEIGHT: f64 = 1.0;
+
+/// Query words with no exact term match fall back to prefix matches at this discount,
+/// so partial words like "bamil" still find "Bamileke".
+const PREFIX_MATCH_FACTOR: f64 = 0.5;
+const MIN_PREFIX_LEN: usize = 3;
+
+/// Query words matching neither exactly nor by prefix fall back to terms within a small
+/// edit distance, so misspellings like "bamilleke" still find "Bamileke".
+const FUZZY_MATCH_FACTOR: f64 = 0.4;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "au", "aux", "de", "des", "du", "en", "et", "for", "in", "la", "le", "les",
+    "of", "on", "ou", "or", "pour", "the", "un", "une", "with",
+];
+
+/// Lowercases `text` and strips diacritics, so "Ndolé" and "ndole" compare equal.
+pub fn fold(text: &str) -> String {
+    text.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Edits tolerated for a word of this length: none for short words, where a single typo
+/// already changes the meaning, then one, then two for long words.
+pub fn max_typos(word: &str) -> usize {
+    match word.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Whether `a` and `b` are at most `max` edits apart (insertions, deletions,
+/// substitutions and adjacent transpositions).
+pub fn within_typos(a: &str, b: &str, max: usize) -> bool {
+    a.chars().count().abs_diff(b.chars().count()) <= max && osa_distance(a, b) <= max
+}
+
+/// Splits `text` into folded words, dropping punctuation and stop words.
+pub fn tokenize(text: &str) -> Vec<String> {
+    fold(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(word))
+        .map(String::from)
+        .collect()
+}
+
+/// Turns text into index terms: every word plus its French and English stems.
+pub struct Analyzer {
+    english: Stemmer,
+    french: Stemmer,
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Analyzer {
+            english: Stemmer::create(Algorithm::English),
+            french: Stemmer::create(Algorithm::French),
+        }
+    }
+}
+
+impl Analyzer {
+    /// All terms a single word is indexed under (the word itself and its stems).
+    pub fn terms(&self, word: &str) -> Vec<String> {
+        let mut terms = vec![word.to_string()];
+        for stem in [self.english.stem(word), self.french.stem(word)] {
+            if !terms.iter().any(|t| t == stem.as_ref()) {
+                terms.push(stem.into_owned());
+            }
+        }
+        terms
+    }
+}
+
+/// Inverted index over product name, description (in every language), category, tags and
+/// vendor name.
+#[derive(Default)]
+pub struct SearchIndex {
+    analyzer: Analyzer,
+    /// term -> (product id, field-weighted term frequency)
+    postings: HashMap<String, Vec<(String, f64)>>,
+    /// product id -> terms it was indexed under, for removal
+    doc_terms: HashMap<String, HashSet<String>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn build<'a>(products: impl IntoIterator<Item = &'a Product>) -> Self {
+        let mut index = SearchIndex::new();
+        for product in products {
+            index.insert(product);
+        }
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.doc_terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doc_terms.is_empty()
+    }
+
+    /// Indexes `product`, replacing any previous entry with the same id. Translated names
+    /// and descriptions are indexed too, so the product matches in either language.
+    pub fn insert(&mut self, product: &Product) {
+        self.insert_with_categories(product, &[]);
+    }
+
+    /// Like `insert`, also matching the product by its category's display names (e.g.
+    /// "Épices" and "Spices" for `spices`).
+    pub fn insert_with_categories(&mut self, product: &Product, category_names: &[&str]) {
+        self.remove(&product.id);
+
+        let tags = product.tags.join(" ");
+        let mut fields = vec![
+            (product.name.as_str(), NAME_WEIGHT),
+            (product.description.as_str(), DESCRIPTION_WEIGHT),
+            (product.category.as_str(), CATEGORY_WEIGHT),
+            (tags.as_str(), TAGS_WEIGHT),
+            (product.vendor_name.as_str(), VENDOR_WEIGHT),
+        ];
+        for text in product.translations.values() {
+            fields.push((text.name.as_str(), NAME_WEIGHT));
+            fields.push((text.description.as_str(), DESCRIPTION_WEIGHT));
+        }
+        fields.extend(category_names.iter().map(|name| (*name, CATEGORY_WEIGHT)));
+
+        let mut frequencies: HashMap<String, f64> = HashMap::new();
+        for (text, weight) in fields {
+            for word in tokenize(text) {
+                for term in self.analyzer.terms(&word) {
+                    *frequencies.entry(term).or_default() += weight;
+                }
+            }
+        }
+
+        let mut terms = HashSet::with_capacity(frequencies.len());
+        for (term, frequency) in frequencies {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .push((product.id.clone(), frequency));
+            terms.insert(term);
+        }
+        self.doc_terms.insert(product.id.clone(), terms);
+    }
+
+    pub fn remove(&mut self, product_id: &str) {
+        let Some(terms) = self.doc_terms.remove(product_id) else {
+            return;
+        };
+        for term in terms {
+            if let Some(postings) = self.postings.get_mut(&term) {
+                postings.retain(|(id, _)| id != product_id);
+                if postings.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Ranks products against `query`, best match first. Products matching none of the
+    /// query words are left out; ties are broken by product id for stable paging.
+    pub fn search(&self, query: &str) -> Vec<(String, f64)> {
+        let words = tokenize(query);
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        let doc_count = self.len() as f64;
+        let mut scores: HashMap<&str, (f64, usize)> = HashMap::new();
+
+        for word in &words {
+            let mut word_scores: HashMap<&str, f64> = HashMap::new();
+            for (term, factor) in self.matching_terms(word) {
+                let postings = &self.postings[term];
+                let idf = (1.0 + doc_count / postings.len() as f64).ln();
+                for (id, frequency) in postings {
+                    // BM25-style saturation so repeating a word doesn't dominate the score.
+                    let tf = frequency / (frequency + 1.2);
+                    let score = word_scores.entry(id.as_str()).or_default();
+                    *score = score.max(tf * idf * factor);
+                }
+            }
+            for (id, score) in word_scores {
+                let entry = scores.entry(id).or_default();
+                entry.0 += score;
+                entry.1 += 1;
+            }
+        }
+
+        // Products matching every query word outrank those matching only some of them.
+        let mut ranked: Vec<(String, f64)> = scores
+            .into_iter()
+            .map(|(id, (score, matched))| {
+                let coverage = matched as f64 / words.len() as f64;
+                (id.to_string(), score * coverage * coverage)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    /// Index terms matching a query word: its exact terms/stems, else prefix matches, else
+    /// terms within `max_typos` edits.
+    fn matching_terms(&self, word: &str) -> Vec<(&str, f64)> {
+        let exact: Vec<(&str, f64)> = self
+            .analyzer
+            .terms(word)
+            .iter()
+            .filter_map(|term| self.postings.get_key_value(term.as_str()))
+            .map(|(term, _)| (term.as_str(), 1.0))
+            .collect();
+        if !exact.is_empty() || word.chars().count() < MIN_PREFIX_LEN {
+            return exact;
+        }
+
+        let prefixed: Vec<(&str, f64)> = self
+            .postings
+            .keys()
+            .filter(|term| term.starts_with(word))
+            .map(|term| (term.as_str(), PREFIX_MATCH_FACTOR))
+            .collect();
+        if !prefixed.is_empty() {
+            return prefixed;
+        }
+
+        let max = max_typos(word);
+        self.postings
+            .keys()
+            .filter(|term| within_typos(word, term, max))
+            .map(|term| (term.as_str(), FUZZY_MATCH_FACTOR))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchIndex, fold, tokenize};
+    use crate::api::model::Product;
+
+    fn product(id: &str, name: &str, description: &str, category: &str) -> Product {
+        Product {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            category: category.to_string(),
+            ..Product::default()
+        }
+    }
+
+    #[test]
+    fn test_fold_strips_accents() {
+        assert_eq!(fold("Ndolé Épicé"), "ndole epice");
+        assert_eq!(
+            tokenize("Sac en cuir, fait à la main"),
+            ["sac", "cuir", "fait", "main"]
+        );
+    }
+
+    #[test]
+    fn test_stemming_matches_plural_and_accents() {
+        let index = SearchIndex::build(&[
+            product("1", "Panier tressé", "Paniers en raphia", "Artisanat"),
+            product("2", "Wooden chairs", "Carved by hand", "Furniture"),
+        ]);
+
+        assert_eq!(index.search("paniers tresses")[0].0, "1");
+        assert_eq!(index.search("chair")[0].0, "2");
+        assert_eq!(index.search("carving")[0].0, "2");
+        assert!(index.search("bicycle").is_empty());
+    }
+
+    #[test]
+    fn test_name_match_outranks_description_match() {
+        let index = SearchIndex::build(&[
+            product("1", "Coffee mug", "Holds pepper sauce too", "Kitchen"),
+            product("2", "Penja pepper", "White pepper from Penja", "Spices"),
+        ]);
+
+        let ranked = index.search("pepper");
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].0, "2");
+    }
+
+    #[test]
+    fn test_typo_tolerant_matching() {
+        let index = SearchIndex::build(&[
+            product("1", "Bamileke Stool", "", "Furniture"),
+            product("2", "Ndolé frais", "Feuilles de ndolé lavées", "Légumes"),
+        ]);
+
+        assert_eq!(index.search("bamilleke")[0].0, "1");
+        assert_eq!(index.search("ndoel")[0].0, "2");
+        assert_eq!(index.search("legumse")[0].0, "2");
+        // Short words must match exactly.
+        assert!(index.search("stol").iter().all(|(id, _)| id == "1"));
+        assert!(index.search("fur").iter().all(|(id, _)| id == "1"));
+        assert!(index.search("xyz").is_empty());
+    }
+
+    #[test]
+    fn test_prefix_fallback_and_removal() {
+        let mut index = SearchIndex::build(&[product("1", "Bamileke Stool", "", "Furniture")]);
+
+        assert_eq!(index.search("bamil")[0].0, "1");
+
+        index.remove("1");
+        assert!(index.search("bamileke").is_empty());
+        assert!(index.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    api::model::{Product, ProductError},
+    middleware::locale::localized_error,
+    models::{
+        locale::{Locale, Localize},
+        notification::{Notification, NotificationKind},
+        wishlist::WishlistItem,
+    },
+    services::{cart_services::CartError, notification_service::NotificationError},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WishlistError {
+    #[error("Failed to lock the wishlist storage")]
+    LockError,
+    #[error("Item not found in wishlist")]
+    NotInWishlist,
+    #[error("Item not found in cart")]
+    NotInCart,
+    #[error(transparent)]
+    Product(#[from] ProductError),
+    #[error(transparent)]
+    Cart(#[from] CartError),
+    #[error(transparent)]
+    Notification(#[from] NotificationError),
+}
+
+impl IntoResponse for WishlistError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            WishlistError::LockError => StatusCode::INTERNAL_SERVER_ERROR,
+            WishlistError::NotInWishlist | WishlistError::NotInCart => StatusCode::NOT_FOUND,
+            WishlistError::Product(err) => return err.into_response(),
+            WishlistError::Cart(err) => return err.into_response(),
+            WishlistError::Notification(err) => return err.into_response(),
+        };
+        localized_error(status, &self)
+    }
+}
+
+impl Localize for WishlistError {
+    fn localized(&self, locale: Locale) -> String {
+        match (locale, self) {
+            (_, WishlistError::Product(err)) => err.localized(locale),
+            (_, WishlistError::Cart(err)) => err.localized(locale),
+            (_, WishlistError::Notification(err)) => err.localized(locale),
+            (Locale::En, _) => self.to_string(),
+            (Locale::Fr, WishlistError::LockError) => {
+                "Impossible d'accéder au stockage des listes d'envies".to_string()
+            }
+            (Locale::Fr, WishlistError::NotInWishlist) => {
+                "Article introuvable dans la liste d'envies".to_string()
+            }
+            (Locale::Fr, WishlistError::NotInCart) => {
+                "Article introuvable dans le panier".to_string()
+            }
+        }
+    }
+}
+
+/// Whether the variant can be bought right now; untracked stock counts as available.
+fn in_stock(product: &Product, variant_sku: Option<&str>) -> Result<bool, ProductError> {
+    Ok(product
+        .available_stock(variant_sku)?
+        .is_none_or(|stock| stock > 0))
+}
+
+/// Per-user wishlists, remembering each item's price and availability when last seen so
+/// that price drops and restocks can be reported.
+#[derive(Clone, Default)]
+pub struct WishlistService {
+    wishlists: Arc<Mutex<HashMap<String, Vec<WishlistItem>>>>,
+}
+
+impl WishlistService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Saves a product (variant) to the user's wishlist; saving it again keeps the
+    /// existing entry.
+    pub fn add(
+        &self,
+        user_id: &str,
+        product: &Product,
+        variant_sku: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<WishlistItem, WishlistError> {
+        let item = WishlistItem {
+            product_id: product.id.clone(),
+            variant_sku: variant_sku.map(str::to_string),
+            added_at: now,
+            seen_price: product.unit_price(variant_sku)?,
+            seen_in_stock: in_stock(product, variant_sku)?,
+        };
+        let mut wishlists = self
+            .wishlists
+            .lock()
+            .map_err(|_| WishlistError::LockError)?;
+        let wishlist = wishlists.entry(user_id.to_string()).or_default();
+        if let Some(existing) = wishlist
+            .iter()
+            .find(|i| i.is_same(&product.id, variant_sku))
+        {
+            return Ok(existing.clone());
+        }
+        wishlist.push(item.clone());
+        Ok(item)
+    }
+
+    /// Removes an item and returns it.
+    pub fn remove(
+        &self,
+        user_id: &str,
+        product_id: &str,
+        variant_sku: Option<&str>,
+    ) -> Result<WishlistItem, WishlistError> {
+        let mut wishlists = self
+            .wishlists
+            .lock()
+            .map_err(|_| WishlistError::LockError)?;
+        let wishlist = wishlists
+            .get_mut(user_id)
+            .ok_or(WishlistError::NotInWishlist)?;
+        let position = wishlist
+            .iter()
+            .position(|i| i.is_same(product_id, variant_sku))
+            .ok_or(WishlistError::NotInWishlist)?;
+        Ok(wishlist.remove(position))
+    }
+
+    /// The user's wishlist, in the order items were saved.
+    pub fn get(&self, user_id: &str) -> Result<Vec<WishlistItem>, WishlistError> {
+        let wishlists = self
+            .wishlists
+            .lock()
+            .map_err(|_| WishlistError::LockError)?;
+        Ok(wishlists.get(user_id).cloned().unwrap_or_default())
+    }
+
+    /// Compares a changed product with what wishlist owners last saw and returns the
+    /// notifications due, per user: a lower price, or availability after selling out.
+    pub fn watch(
+        &self,
+        product: &Product,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<(String, Notification)>, WishlistError> {
+        let mut wishlists = self
+            .wishlists
+            .lock()
+            .map_err(|_| WishlistError::LockError)?;
+        let mut due = Vec::new();
+        for (user_id, wishlist) in wishlists.iter_mut() {
+            for item in wishlist.iter_mut().filter(|i| i.product_id == product.id) {
+                let sku = item.variant_sku.as_deref();
+                // The variant may have been withdrawn; there is nothing to report then.
+                let (Ok(price), Ok(available)) = (product.unit_price(sku), in_stock(product, sku))
+                else {
+                    continue;
+                };
+                let notification = |kind, previous_price| Notification {
+                    notification_id: Uuid::new_v4().to_string(),
+                    kind,
+                    product_id: product.id.clone(),
+                    variant_sku: item.variant_sku.clone(),
+                    product_name: product.name.clone(),
+                    previous_price,
+                    price,
+                    created_at: now,
+                };
+                if available && !item.seen_in_stock {
+                    due.push((
+                        user_id.clone(),
+                        notification(NotificationKind::BackInStock, None),
+                    ));
+                } else if available && price < item.seen_price {
+                    due.push((
+                        user_id.clone(),
+                        notification(NotificationKind::PriceDrop, Some(item.seen_price)),
+                    ));
+                }
+                item.seen_price = price;
+                item.seen_in_stock = available;
+            }
+        }
+        Ok(due)
+    }
+}
@@ -0,0 +1,105 @@
+use std::{future::Future, time::Duration};
+
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+/// Coordinates graceful shutdown between the HTTP server and background workers.
+///
+/// Cloning is cheap; every clone observes the same shutdown signal.
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    token: CancellationToken,
+    workers: TaskTracker,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts the shutdown sequence. Readiness probes fail from this point on.
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown has been triggered.
+    pub fn signalled(&self) -> impl Future<Output = ()> + Send + 'static {
+        self.token.clone().cancelled_owned()
+    }
+
+    /// Spawns a background worker. The worker receives a token that is cancelled on
+    /// shutdown and is expected to return promptly once it fires.
+    pub fn spawn_worker<F, Fut>(&self, worker: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.workers.spawn(worker(self.token.child_token()));
+    }
+
+    /// Waits for all background workers to finish. Returns `false` if `deadline` elapsed first.
+    pub async fn stop_workers(&self, deadline: Duration) -> bool {
+        self.trigger();
+        self.workers.close();
+        tokio::time::timeout(deadline, self.workers.wait())
+            .await
+            .is_ok()
+    }
+}
+
+/// Waits for SIGINT (Ctrl+C) or, on Unix, SIGTERM.
+pub async fn wait_for_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Shutdown;
+
+    #[tokio::test]
+    async fn test_workers_stop_on_shutdown() {
+        let shutdown = Shutdown::new();
+        shutdown.spawn_worker(|token| async move {
+            token.cancelled().await;
+        });
+
+        assert!(!shutdown.is_triggered());
+        assert!(shutdown.stop_workers(Duration::from_secs(1)).await);
+        assert!(shutdown.is_triggered());
+    }
+
+    #[tokio::test]
+    async fn test_stop_workers_times_out_on_stuck_worker() {
+        let shutdown = Shutdown::new();
+        shutdown.spawn_worker(|_token| async move {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+
+        assert!(!shutdown.stop_workers(Duration::from_millis(20)).await);
+    }
+}
@@ -1,10 +1,32 @@
-use crate::{api::model::ProductService, services::{cart_services::CartService, checkout_service::CheckoutService, payment_service::PaymentService}};
+use crate::{
+    api::model::ProductService,
+    config::AppConfig,
+    services::{
+        cart_services::CartService, certification_service::CertificationService,
+        checkout_service::CheckoutService,
+        image_service::ImageService, moderation_service::ModerationService,
+        payment_service::PaymentService, preference_service::PreferenceService,
+        promotion_service::PromotionService,
+        notification_service::NotificationService, review_service::ReviewService,
+        wishlist_service::WishlistService,
+    },
+    shutdown::Shutdown,
+};
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct AppState {
     pub checkout_service: CheckoutService,
-    pub cart_service:CartService,
+    pub cart_service: CartService,
     pub product_service: ProductService,
     pub payment_service: PaymentService,
-    
+    pub image_service: ImageService,
+    pub certification_service: CertificationService,
+    pub moderation_service: ModerationService,
+    pub preference_service: PreferenceService,
+    pub review_service: ReviewService,
+    pub wishlist_service: WishlistService,
+    pub notification_service: NotificationService,
+    pub promotion_service: PromotionService,
+    pub config: AppConfig,
+    pub shutdown: Shutdown,
 }